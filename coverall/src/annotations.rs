@@ -0,0 +1,140 @@
+use std::collections::HashSet;
+use std::env;
+use std::fmt::Write;
+use std::path::Path;
+use std::process;
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::report::CoverageResults;
+use crate::utils::CoverageStatus;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum AnnotationStyle {
+    // GitHub Actions, which also takes an end line and a title
+    Github,
+    // Only the file and line, which other runners taking workflow commands understand
+    Generic,
+}
+
+/// The files changed since `base`, relative to the repo, going by
+/// `git diff <base>...HEAD`.
+pub fn changed_files(repo: &str, base: &str) -> Result<HashSet<String>> {
+    let output = process::Command::new("git")
+        .args(["diff", "--name-only", "--relative"])
+        .arg(format!("{}...HEAD", base))
+        .current_dir(repo)
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git diff against {} failed: {}",
+            base,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(String::from)
+        .collect())
+}
+
+// Workflow commands end their message at a newline, and their properties at a
+// comma or colon
+fn escape_data(text: &str) -> String {
+    text.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(text: &str) -> String {
+    escape_data(text).replace(':', "%3A").replace(',', "%2C")
+}
+
+/// Renders a `::warning` workflow command per uncovered method, limited to the
+/// `changed` files when given. Files are named relative to the working directory
+/// when the repo is inside it, as runners expect paths from the workspace root,
+/// and relative to the repo otherwise.
+pub fn annotations_report(
+    results: &CoverageResults,
+    style: AnnotationStyle,
+    changed: Option<&HashSet<String>>,
+) -> String {
+    let workspace = env::current_dir().and_then(|dir| dir.canonicalize()).ok();
+    let file_name = |path: &str| {
+        let full = results.root.join(path);
+        workspace
+            .as_deref()
+            .and_then(|dir| full.strip_prefix(dir).ok())
+            .unwrap_or(Path::new(path))
+            .to_string_lossy()
+            .replace('\\', "/")
+    };
+
+    let mut annotations = String::new();
+    for method in results
+        .methods
+        .iter()
+        .filter(|m| m.status == CoverageStatus::Uncovered)
+        .filter(|m| changed.is_none_or(|files| files.contains(&m.path)))
+    {
+        let file = escape_property(&file_name(&method.path));
+        let message = escape_data(&format!("{} isn't called by any test.", method.id));
+        match style {
+            AnnotationStyle::Github => writeln!(
+                annotations,
+                "::warning file={},line={},endLine={},title={}::{}",
+                file,
+                method.method.start_line,
+                method.method.end_line(),
+                escape_property(&format!("Untested method {}", method.id)),
+                message
+            ),
+            AnnotationStyle::Generic => writeln!(
+                annotations,
+                "::warning file={},line={}::{}",
+                file, method.method.start_line, message
+            ),
+        }
+        .unwrap();
+    }
+    annotations
+}
+
+#[test]
+fn test_annotations_report() {
+    use crate::codeanalysis::create_lang_settings;
+    use crate::utils::{CallTarget, Lang, Method, TestedMethods};
+
+    let method = |file: &str, class: &str, name: &str, start_line: usize| Method {
+        class_name: class.to_string(),
+        method_name: name.to_string(),
+        body: vec![String::new(); 3],
+        start_line,
+        is_test: false,
+        file_path: format!("/nonexistent/repo/{}", file),
+    };
+    let methods = vec![
+        method("lib/cart.rb", "Cart", "add", 2),
+        method("lib/cart.rb", "Cart", "total", 6),
+        method("lib/slug,util.rb", "Slug", "make", 1),
+    ];
+    let mut tests = TestedMethods::default();
+    tests.record(CallTarget::exact(&methods[0]), "CartTest.test_add");
+
+    let lang_settings = create_lang_settings(&Lang::Ruby);
+    let results = CoverageResults::new("/nonexistent/repo", &methods, &tests, &lang_settings);
+
+    assert_eq!(
+        annotations_report(&results, AnnotationStyle::Github, None),
+        "::warning file=lib/cart.rb,line=6,endLine=8,title=Untested method Cart.total::Cart.total isn't called by any test.
+::warning file=lib/slug%2Cutil.rb,line=1,endLine=3,title=Untested method Slug.make::Slug.make isn't called by any test.
+"
+    );
+
+    let changed = HashSet::from([String::from("lib/cart.rb")]);
+    assert_eq!(
+        annotations_report(&results, AnnotationStyle::Generic, Some(&changed)),
+        "::warning file=lib/cart.rb,line=6::Cart.total isn't called by any test.\n"
+    );
+}
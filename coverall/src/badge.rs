@@ -0,0 +1,166 @@
+use anyhow::{anyhow, Result};
+
+use crate::cobertura::xml_escape;
+
+pub const DEFAULT_THRESHOLDS: &str =
+    "0=red,50=orange,65=yellow,80=yellowgreen,90=green,100=brightgreen";
+
+/// How the badge looks: its label and the colors coverage gets from each threshold.
+#[derive(PartialEq, Debug)]
+pub struct Badge {
+    pub label: String,
+    // Lowest percentage for each color, in ascending order
+    pub thresholds: Vec<(f64, String)>,
+}
+
+impl Default for Badge {
+    fn default() -> Badge {
+        Badge {
+            label: String::from("coverage"),
+            thresholds: parse_thresholds(DEFAULT_THRESHOLDS).unwrap(),
+        }
+    }
+}
+
+// The colors shields.io has names for
+fn named_color(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "brightgreen" => "#4c1",
+        "green" => "#97ca00",
+        "yellowgreen" => "#a4a61d",
+        "yellow" => "#dfb317",
+        "orange" => "#fe7d37",
+        "red" => "#e05d44",
+        "blue" => "#007ec6",
+        "lightgrey" | "lightgray" => "#9f9f9f",
+        _ => return None,
+    })
+}
+
+/// Parses thresholds such as `0=red,60=yellow,80=green`, where coverage takes the
+/// color of the highest threshold it reaches. Colors are shields.io's names or 3 or 6
+/// digit hex codes like `#4c1` or `#44cc11`.
+pub fn parse_thresholds(value: &str) -> Result<Vec<(f64, String)>> {
+    let mut thresholds = Vec::new();
+    for pair in value.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (percent, color) = pair
+            .split_once('=')
+            .ok_or_else(|| anyhow!("Expected <percent>=<color> rather than {}", pair))?;
+        let percent: f64 = percent
+            .trim()
+            .trim_end_matches('%')
+            .parse()
+            .map_err(|_| anyhow!("{} isn't a percentage", percent))?;
+        let color = color.trim();
+        let is_hex = matches!(color.len(), 4 | 7)
+            && color.starts_with('#')
+            && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+        let color = match named_color(color) {
+            Some(hex) => hex.to_string(),
+            None if is_hex => color.to_string(),
+            None => return Err(anyhow!("Unknown color {}", color)),
+        };
+        thresholds.push((percent, color));
+    }
+    if thresholds.is_empty() {
+        return Err(anyhow!("No thresholds given"));
+    }
+    thresholds.sort_by(|a, b| a.0.total_cmp(&b.0));
+    Ok(thresholds)
+}
+
+// Widths of Verdana at 11px, which badges are set in, close enough to size the text
+fn text_width(text: &str) -> f64 {
+    text.chars()
+        .map(|c| match c {
+            'i' | 'j' | 'l' | '\'' | '|' | '!' | '.' | ',' | ':' | ';' => 3.7,
+            ' ' | 'f' | 'r' | 't' | '(' | ')' | '[' | ']' | 'I' => 4.6,
+            'm' | 'w' | 'M' | 'W' | '%' => 10.6,
+            'A'..='Z' => 7.6,
+            _ => 7.0,
+        })
+        .sum()
+}
+
+// Rounded down, so a badge only shows 100% when everything is covered
+fn format_percent(percent: f64) -> String {
+    let tenths = (percent * 10.0).floor() / 10.0;
+    format!("{:.1}", tenths).trim_end_matches(".0").to_string() + "%"
+}
+
+/// Renders a shields.io style flat badge showing the coverage percentage.
+pub fn badge_svg(percent: f64, badge: &Badge) -> String {
+    let color = badge
+        .thresholds
+        .iter()
+        .rev()
+        .find(|(threshold, _)| percent >= *threshold)
+        .or(badge.thresholds.first())
+        .map(|(_, color)| color.as_str())
+        .unwrap_or("#9f9f9f");
+    let value = format_percent(percent);
+    let label_width = (text_width(&badge.label) + 10.0).round();
+    let value_width = (text_width(&value) + 10.0).round();
+    let width = label_width + value_width;
+    let label = xml_escape(&badge.label);
+
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{label}: {value}">
+  <title>{label}: {value}</title>
+  <linearGradient id="s" x2="0" y2="100%">
+    <stop offset="0" stop-color="#bbb" stop-opacity=".1"/>
+    <stop offset="1" stop-opacity=".1"/>
+  </linearGradient>
+  <clipPath id="r">
+    <rect width="{width}" height="20" rx="3" fill="#fff"/>
+  </clipPath>
+  <g clip-path="url(#r)">
+    <rect width="{label_width}" height="20" fill="#555"/>
+    <rect x="{label_width}" width="{value_width}" height="20" fill="{color}"/>
+    <rect width="{width}" height="20" fill="url(#s)"/>
+  </g>
+  <g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" text-rendering="geometricPrecision" font-size="11">
+    <text x="{label_x}" y="15" fill="#010101" fill-opacity=".3">{label}</text>
+    <text x="{label_x}" y="14">{label}</text>
+    <text x="{value_x}" y="15" fill="#010101" fill-opacity=".3">{value}</text>
+    <text x="{value_x}" y="14">{value}</text>
+  </g>
+</svg>
+"##,
+        label_x = label_width / 2.0,
+        value_x = label_width + value_width / 2.0,
+    )
+}
+
+#[test]
+fn test_badge_svg() {
+    let badge = Badge::default();
+    let svg = badge_svg(79.96, &badge);
+    let document = roxmltree::Document::parse(&svg).unwrap();
+    let texts: Vec<&str> = document
+        .descendants()
+        .filter(|n| n.has_tag_name("text"))
+        .filter_map(|n| n.text())
+        .collect();
+    assert_eq!(texts, vec!["coverage", "coverage", "79.9%", "79.9%"]);
+    // Below the 80% threshold, so still yellow
+    assert!(svg.contains("fill=\"#dfb317\""));
+    assert!(badge_svg(100.0, &badge).contains(">100%<"));
+
+    let custom = Badge {
+        label: String::from("tests & docs"),
+        thresholds: parse_thresholds("75=#0a0, 0=red").unwrap(),
+    };
+    assert_eq!(
+        custom.thresholds,
+        vec![(0.0, String::from("#e05d44")), (75.0, String::from("#0a0"))]
+    );
+    let svg = badge_svg(75.0, &custom);
+    assert!(svg.contains("fill=\"#0a0\""));
+    assert!(svg.contains("aria-label=\"tests &amp; docs: 75%\""));
+
+    assert!(parse_thresholds("50=purple").is_err());
+    assert!(parse_thresholds("half=red").is_err());
+    assert!(parse_thresholds("0=#1").is_err());
+    assert!(parse_thresholds("0=#12345").is_err());
+}
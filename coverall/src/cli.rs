@@ -20,19 +20,112 @@ fn build_command() -> Command {
         .arg(
             Arg::new("repo")
                 .help("Path to the repo you are wanting to check the test coverage of.")
-                .required(true)
                 .long("repo")
                 .short('r')
                 .value_name("PATH")
+                .global(true)
                 .action(ArgAction::Set),
         )
         .arg(
             Arg::new("language")
-                .help("language repo is written in")
-                .required(true)
+                .help("language repo is written in (csharp, js, ts, go, java, kotlin, cpp, ruby, php, swift, rust). Detected from the repo's files when left out.")
                 .long("language")
                 .short('l')
                 .value_name("language")
+                .global(true)
                 .action(ArgAction::Set),
         )
+        .arg(
+            Arg::new("config-dir")
+                .help("Directory holding language definitions under languages/. Defaults to $COVERME_CONFIG_DIR, or coverme/ in the user's config directory.")
+                .long("config-dir")
+                .value_name("PATH")
+                .global(true)
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("include-generated")
+                .help("Include generated sources such as designer files and obj/ output.")
+                .long("include-generated")
+                .global(true)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("format")
+                .help("Report format: text, cobertura for Cobertura XML, lcov for an LCOV tracefile, html for a static site written to --output, markdown for a summary to post on merge requests, sarif for code scanning tools, or github and annotations for CI workflow annotations. Defaults to text.")
+                .long("format")
+                .short('f')
+                .value_name("format")
+                .value_parser(["text", "cobertura", "lcov", "html", "markdown", "sarif", "github", "annotations"])
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("output")
+                .help("File to write the report to instead of stdout, or the directory to write an html report to.")
+                .long("output")
+                .short('o')
+                .value_name("PATH")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("baseline")
+                .help("Cobertura report of an earlier run for the markdown summary to show changes in coverage against, or for sarif to only report methods that weren't uncovered in it.")
+                .long("baseline")
+                .value_name("PATH")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("sarif-level")
+                .help("Level of a SARIF rule as <rule>=<level>, e.g. untested-method=error. Levels are none, note, warning and error; none turns the rule off. Can be repeated.")
+                .long("sarif-level")
+                .value_name("RULE=LEVEL")
+                .action(ArgAction::Append),
+        )
+        .arg(
+            Arg::new("changed-since")
+                .help("Git ref to compare with, so github and annotations output only annotates files changed since it.")
+                .long("changed-since")
+                .value_name("REF")
+                .action(ArgAction::Set),
+        )
+        .arg(
+            Arg::new("lcov-lines")
+                .help("Add line records spanning each method's body to LCOV output.")
+                .long("lcov-lines")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("verbose")
+                .help("List the tests covering each method.")
+                .long("verbose")
+                .short('v')
+                .action(ArgAction::SetTrue),
+        )
+        .subcommand(
+            Command::new("badge")
+                .about("Writes an SVG badge showing the repo's method coverage. --repo defaults to the current directory.")
+                .arg(
+                    Arg::new("output")
+                        .help("File to write the badge to.")
+                        .required(true)
+                        .long("output")
+                        .short('o')
+                        .value_name("PATH")
+                        .action(ArgAction::Set),
+                )
+                .arg(
+                    Arg::new("label")
+                        .help("Text on the left of the badge. Defaults to coverage.")
+                        .long("label")
+                        .value_name("TEXT")
+                        .action(ArgAction::Set),
+                )
+                .arg(
+                    Arg::new("thresholds")
+                        .help("Colors by coverage as <percent>=<color> pairs, e.g. 0=red,60=yellow,80=green. Colors are shields.io names or 3 or 6 digit hex codes.")
+                        .long("thresholds")
+                        .value_name("LIST")
+                        .action(ArgAction::Set),
+                ),
+        )
 }
@@ -0,0 +1,230 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::report::{CoverageResults, LineHits, MethodCoverage};
+
+fn write_lines(lines: &LineHits, xml: &mut String, indent: &str) {
+    writeln!(xml, "{}<lines>", indent).unwrap();
+    for (line, hits) in lines.iter() {
+        writeln!(
+            xml,
+            "{}  <line number=\"{}\" hits=\"{}\"/>",
+            indent, line, hits
+        )
+        .unwrap();
+    }
+    writeln!(xml, "{}</lines>", indent).unwrap();
+}
+
+fn line_rate(covered: usize, valid: usize) -> String {
+    if valid == 0 {
+        return String::from("1");
+    }
+    let rate = (covered as f64 / valid as f64 * 10000.0).round() / 10000.0;
+    rate.to_string()
+}
+
+pub(crate) fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Renders the results as Cobertura XML. Packages are the directories of the source
+/// files and classes the methods' classes within each file, with a file's free
+/// functions reported under the file's name. Each line of a method's span gets the
+/// number of tests covering the method as its hits, so line rates follow the share
+/// of code in covered methods.
+pub fn cobertura_report(results: &CoverageResults) -> String {
+    // Package, then file and class
+    let mut packages: BTreeMap<String, BTreeMap<(&str, String), Vec<&MethodCoverage>>> =
+        BTreeMap::new();
+    for method in &results.methods {
+        let package = Path::new(&method.path)
+            .parent()
+            .map(|dir| dir.to_string_lossy().replace('/', "."))
+            .filter(|dir| !dir.is_empty())
+            .unwrap_or_else(|| String::from("."));
+        let class = if method.method.class_name.is_empty() {
+            method
+                .path
+                .rsplit('/')
+                .next()
+                .unwrap_or_default()
+                .to_string()
+        } else {
+            method.method.class_name.clone()
+        };
+        packages
+            .entry(package)
+            .or_default()
+            .entry((&method.path, class))
+            .or_default()
+            .push(method);
+    }
+
+    let mut body = String::new();
+    let (mut covered, mut valid) = (0, 0);
+    for (package, classes) in &packages {
+        let mut package_xml = String::new();
+        let (mut package_covered, mut package_valid) = (0, 0);
+        for ((path, class), methods) in classes {
+            let mut class_lines = LineHits::default();
+            let mut methods_xml = String::new();
+            for method in methods {
+                let mut lines = LineHits::default();
+                lines.add(method);
+                class_lines.add(method);
+                writeln!(
+                    methods_xml,
+                    "            <method name=\"{}\" signature=\"\" line-rate=\"{}\" branch-rate=\"0\" complexity=\"0\">",
+                    xml_escape(&method.method.method_name),
+                    line_rate(lines.covered(), lines.valid())
+                )
+                .unwrap();
+                write_lines(&lines, &mut methods_xml, "              ");
+                writeln!(methods_xml, "            </method>").unwrap();
+            }
+
+            writeln!(
+                package_xml,
+                "        <class name=\"{}\" filename=\"{}\" line-rate=\"{}\" branch-rate=\"0\" complexity=\"0\">",
+                xml_escape(class),
+                xml_escape(path),
+                line_rate(class_lines.covered(), class_lines.valid())
+            )
+            .unwrap();
+            writeln!(package_xml, "          <methods>").unwrap();
+            package_xml.push_str(&methods_xml);
+            writeln!(package_xml, "          </methods>").unwrap();
+            write_lines(&class_lines, &mut package_xml, "          ");
+            writeln!(package_xml, "        </class>").unwrap();
+            package_covered += class_lines.covered();
+            package_valid += class_lines.valid();
+        }
+
+        writeln!(
+            body,
+            "    <package name=\"{}\" line-rate=\"{}\" branch-rate=\"0\" complexity=\"0\">",
+            xml_escape(package),
+            line_rate(package_covered, package_valid)
+        )
+        .unwrap();
+        writeln!(body, "      <classes>").unwrap();
+        body.push_str(&package_xml);
+        writeln!(body, "      </classes>").unwrap();
+        writeln!(body, "    </package>").unwrap();
+        covered += package_covered;
+        valid += package_valid;
+    }
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+    let mut xml = String::new();
+    writeln!(xml, "<?xml version=\"1.0\" ?>").unwrap();
+    writeln!(
+        xml,
+        "<!DOCTYPE coverage SYSTEM \"http://cobertura.sourceforge.net/xml/coverage-04.dtd\">"
+    )
+    .unwrap();
+    writeln!(
+        xml,
+        "<coverage line-rate=\"{}\" branch-rate=\"0\" lines-covered=\"{}\" lines-valid=\"{}\" branches-covered=\"0\" branches-valid=\"0\" complexity=\"0\" version=\"coverme {}\" timestamp=\"{}\">",
+        line_rate(covered, valid),
+        covered,
+        valid,
+        env!("CARGO_PKG_VERSION"),
+        timestamp
+    )
+    .unwrap();
+    writeln!(xml, "  <sources>").unwrap();
+    writeln!(
+        xml,
+        "    <source>{}</source>",
+        xml_escape(&results.root.to_string_lossy())
+    )
+    .unwrap();
+    writeln!(xml, "  </sources>").unwrap();
+    writeln!(xml, "  <packages>").unwrap();
+    xml.push_str(&body);
+    writeln!(xml, "  </packages>").unwrap();
+    writeln!(xml, "</coverage>").unwrap();
+    xml
+}
+
+#[test]
+fn test_cobertura_report() {
+    use crate::codeanalysis::create_lang_settings;
+    use crate::utils::{CallTarget, Lang, Method, TestedMethods};
+
+    let method = |file: &str, class: &str, name: &str, start_line: usize, lines: usize| Method {
+        class_name: class.to_string(),
+        method_name: name.to_string(),
+        body: vec![String::new(); lines],
+        start_line,
+        is_test: false,
+        file_path: format!("/repo/{}", file),
+    };
+    let methods = vec![
+        method("src/shop/cart.py", "Cart", "add", 2, 2),
+        method("src/shop/cart.py", "Cart", "total", 5, 3),
+        method("src/shop/cart.py", "", "slugify", 10, 2),
+        method("main.py", "", "main", 1, 4),
+    ];
+    let mut tests = TestedMethods::default();
+    tests.record(CallTarget::exact(&methods[0]), "test_add");
+    tests.record(CallTarget::exact(&methods[0]), "test_total");
+    tests.record(CallTarget::exact(&methods[2]), "test_slugify");
+
+    let lang_settings = create_lang_settings(&Lang::Rust);
+    let results = CoverageResults::new("/repo", &methods, &tests, &lang_settings);
+    let xml = cobertura_report(&results);
+    let document = roxmltree::Document::parse_with_options(
+        &xml,
+        roxmltree::ParsingOptions {
+            allow_dtd: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let coverage = document.root_element();
+    assert_eq!(coverage.attribute("lines-covered"), Some("4"));
+    assert_eq!(coverage.attribute("lines-valid"), Some("11"));
+    assert_eq!(coverage.attribute("line-rate"), Some("0.3636"));
+
+    let packages: Vec<&str> = document
+        .descendants()
+        .filter(|n| n.has_tag_name("package"))
+        .filter_map(|n| n.attribute("name"))
+        .collect();
+    assert_eq!(packages, vec![".", "src.shop"]);
+
+    let cart = document
+        .descendants()
+        .find(|n| n.has_tag_name("class") && n.attribute("name") == Some("Cart"))
+        .unwrap();
+    assert_eq!(cart.attribute("filename"), Some("src/shop/cart.py"));
+    assert_eq!(cart.attribute("line-rate"), Some("0.4"));
+    let add_hits: Vec<&str> = cart
+        .descendants()
+        .find(|n| n.has_tag_name("method") && n.attribute("name") == Some("add"))
+        .unwrap()
+        .descendants()
+        .filter(|n| n.has_tag_name("line"))
+        .filter_map(|n| n.attribute("hits"))
+        .collect();
+    assert_eq!(add_hits, vec!["2", "2"]);
+
+    let free_functions = document
+        .descendants()
+        .find(|n| n.has_tag_name("class") && n.attribute("name") == Some("cart.py"))
+        .unwrap();
+    assert_eq!(free_functions.attribute("line-rate"), Some("1"));
+}
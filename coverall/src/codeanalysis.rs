@@ -4,16 +4,33 @@ use std::io::{BufRead, BufReader};
 use std::path::Path;
 use walkdir::WalkDir;
 
-use crate::coverage;
+use crate::coverage::{self, ReportLayout};
+use crate::cpp::{
+    extract_cpp_tested_methods, is_cpp_test_file, traverse_cpp_nodes, CppDeclarations,
+};
 use crate::csharp::{
-    extract_csharp_assert_targets, extract_csharp_method_calls, traverse_c_sharp_nodes,
+    build_csharp_type_index, extract_csharp_tested_methods, is_generated_csharp_file,
+    traverse_c_sharp_nodes,
+};
+use crate::dotnet::{discover_projects, extract_project_tested_methods};
+use crate::go::{extract_go_tested_methods, is_go_test_file, traverse_go_nodes};
+use crate::java::{extract_java_tested_methods, is_java_test_source, traverse_java_nodes};
+use crate::js::{extract_js_tested_methods, traverse_js_nodes, JsTestFiles};
+use crate::kotlin::{extract_kotlin_tested_methods, is_kotlin_test_source, traverse_kotlin_nodes};
+use crate::php::{extract_php_tested_methods, is_php_test_file, traverse_php_nodes};
+use crate::querylang::{
+    extract_query_tested_methods, load_query_languages, traverse_query_nodes, QueryLanguage,
+};
+use crate::ruby::{extract_ruby_tested_methods, is_ruby_test_file, traverse_ruby_nodes};
+use crate::swift::{
+    extract_swift_tested_methods, is_swift_test_file, merge_swift_extensions, traverse_swift_nodes,
+};
+use crate::utils::{
+    self, extract_body, get_parser, should_skip_dir, CallTarget, Lang, LangSettings, Method,
+    TestedMethods,
 };
-use crate::js::extract_js_tested_methods;
-use crate::utils::{self, extract_body, get_parser, should_skip_dir, Lang, LangSettings, Method};
-
-pub fn start_analysis(repo: utils::Command) {
-    let lang_settings = create_lang_settings(&repo.lang);
 
+pub fn start_analysis(mut repo: utils::Command) {
     //eventually I want to return a custom error but panicing will work for now
     if !path_exists(&repo.repo) {
         panic!(
@@ -22,45 +39,242 @@ pub fn start_analysis(repo: utils::Command) {
         );
     }
 
-    let logic_methods = extract_logic_methods(&repo.repo, &lang_settings);
-    let mut _tested_methods = Vec::new();
-    if lang_settings.ext == "js" {
-        _tested_methods = extract_js_tested_methods(&repo.repo, &lang_settings, &logic_methods);
-    } else {
-        _tested_methods = extract_tested_methods(&logic_methods, &lang_settings);
+    if repo.lang == Lang::Undefined {
+        let query_languages = match &repo.config_dir {
+            Some(dir) => load_query_languages(dir).unwrap_or_else(|e| panic!("{:#}", e)),
+            None => Vec::new(),
+        };
+        repo.lang = detect_language(&repo.repo, query_languages);
+        if repo.lang == Lang::Undefined {
+            panic!(
+                "Could not detect the language of {}. Please pass it with --language.",
+                &repo.repo
+            );
+        }
+        // Only the grammar of the language picked is loaded
+        if let Lang::Query(language) = &repo.lang {
+            if let Err(e) = language.validate() {
+                panic!("{:#}", e);
+            }
+        }
+    }
+    let lang_settings = create_lang_settings(&repo.lang);
+
+    let mut logic_methods = extract_logic_methods(&repo, &lang_settings);
+
+    if let Lang::Query(language) = &repo.lang {
+        let tested_methods = extract_query_tested_methods(&repo.repo, language, &logic_methods);
+        coverage::generate_coverage_report(
+            &repo,
+            ReportLayout::Methods,
+            logic_methods,
+            tested_methods,
+            &lang_settings,
+        );
+        return;
+    }
+
+    if lang_settings.ext == "cs" {
+        let index = build_csharp_type_index(&repo);
+
+        // When the repo has .NET projects, only count a test project's calls against
+        // the projects it references and report per production project
+        let projects = discover_projects(&repo.repo);
+        if !projects.is_empty() {
+            let tested_methods = extract_project_tested_methods(&projects, &logic_methods, &index);
+            coverage::generate_coverage_report(
+                &repo,
+                ReportLayout::Projects(&projects),
+                logic_methods,
+                tested_methods,
+                &lang_settings,
+            );
+            return;
+        }
+
+        let test_methods: Vec<&Method> = logic_methods.iter().filter(|m| m.is_test).collect();
+        let candidates: Vec<&Method> = logic_methods.iter().filter(|m| !m.is_test).collect();
+        let tested_methods = extract_csharp_tested_methods(&test_methods, &candidates, &index);
+        coverage::generate_coverage_report(
+            &repo,
+            ReportLayout::Methods,
+            logic_methods,
+            tested_methods,
+            &lang_settings,
+        );
+        return;
+    }
+
+    if lang_settings.ext == "kt" {
+        let tested_methods = extract_kotlin_tested_methods(&repo.repo, &logic_methods);
+        coverage::generate_coverage_report(
+            &repo,
+            ReportLayout::Packages,
+            logic_methods,
+            tested_methods,
+            &lang_settings,
+        );
+        return;
     }
 
-    coverage::generate_method_level_coverage_report(logic_methods, _tested_methods, &lang_settings);
+    if lang_settings.ext == "swift" {
+        merge_swift_extensions(&mut logic_methods);
+    }
+
+    let tested_methods = if is_js_family(&lang_settings) {
+        extract_js_tested_methods(&repo.repo, &lang_settings, &logic_methods)
+    } else if lang_settings.ext == "go" {
+        extract_go_tested_methods(&repo.repo, &logic_methods)
+    } else if lang_settings.ext == "java" {
+        extract_java_tested_methods(&repo.repo, &logic_methods)
+    } else if lang_settings.ext == "cpp" {
+        extract_cpp_tested_methods(&repo.repo, &lang_settings, &logic_methods)
+    } else if lang_settings.ext == "rb" {
+        extract_ruby_tested_methods(&repo.repo, &logic_methods)
+    } else if lang_settings.ext == "php" {
+        extract_php_tested_methods(&repo.repo, &logic_methods)
+    } else if lang_settings.ext == "swift" {
+        extract_swift_tested_methods(&repo.repo, &logic_methods)
+    } else {
+        extract_tested_methods(&logic_methods)
+    };
+
+    coverage::generate_coverage_report(
+        &repo,
+        ReportLayout::Methods,
+        logic_methods,
+        tested_methods,
+        &lang_settings,
+    );
 }
 
-fn extract_logic_methods(repo: &String, lang_settings: &LangSettings) -> Vec<Method> {
+fn extract_logic_methods(repo: &utils::Command, lang_settings: &LangSettings) -> Vec<Method> {
     let mut methods = Vec::new();
-    let mut parser = get_parser(&lang_settings.ext);
+    let js_test_files = JsTestFiles::load(&repo.repo, &lang_settings.extensions);
+    let cpp_declarations = if lang_settings.ext == "cpp" {
+        CppDeclarations::load(&repo.repo, lang_settings)
+    } else {
+        CppDeclarations::default()
+    };
+    let queries = match &repo.lang {
+        Lang::Query(language) => language.queries().ok(),
+        _ => None,
+    };
 
-    for entry in WalkDir::new(repo)
+    for entry in WalkDir::new(&repo.repo)
         .into_iter()
         .filter_entry(|e| !should_skip_dir(e))
         .filter_map(Result::ok)
     {
-        if entry
-            .path()
-            .extension()
-            .map_or(false, |ext| *ext == *lang_settings.ext)
-        {
+        if lang_settings.is_source_file(entry.path()) {
             if let Ok(file) = File::open(entry.path()) {
                 let reader = BufReader::new(file);
                 let source_code = read_to_string_buffered(reader);
 
+                // Languages defined in the config directory bring their own grammar
+                if let Some(queries) = &queries {
+                    if let Some(tree) = queries.parser().parse(&source_code, None) {
+                        traverse_query_nodes(
+                            tree.root_node(),
+                            &source_code,
+                            &entry.path().to_string_lossy(),
+                            queries.is_test_file(&repo.repo, entry.path()),
+                            queries,
+                            &mut methods,
+                        );
+                    }
+                    continue;
+                }
+
+                let mut parser = get_parser(&file_extension(entry.path()));
+
                 if let Some(tree) = parser.parse(&source_code, None) {
                     let root_node = tree.root_node();
                     let mut cursor = root_node.walk();
 
+                    let file_path = entry.path().to_string_lossy();
+
                     if lang_settings.ext == "cs" {
+                        if !repo.include_generated
+                            && is_generated_csharp_file(
+                                Path::new(&repo.repo),
+                                entry.path(),
+                                &source_code,
+                            )
+                        {
+                            continue;
+                        }
                         traverse_c_sharp_nodes(
                             root_node,
-                            &mut cursor,
                             &source_code,
+                            &file_path,
                             lang_settings,
+                            repo.include_generated,
+                            &mut methods,
+                        );
+                    } else if lang_settings.ext == "go" {
+                        traverse_go_nodes(
+                            root_node,
+                            &source_code,
+                            &file_path,
+                            is_go_test_file(entry.path()),
+                            &mut methods,
+                        );
+                    } else if lang_settings.ext == "cpp" {
+                        traverse_cpp_nodes(
+                            root_node,
+                            &source_code,
+                            &file_path,
+                            is_cpp_test_file(&repo.repo, entry.path()),
+                            &cpp_declarations,
+                            &mut methods,
+                        );
+                    } else if lang_settings.ext == "rb" {
+                        traverse_ruby_nodes(
+                            root_node,
+                            &source_code,
+                            &file_path,
+                            is_ruby_test_file(&repo.repo, entry.path()),
+                            &mut methods,
+                        );
+                    } else if lang_settings.ext == "php" {
+                        traverse_php_nodes(
+                            root_node,
+                            &source_code,
+                            &file_path,
+                            is_php_test_file(&repo.repo, entry.path()),
+                            &mut methods,
+                        );
+                    } else if lang_settings.ext == "swift" {
+                        traverse_swift_nodes(
+                            root_node,
+                            &source_code,
+                            &file_path,
+                            is_swift_test_file(&repo.repo, entry.path()),
+                            &mut methods,
+                        );
+                    } else if lang_settings.ext == "kt" {
+                        traverse_kotlin_nodes(
+                            root_node,
+                            &source_code,
+                            &file_path,
+                            is_kotlin_test_source(&repo.repo, entry.path()),
+                            &mut methods,
+                        );
+                    } else if lang_settings.ext == "java" {
+                        traverse_java_nodes(
+                            root_node,
+                            &source_code,
+                            &file_path,
+                            is_java_test_source(&repo.repo, entry.path()),
+                            &mut methods,
+                        );
+                    } else if is_js_family(lang_settings) {
+                        traverse_js_nodes(
+                            root_node,
+                            &source_code,
+                            &file_path,
+                            js_test_files.is_test_file(entry.path()),
                             &mut methods,
                         );
                     } else {
@@ -86,7 +300,9 @@ fn extract_logic_methods(repo: &String, lang_settings: &LangSettings) -> Vec<Met
                                         class_name,
                                         method_name,
                                         body: extract_body(node, &source_code),
+                                        start_line: node.start_position().row + 1,
                                         is_test: test,
+                                        file_path: file_path.to_string(),
                                     });
                                 }
                             }
@@ -99,9 +315,63 @@ fn extract_logic_methods(repo: &String, lang_settings: &LangSettings) -> Vec<Met
     methods
 }
 
+// JavaScript and TypeScript share the same backend
+fn is_js_family(lang_settings: &LangSettings) -> bool {
+    lang_settings.ext == "js" || lang_settings.ext == "ts"
+}
+
+pub fn file_extension(path: &Path) -> String {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Picks the language with the most source files in the repo. Languages defined in
+/// the config directory claim their files ahead of the built-in ones.
+pub fn detect_language(repo: &str, query_languages: Vec<QueryLanguage>) -> Lang {
+    let built_in = [
+        Lang::Csharp,
+        Lang::JS,
+        Lang::TypeScript,
+        Lang::Go,
+        Lang::Java,
+        Lang::Kotlin,
+        Lang::Cpp,
+        Lang::Ruby,
+        Lang::Php,
+        Lang::Swift,
+        Lang::Rust,
+    ];
+    let languages: Vec<Lang> = query_languages
+        .into_iter()
+        .map(|l| Lang::Query(Box::new(l)))
+        .chain(built_in)
+        .collect();
+    let settings: Vec<LangSettings> = languages.iter().map(create_lang_settings).collect();
+    let mut counts = vec![0; languages.len()];
+
+    for entry in WalkDir::new(repo)
+        .into_iter()
+        .filter_entry(|e| !should_skip_dir(e))
+        .filter_map(Result::ok)
+    {
+        if let Some(i) = settings.iter().position(|s| s.is_source_file(entry.path())) {
+            counts[i] += 1;
+        }
+    }
+
+    languages
+        .into_iter()
+        .zip(counts)
+        .filter(|(_, count)| *count > 0)
+        .max_by_key(|(_, count)| *count)
+        .map(|(lang, _)| lang)
+        .unwrap_or(Lang::Undefined)
+}
+
 pub fn read_to_string_buffered(reader: BufReader<File>) -> String {
     let mut source_code = String::new();
-    for line in reader.lines().flatten() {
+    for line in reader.lines().map_while(Result::ok) {
         source_code.push_str(&line);
         source_code.push('\n');
     }
@@ -110,44 +380,32 @@ pub fn read_to_string_buffered(reader: BufReader<File>) -> String {
 }
 
 fn find_class_name(node: &tree_sitter::Node, source: &str) -> String {
-    let _cursor = node.walk();
-    node.parent().map_or(String::new(), |parent| {
+    let mut current = *node;
+
+    while let Some(parent) = current.parent() {
         if parent.kind() == "class_declaration" {
-            source[parent.start_byte()..parent.end_byte()].to_string()
-        } else {
-            String::new()
+            if let Some(name) = parent.child_by_field_name("name") {
+                return source[name.start_byte()..name.end_byte()].to_string();
+            }
         }
-    })
+        current = parent;
+    }
+
+    String::new()
 }
 
-fn extract_tested_methods(logic_methods: &[Method], lang_settings: &LangSettings) -> Vec<String> {
-    let mut tested_methods = Vec::new();
+fn extract_tested_methods(logic_methods: &[Method]) -> TestedMethods {
+    let mut tested_methods = TestedMethods::default();
     let logic_method_names: HashSet<String> = logic_methods
         .iter()
         .map(|m| m.method_name.clone())
         .collect();
 
     for method in logic_methods {
-        if method.is_test == true {
+        if method.is_test {
             for line in &method.body {
                 let normalized_line = utils::normalize_line(line);
 
-                if lang_settings.ext == "cs" {
-                    if normalized_line.contains("Assert.") {
-                        extract_csharp_assert_targets(
-                            &line,
-                            &logic_method_names,
-                            &mut tested_methods,
-                        );
-                    } else {
-                        extract_csharp_method_calls(
-                            &line,
-                            &logic_method_names,
-                            &mut tested_methods,
-                        );
-                    }
-                }
-
                 if let Some(start) = normalized_line.find('!') {
                     let macro_name = &normalized_line[..start];
 
@@ -167,7 +425,10 @@ fn extract_tested_methods(logic_methods: &[Method], lang_settings: &LangSettings
                                         .to_string();
 
                                     if logic_method_names.contains(&called_function) {
-                                        tested_methods.push(called_function)
+                                        tested_methods.record(
+                                            CallTarget::Name(called_function),
+                                            &method.method_name,
+                                        );
                                     }
                                 }
                             }
@@ -177,13 +438,19 @@ fn extract_tested_methods(logic_methods: &[Method], lang_settings: &LangSettings
                 if let Some(pos) = normalized_line.find('(') {
                     let before_paren = &normalized_line[..pos].trim();
                     let called_function = if before_paren.contains("=") {
-                        before_paren.split('=').last().unwrap().trim().to_string()
+                        before_paren
+                            .split('=')
+                            .next_back()
+                            .unwrap()
+                            .trim()
+                            .to_string()
                     } else {
                         before_paren.to_string()
                     };
 
                     if logic_method_names.contains(&called_function) {
-                        tested_methods.push(called_function);
+                        tested_methods
+                            .record(CallTarget::Name(called_function), &method.method_name);
                     }
                 }
             }
@@ -228,28 +495,98 @@ fn path_exists(repo: &String) -> bool {
     Path::new(repo).exists()
 }
 
-fn create_lang_settings(lang: &Lang) -> LangSettings {
+pub fn create_lang_settings(lang: &Lang) -> LangSettings {
     match lang {
         Lang::Csharp => LangSettings {
             ext: String::from("cs"),
+            extensions: vec![String::from("cs")],
             uses_classes: true,
             test_pattern: String::from("[Fact]"),
             test_method_start: String::from("Public"),
         },
         Lang::Rust => LangSettings {
             ext: String::from("rs"),
+            extensions: vec![String::from("rs")],
             uses_classes: false,
             test_pattern: String::from("[test]"),
             test_method_start: String::from("fn"),
         },
-        Lang::Python => unimplemented!(),
+        Lang::Go => LangSettings {
+            ext: String::from("go"),
+            extensions: vec![String::from("go")],
+            uses_classes: true,
+            test_pattern: String::from("Test"),
+            test_method_start: String::from("func"),
+        },
+        Lang::Java => LangSettings {
+            ext: String::from("java"),
+            extensions: vec![String::from("java")],
+            uses_classes: true,
+            test_pattern: String::from("@Test"),
+            test_method_start: String::from("void"),
+        },
+        Lang::Kotlin => LangSettings {
+            ext: String::from("kt"),
+            extensions: vec![String::from("kt")],
+            uses_classes: true,
+            test_pattern: String::from("@Test"),
+            test_method_start: String::from("fun"),
+        },
+        Lang::Cpp => LangSettings {
+            ext: String::from("cpp"),
+            extensions: ["c", "h", "cc", "cpp", "cxx", "c++", "hh", "hpp", "hxx"]
+                .map(String::from)
+                .to_vec(),
+            uses_classes: true,
+            test_pattern: String::from("TEST"),
+            test_method_start: String::from("TEST"),
+        },
+        Lang::Ruby => LangSettings {
+            ext: String::from("rb"),
+            extensions: vec![String::from("rb")],
+            uses_classes: true,
+            test_pattern: String::from("it"),
+            test_method_start: String::from("def"),
+        },
+        Lang::Php => LangSettings {
+            ext: String::from("php"),
+            extensions: vec![String::from("php")],
+            uses_classes: true,
+            test_pattern: String::from("test"),
+            test_method_start: String::from("function"),
+        },
+        Lang::Swift => LangSettings {
+            ext: String::from("swift"),
+            extensions: vec![String::from("swift")],
+            uses_classes: true,
+            test_pattern: String::from("test"),
+            test_method_start: String::from("func"),
+        },
+        Lang::Query(language) => LangSettings {
+            ext: language.name.clone(),
+            extensions: language.extensions.clone(),
+            uses_classes: true,
+            test_pattern: String::new(),
+            test_method_start: String::new(),
+        },
+        // Rejected when reading the arguments
+        Lang::Python => unreachable!("Python isn't supported yet"),
         Lang::JS => LangSettings {
             ext: String::from("js"),
+            extensions: ["js", "mjs", "cjs", "jsx"].map(String::from).to_vec(),
             uses_classes: true,
             test_pattern: String::from("test"),
             test_method_start: String::from("test"),
         },
-        Lang::Undefined => unimplemented!(),
+        Lang::TypeScript => LangSettings {
+            ext: String::from("ts"),
+            extensions: ["ts", "tsx", "mts", "cts"].map(String::from).to_vec(),
+            uses_classes: true,
+            test_pattern: String::from("test"),
+            test_method_start: String::from("test"),
+        },
+        // Replaced by the detected language before the analysis starts
+        Lang::Undefined => unreachable!("the language is detected before analysis"),
     }
 }
 
@@ -258,6 +595,7 @@ fn test_create_lang_settings_rust() {
     let result = create_lang_settings(&Lang::Rust);
     let expected = LangSettings {
         ext: String::from("rs"),
+        extensions: vec![String::from("rs")],
         uses_classes: false,
         test_pattern: String::from("[test]"),
         test_method_start: String::from("fn"),
@@ -310,10 +648,12 @@ fn test_extract_logic_methods() {
 
     // Run function on temp directory path
     let lang_settings = create_lang_settings(&Lang::Rust);
-    let methods = extract_logic_methods(
-        &temp_dir.path().to_string_lossy().to_string(),
-        &lang_settings,
-    );
+    let repo = utils::Command {
+        repo: temp_dir.path().to_string_lossy().to_string(),
+        lang: Lang::Rust,
+        ..Default::default()
+    };
+    let methods = extract_logic_methods(&repo, &lang_settings);
 
     // Verify results
     assert_eq!(methods.len(), 2);
@@ -452,6 +792,7 @@ fn test_read_to_string_buffered() {
 }
 
 #[test]
+#[allow(clippy::assertions_on_constants)]
 fn test_start_analysis_valid_repo() {
     use std::fs::{create_dir_all, File};
     use std::io::Write;
@@ -474,6 +815,7 @@ fn test_start_analysis_valid_repo() {
     let mock_repo = Command {
         repo: repo_path.to_str().unwrap().to_string(),
         lang: Lang::Rust,
+        ..Default::default()
     };
 
     // Redirect output to avoid cluttering the test logs
@@ -484,3 +826,37 @@ fn test_start_analysis_valid_repo() {
     // If we reach this point, the function didn't panic, meaning it handled the input correctly.
     assert!(true);
 }
+
+#[test]
+fn test_detect_language() {
+    use std::fs::{self, create_dir_all};
+    use tempfile::tempdir;
+
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let root = temp_dir.path();
+    create_dir_all(root.join("src")).unwrap();
+    create_dir_all(root.join("docs")).unwrap();
+    create_dir_all(root.join("node_modules/lib")).unwrap();
+    for file in [
+        "src/app.ts",
+        "src/view.tsx",
+        "src/types.d.ts",
+        "src/types2.d.ts",
+        "src/types3.d.ts",
+        "jest.config.js",
+        "node_modules/lib/a.js",
+        "node_modules/lib/b.js",
+        "node_modules/lib/c.js",
+    ] {
+        fs::write(root.join(file), "").unwrap();
+    }
+
+    assert_eq!(
+        detect_language(&root.to_string_lossy(), Vec::new()),
+        Lang::TypeScript
+    );
+    assert_eq!(
+        detect_language(&root.join("docs").to_string_lossy(), Vec::new()),
+        Lang::Undefined
+    );
+}
@@ -1,40 +1,317 @@
-use crate::utils::{LangSettings, Method};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::annotations::{annotations_report, AnnotationStyle};
+use crate::badge::badge_svg;
+use crate::cobertura::cobertura_report;
+use crate::dotnet::{project_for_file, DotnetProject};
+use crate::html::write_html_report;
+use crate::lcov::lcov_report;
+use crate::markdown::markdown_report;
+use crate::report::{split_package, CoverageResults};
+use crate::sarif::sarif_report;
+use crate::utils::{Command, CoverageStatus, LangSettings, Method, ReportFormat, TestedMethods};
+
+#[derive(Debug, Default)]
+struct CoverageCounts {
+    total: usize,
+    tested: usize,
+    via_interface: usize,
+}
+
+impl CoverageCounts {
+    fn add(&mut self, other: &CoverageCounts) {
+        self.total += other.total;
+        self.tested += other.tested;
+        self.via_interface += other.via_interface;
+    }
+}
+
+/// How the text report groups methods; the other formats lay out methods their own way.
+pub enum ReportLayout<'a> {
+    Methods,
+    // By .NET project
+    Projects(&'a [DotnetProject]),
+    // By package, then class
+    Packages,
+}
+
+// Prints a report, or writes it to the --output file
+fn emit(report: String, output: Option<&str>) {
+    match output {
+        Some(path) => fs::write(path, report)
+            .unwrap_or_else(|e| panic!("Could not write the report to {}: {}", path, e)),
+        None => print!("{}", report),
+    }
+}
+
+/// Prints the report in the format asked for on the command line, or writes it to
+/// the output given.
+pub fn generate_coverage_report(
+    repo: &Command,
+    layout: ReportLayout,
+    data: Vec<Method>,
+    tests: TestedMethods,
+    lang_settings: &LangSettings,
+) {
+    let results = || CoverageResults::new(&repo.repo, &data, &tests, lang_settings);
+    match repo.format {
+        ReportFormat::Text => match layout {
+            ReportLayout::Methods => {
+                generate_method_level_coverage_report(data, tests, lang_settings, repo.show_tests)
+            }
+            ReportLayout::Projects(projects) => generate_project_coverage_report(
+                projects,
+                data,
+                tests,
+                lang_settings,
+                repo.show_tests,
+            ),
+            ReportLayout::Packages => {
+                generate_package_coverage_report(data, tests, lang_settings, repo.show_tests)
+            }
+        },
+        ReportFormat::Cobertura => emit(cobertura_report(&results()), repo.output.as_deref()),
+        ReportFormat::Lcov => emit(
+            lcov_report(&results(), repo.lcov_lines),
+            repo.output.as_deref(),
+        ),
+        ReportFormat::Markdown => emit(
+            markdown_report(&results(), repo.baseline.as_ref()),
+            repo.output.as_deref(),
+        ),
+        ReportFormat::Sarif => emit(
+            sarif_report(&results(), &repo.sarif_levels, repo.baseline.as_ref()),
+            repo.output.as_deref(),
+        ),
+        ReportFormat::Github => emit(
+            annotations_report(
+                &results(),
+                AnnotationStyle::Github,
+                repo.changed_files.as_ref(),
+            ),
+            repo.output.as_deref(),
+        ),
+        ReportFormat::Annotations => emit(
+            annotations_report(
+                &results(),
+                AnnotationStyle::Generic,
+                repo.changed_files.as_ref(),
+            ),
+            repo.output.as_deref(),
+        ),
+        ReportFormat::Badge => {
+            let percent = results().percent();
+            emit(badge_svg(percent, &repo.badge), repo.output.as_deref());
+            if let Some(output) = &repo.output {
+                println!("Coverage badge written to {} ({:.2}%)", output, percent);
+            }
+        }
+        ReportFormat::Html => {
+            let dir = repo
+                .output
+                .as_deref()
+                .expect("--format html is rejected without --output");
+            write_html_report(&results(), Path::new(dir))
+                .unwrap_or_else(|e| panic!("Could not write the HTML report to {}: {}", dir, e));
+            println!(
+                "HTML report written to {}",
+                Path::new(dir).join("index.html").display()
+            );
+        }
+    }
+}
 
 //eventually want to be able to pipe output to file
 pub fn generate_method_level_coverage_report(
     data: Vec<Method>,
-    tests: Vec<String>,
+    tests: TestedMethods,
+    lang_settings: &LangSettings,
+    show_tests: bool,
+) {
+    println!("Test Coverage Report");
+    println!("---------------------");
+
+    let counts = print_methods(data.iter(), &tests, show_tests, |m| {
+        method_id(&m.class_name, &m.method_name, lang_settings)
+    });
+
+    println!();
+    print_totals("Total", &counts);
+}
+
+pub fn generate_project_coverage_report(
+    projects: &[DotnetProject],
+    data: Vec<Method>,
+    tests: TestedMethods,
     lang_settings: &LangSettings,
+    show_tests: bool,
 ) {
     println!("Test Coverage Report");
     println!("---------------------");
 
-    let mut total_methods = 0;
-    let mut tested_count = 0;
+    let mut counts = CoverageCounts::default();
+
+    for project in projects.iter().filter(|p| !p.is_test) {
+        let methods = data
+            .iter()
+            .filter(|m| project_for_file(projects, &m.file_path) == Some(project));
+
+        println!("\nProject: {} ({})", project.name, project.path.display());
 
-    for method in data {
-        let method_id = if lang_settings.uses_classes {
-            format!("{}.{}", method.class_name, method.method_name)
+        let test_projects: Vec<&str> = projects
+            .iter()
+            .filter(|p| p.is_test && p.references.contains(&project.path))
+            .map(|p| p.name.as_str())
+            .collect();
+        if test_projects.is_empty() {
+            println!("Tested by: no test projects");
         } else {
-            method.method_name.clone()
-        };
+            println!("Tested by: {}", test_projects.join(", "));
+        }
+
+        let project_counts = print_methods(methods, &tests, show_tests, |m| {
+            method_id(&m.class_name, &m.method_name, lang_settings)
+        });
+        counts.add(&project_counts);
+
+        print_totals("Project", &project_counts);
+    }
+
+    // Sources outside any project can't be reached by a test project
+    let unassigned: Vec<&Method> = data
+        .iter()
+        .filter(|m| !m.is_test && project_for_file(projects, &m.file_path).is_none())
+        .collect();
+    if !unassigned.is_empty() {
+        println!("\nNot part of any project");
+        counts.add(&print_methods(
+            unassigned.into_iter(),
+            &tests,
+            show_tests,
+            |m| method_id(&m.class_name, &m.method_name, lang_settings),
+        ));
+    }
+
+    println!();
+    print_totals("Total", &counts);
+}
+
+// Groups methods by package and class, the way the project report groups C# by project
+pub fn generate_package_coverage_report(
+    data: Vec<Method>,
+    tests: TestedMethods,
+    lang_settings: &LangSettings,
+    show_tests: bool,
+) {
+    println!("Test Coverage Report");
+    println!("---------------------");
+
+    let mut packages: BTreeMap<String, Vec<(String, &Method)>> = BTreeMap::new();
+    for method in data.iter().filter(|m| !m.is_test) {
+        let (package, class) = split_package(&method.class_name);
+        packages.entry(package).or_default().push((class, method));
+    }
+
+    let mut counts = CoverageCounts::default();
+    for (package, mut methods) in packages {
+        // Stable, so methods keep their source order within a class
+        methods.sort_by(|a, b| a.0.cmp(&b.0));
+
+        if package.is_empty() {
+            println!("\nDefault package");
+        } else {
+            println!("\nPackage: {}", package);
+        }
+        let package_counts = print_methods(
+            methods.into_iter().map(|(_, m)| m),
+            &tests,
+            show_tests,
+            |m| {
+                let (_, class) = split_package(&m.class_name);
+                method_id(&class, &m.method_name, lang_settings)
+            },
+        );
+        counts.add(&package_counts);
+
+        print_totals("Package", &package_counts);
+    }
+
+    println!();
+    print_totals("Total", &counts);
+}
+
+pub(crate) fn method_id(
+    class_name: &str,
+    method_name: &str,
+    lang_settings: &LangSettings,
+) -> String {
+    if lang_settings.uses_classes && !class_name.is_empty() {
+        format!("{}.{}", class_name, method_name)
+    } else {
+        method_name.to_string()
+    }
+}
+
+// Prints one line per logic method and returns the counts for them
+fn print_methods<'a>(
+    methods: impl Iterator<Item = &'a Method>,
+    tests: &TestedMethods,
+    show_tests: bool,
+    id: impl Fn(&Method) -> String,
+) -> CoverageCounts {
+    let mut counts = CoverageCounts::default();
+
+    for method in methods {
+        let method_id = id(method);
 
         if !method.is_test {
-            total_methods += 1;
-            if tests.contains(&method.method_name) {
-                println!("✅ Method: {}", method_id);
-                tested_count += 1;
-            } else {
-                println!("❌ Method: {}", method_id);
+            counts.total += 1;
+            match tests.status(method) {
+                CoverageStatus::Covered => {
+                    println!("✅ Method: {}", method_id);
+                    counts.tested += 1;
+                }
+                CoverageStatus::ViaInterface => {
+                    println!("🔷 Method: {} (via interface)", method_id);
+                    counts.via_interface += 1;
+                }
+                CoverageStatus::Uncovered => println!("❌ Method: {}", method_id),
+            }
+
+            if show_tests {
+                for test_name in tests.tests_for(method) {
+                    println!("    ↳ {}", test_name);
+                }
             }
         }
     }
 
-    let coverage = if total_methods > 0 {
+    counts
+}
+
+fn print_totals(scope: &str, counts: &CoverageCounts) {
+    println!(
+        "{} Method Coverage: {:.2}%",
+        scope,
+        coverage_percent(counts.tested, counts.total)
+    );
+    // Interface dispatch only shows an implementation may be exercised, so it is
+    // reported on top of the direct coverage rather than folded into it
+    if counts.via_interface > 0 {
+        println!(
+            "{} Method Coverage Including Interface Dispatch: {:.2}%",
+            scope,
+            coverage_percent(counts.tested + counts.via_interface, counts.total)
+        );
+    }
+}
+
+pub(crate) fn coverage_percent(tested_count: usize, total_methods: usize) -> f64 {
+    if total_methods > 0 {
         (tested_count as f64 / total_methods as f64) * 100.0
     } else {
         0.0
-    };
-
-    println!("\nTotal Method Coverage: {:.2}%", coverage);
+    }
 }
@@ -0,0 +1,845 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+use crate::codeanalysis::file_extension;
+use crate::utils::{
+    extract_body, get_parser, repo_relative_path, should_skip_dir, CallTarget, LangSettings,
+    Method, TestedMethods,
+};
+
+// GoogleTest macros defining a test as `TEST(Suite, Name) { ... }`
+const GTEST_MACROS: [&str; 5] = ["TEST", "TEST_F", "TEST_P", "TYPED_TEST", "TYPED_TEST_P"];
+// Catch2 and doctest macros defining a named test case
+const CATCH_TEST_MACROS: [&str; 14] = [
+    "TEST_CASE",
+    "TEST_CASE_METHOD",
+    "TEST_CASE_FIXTURE",
+    "TEST_CASE_TEMPLATE",
+    "TEMPLATE_TEST_CASE",
+    "TEMPLATE_PRODUCT_TEST_CASE",
+    "SCENARIO",
+    "SCENARIO_METHOD",
+    "CATCH_TEST_CASE",
+    "CATCH_SCENARIO",
+    "DOCTEST_TEST_CASE",
+    "DOCTEST_TEST_CASE_FIXTURE",
+    "DOCTEST_TEST_CASE_TEMPLATE",
+    "DOCTEST_SCENARIO",
+];
+// Catch2 and doctest blocks nested in a test case
+const CATCH_SECTION_MACROS: [&str; 12] = [
+    "SECTION",
+    "CATCH_SECTION",
+    "SUBCASE",
+    "DOCTEST_SUBCASE",
+    "GIVEN",
+    "AND_GIVEN",
+    "WHEN",
+    "AND_WHEN",
+    "THEN",
+    "AND_THEN",
+    "DYNAMIC_SECTION",
+    "CATCH_DYNAMIC_SECTION",
+];
+
+/// Test sources by the usual naming: anything under a `test`/`tests` directory, and
+/// files such as `foo_test.cpp`, `test_foo.c` or `FooTest.cpp`.
+pub fn is_cpp_test_file(repo: &str, path: &Path) -> bool {
+    let path = repo_relative_path(repo, path);
+    let in_test_dir = path.parent().is_some_and(|dir| {
+        dir.components().any(|c| {
+            let name = c.as_os_str().to_string_lossy().to_lowercase();
+            name == "test" || name == "tests" || name == "unittests"
+        })
+    });
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    in_test_dir
+        || ["_test", "_tests", "_unittest", "Test", "Tests"]
+            .iter()
+            .any(|suffix| stem.ends_with(suffix))
+        || stem.starts_with("test_")
+}
+
+/// Class and namespace names declared anywhere in the repo, so that an out-of-line
+/// definition such as `Foo::bar` in a `.cpp` file can be told apart from a function
+/// in namespace `Foo` using the class declared in its header.
+#[derive(Debug, Default)]
+pub struct CppDeclarations {
+    classes: HashSet<String>,
+    namespaces: HashSet<String>,
+}
+
+impl CppDeclarations {
+    pub fn load(repo: &str, lang_settings: &LangSettings) -> CppDeclarations {
+        let mut declarations = CppDeclarations::default();
+
+        for entry in WalkDir::new(repo)
+            .into_iter()
+            .filter_entry(|e| !should_skip_dir(e))
+            .filter_map(Result::ok)
+            .filter(|e| lang_settings.is_source_file(e.path()))
+        {
+            let Ok(source_code) = fs::read_to_string(entry.path()) else {
+                continue;
+            };
+            let mut parser = get_parser(&file_extension(entry.path()));
+            if let Some(tree) = parser.parse(&source_code, None) {
+                declarations.collect(tree.root_node(), &source_code);
+            }
+        }
+        declarations
+    }
+
+    fn collect(&mut self, node: tree_sitter::Node, source_code: &str) {
+        let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+
+        match node.kind() {
+            "class_specifier" | "struct_specifier" | "union_specifier"
+                if node.child_by_field_name("body").is_some() =>
+            {
+                if let Some(name) = node.child_by_field_name("name") {
+                    self.classes.insert(cpp_simple_name(&text(name)));
+                }
+            }
+            "namespace_definition" => {
+                if let Some(name) = node.child_by_field_name("name") {
+                    // `namespace a::b { }`
+                    self.namespaces
+                        .extend(text(name).split("::").map(|s| s.trim().to_string()));
+                }
+            }
+            _ => {}
+        }
+
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.collect(child, source_code);
+        }
+    }
+
+    fn is_class(&self, name: &str) -> bool {
+        self.classes.contains(name) || !self.namespaces.contains(name)
+    }
+}
+
+// `Foo<T>` gives "Foo", `ns::Foo` gives "Foo"
+fn cpp_simple_name(name: &str) -> String {
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name).trim().to_string()
+}
+
+/// Collects free functions and member functions with a body. Members defined inside
+/// their class and out-of-line `Foo::bar` definitions are both reported on class
+/// `Foo`; namespaces aren't part of the name.
+pub fn traverse_cpp_nodes(
+    node: tree_sitter::Node,
+    source_code: &str,
+    file_path: &str,
+    is_test_file: bool,
+    declarations: &CppDeclarations,
+    methods: &mut Vec<Method>,
+) {
+    // Helpers next to tests aren't logic to be covered
+    let mut tests = Vec::new();
+    collect_cpp_tests(node, source_code, None, &mut tests);
+    let is_test = is_test_file || !tests.is_empty();
+
+    collect_cpp_functions(
+        node,
+        source_code,
+        file_path,
+        is_test,
+        declarations,
+        &mut Vec::new(),
+        methods,
+    );
+}
+
+fn collect_cpp_functions(
+    node: tree_sitter::Node,
+    source_code: &str,
+    file_path: &str,
+    is_test: bool,
+    declarations: &CppDeclarations,
+    classes: &mut Vec<String>,
+    methods: &mut Vec<Method>,
+) {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+
+    match node.kind() {
+        "class_specifier" | "struct_specifier" | "union_specifier" => {
+            if let (Some(name), Some(body)) = (
+                node.child_by_field_name("name"),
+                node.child_by_field_name("body"),
+            ) {
+                classes.push(cpp_simple_name(&text(name)));
+                collect_cpp_functions(
+                    body,
+                    source_code,
+                    file_path,
+                    is_test,
+                    declarations,
+                    classes,
+                    methods,
+                );
+                classes.pop();
+            }
+            return;
+        }
+        "function_definition" => {
+            if let Some((scope, name)) = cpp_function_name(node, source_code) {
+                if GTEST_MACROS.contains(&name.as_str()) && scope.is_empty() {
+                    return;
+                }
+                let mut class_path = classes.clone();
+                class_path.extend(scope.into_iter().filter(|s| declarations.is_class(s)));
+                methods.push(Method {
+                    class_name: class_path.join("."),
+                    method_name: name,
+                    body: extract_body(node, source_code),
+                    start_line: node.start_position().row + 1,
+                    is_test,
+                    file_path: file_path.to_string(),
+                });
+            }
+            // Lambdas and local classes belong to the function they are declared in
+            return;
+        }
+        _ => {}
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_cpp_functions(
+            child,
+            source_code,
+            file_path,
+            is_test,
+            declarations,
+            classes,
+            methods,
+        );
+    }
+}
+
+// The scope segments and name of a function definition: `int Foo::bar()` gives
+// (["Foo"], "bar"), `Foo::~Foo()` gives (["Foo"], "~Foo")
+fn cpp_function_name(node: tree_sitter::Node, source_code: &str) -> Option<(Vec<String>, String)> {
+    let mut declarator = node.child_by_field_name("declarator")?;
+    // Return types such as `char *` and `Foo &` wrap the function declarator
+    while declarator.kind() != "function_declarator" {
+        declarator = declarator.child_by_field_name("declarator")?;
+    }
+    let name = declarator.child_by_field_name("declarator")?;
+    let name = &source_code[name.start_byte()..name.end_byte()];
+
+    let mut segments: Vec<String> = name
+        .split("::")
+        .map(|s| s.split('<').next().unwrap_or(s).trim().to_string())
+        .collect();
+    let name = segments.pop()?;
+    Some((
+        segments.into_iter().filter(|s| !s.is_empty()).collect(),
+        name,
+    ))
+}
+
+// The macro name and arguments of a statement such as `TEST_CASE("name") ` that is
+// followed by its block. tree-sitter can't tell these are macros, so they show up as
+// calls, sometimes wrapped in an error node.
+fn cpp_macro_call<'a>(
+    node: tree_sitter::Node<'a>,
+    source_code: &str,
+) -> Option<(String, tree_sitter::Node<'a>)> {
+    if !matches!(node.kind(), "expression_statement" | "ERROR") {
+        return None;
+    }
+    let call = node
+        .named_child(0)
+        .filter(|c| c.kind() == "call_expression")?;
+    let function = call
+        .child_by_field_name("function")
+        .filter(|f| f.kind() == "identifier")?;
+    Some((
+        source_code[function.start_byte()..function.end_byte()].to_string(),
+        call.child_by_field_name("arguments")?,
+    ))
+}
+
+fn first_string_literal(node: tree_sitter::Node, source_code: &str) -> Option<String> {
+    if node.kind() == "string_literal" {
+        let text = &source_code[node.start_byte()..node.end_byte()];
+        return Some(text.trim_matches('"').to_string());
+    }
+    let mut cursor = node.walk();
+    let found = node
+        .named_children(&mut cursor)
+        .find_map(|c| first_string_literal(c, source_code));
+    found
+}
+
+// Catch2 hides tests tagged `[.]`, `[.tag]` or `[!hide]`, and doctest skips tests
+// decorated with `doctest::skip()`
+fn is_skipped_catch_test(arguments: tree_sitter::Node, source_code: &str) -> bool {
+    let arguments = &source_code[arguments.start_byte()..arguments.end_byte()];
+    arguments.contains("[.") || arguments.contains("[!hide]") || arguments.contains("skip()")
+}
+
+// Finds enabled tests, returning each test's name with the node holding its body.
+// GoogleTest tests are named `Suite.Name`; Catch2 and doctest sections are named after
+// their test case, e.g. `clamps values > negative`.
+fn collect_cpp_tests<'a>(
+    node: tree_sitter::Node<'a>,
+    source_code: &str,
+    parent: Option<&str>,
+    tests: &mut Vec<(String, tree_sitter::Node<'a>)>,
+) {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+    let mut cursor = node.walk();
+    let children: Vec<tree_sitter::Node> = node.named_children(&mut cursor).collect();
+
+    for (i, child) in children.iter().enumerate() {
+        if parent.is_none() && child.kind() == "function_definition" {
+            let Some((scope, name)) = cpp_function_name(*child, source_code) else {
+                continue;
+            };
+            let declarator = child
+                .child_by_field_name("declarator")
+                .and_then(|d| d.child_by_field_name("parameters"));
+            if let (true, Some(parameters), Some(body)) = (
+                scope.is_empty() && GTEST_MACROS.contains(&name.as_str()),
+                declarator,
+                child.child_by_field_name("body"),
+            ) {
+                let mut cursor = parameters.walk();
+                let parts: Vec<String> = parameters
+                    .named_children(&mut cursor)
+                    .map(|p| text(p).trim().to_string())
+                    .collect();
+                // GoogleTest skips tests and suites prefixed with `DISABLED_`
+                if !parts.iter().any(|p| p.starts_with("DISABLED_")) {
+                    tests.push((parts.join("."), body));
+                }
+            }
+            continue;
+        }
+
+        if let Some((name, arguments)) = cpp_macro_call(*child, source_code) {
+            let macros: &[&str] = if parent.is_none() {
+                &CATCH_TEST_MACROS
+            } else {
+                &CATCH_SECTION_MACROS
+            };
+            let block = children
+                .get(i + 1)
+                .filter(|b| b.kind() == "compound_statement");
+            if let (true, Some(block)) = (macros.contains(&name.as_str()), block) {
+                if is_skipped_catch_test(arguments, source_code) {
+                    continue;
+                }
+                let title = first_string_literal(arguments, source_code).unwrap_or(name);
+                let test_name = match parent {
+                    Some(parent) => format!("{} > {}", parent, title),
+                    None => title,
+                };
+                collect_cpp_tests(*block, source_code, Some(&test_name), tests);
+                tests.push((test_name, *block));
+            }
+            continue;
+        }
+
+        if parent.is_none()
+            && matches!(
+                child.kind(),
+                "namespace_definition" | "declaration_list" | "linkage_specification"
+            )
+        {
+            collect_cpp_tests(*child, source_code, None, tests);
+        } else if parent.is_some()
+            && child.kind() == "compound_statement"
+            && !is_section_block(*child, source_code)
+        {
+            // Sections can sit in plain blocks inside a test case
+            collect_cpp_tests(*child, source_code, parent, tests);
+        }
+    }
+}
+
+// A call made in a test
+#[derive(PartialEq, Debug)]
+enum CppCall {
+    // `clamp(x)`
+    Unqualified(String),
+    // `Calculator::make()` or `math::twice(2)`
+    Scoped(String, String),
+    // `calc.add(1, 2)` or `calc->add(1, 2)`, with the receiver variable if there is one
+    Member(Option<String>, String),
+    // `Calculator c;`, `new Calculator()`
+    Construct(String),
+}
+
+// Nested Catch2 sections have their own tests, so calls in them aren't gathered for
+// the test case around them
+fn is_section_block(node: tree_sitter::Node, source_code: &str) -> bool {
+    node.kind() == "compound_statement"
+        && node
+            .prev_named_sibling()
+            .and_then(|p| cpp_macro_call(p, source_code))
+            .is_some_and(|(name, _)| CATCH_SECTION_MACROS.contains(&name.as_str()))
+}
+
+fn collect_cpp_calls(
+    node: tree_sitter::Node,
+    source_code: &str,
+    root: bool,
+    calls: &mut Vec<CppCall>,
+) {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+    if !root && is_section_block(node, source_code) {
+        return;
+    }
+
+    let call = match node.kind() {
+        "call_expression" => {
+            node.child_by_field_name("function")
+                .and_then(|function| match function.kind() {
+                    "field_expression" => function.child_by_field_name("field").map(|field| {
+                        let receiver = function
+                            .child_by_field_name("argument")
+                            .filter(|a| a.kind() == "identifier")
+                            .map(text);
+                        CppCall::Member(receiver, cpp_simple_name(&text(field)))
+                    }),
+                    "identifier" | "qualified_identifier" | "template_function" => {
+                        let name = text(function);
+                        let name = name.split('<').next().unwrap_or(&name).to_string();
+                        Some(match name.rsplit_once("::") {
+                            Some((scope, name)) => {
+                                CppCall::Scoped(cpp_simple_name(scope), name.to_string())
+                            }
+                            None => CppCall::Unqualified(name),
+                        })
+                    }
+                    _ => None,
+                })
+        }
+        "new_expression" => node
+            .child_by_field_name("type")
+            .map(|t| CppCall::Construct(cpp_simple_name(&text(t)))),
+        // `Calculator calc;` and `Calculator calc(2);` run the constructor
+        "declaration" => node
+            .child_by_field_name("type")
+            .filter(|t| {
+                matches!(
+                    t.kind(),
+                    "type_identifier" | "qualified_identifier" | "template_type"
+                )
+            })
+            .filter(|_| {
+                let mut cursor = node.walk();
+                let constructs = node
+                    .children_by_field_name("declarator", &mut cursor)
+                    .any(|d| {
+                        d.kind() == "identifier"
+                            || (d.kind() == "init_declarator"
+                                && d.child_by_field_name("value").is_some_and(|v| {
+                                    matches!(v.kind(), "argument_list" | "initializer_list")
+                                }))
+                    });
+                constructs
+            })
+            .map(|t| CppCall::Construct(cpp_simple_name(&text(t)))),
+        _ => None,
+    };
+
+    if let Some(call) = call {
+        if !calls.contains(&call) {
+            calls.push(call);
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_cpp_calls(child, source_code, false, calls);
+    }
+}
+
+// Records the declared types of fields, parameters and locals in a file. `auto x =
+// Foo::make()`, `auto x = new Foo()` and `std::make_unique<Foo>()` are typed from
+// their initializer, and smart pointers by the type they hold.
+fn collect_cpp_declared_types(
+    node: tree_sitter::Node,
+    source_code: &str,
+    types: &mut HashMap<String, String>,
+) {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+
+    if matches!(
+        node.kind(),
+        "declaration" | "field_declaration" | "parameter_declaration"
+    ) {
+        if let Some(declared) = node.child_by_field_name("type").map(text) {
+            let mut cursor = node.walk();
+            for mut declarator in node.children_by_field_name("declarator", &mut cursor) {
+                let value = declarator.child_by_field_name("value");
+                // Step through `*p`, `&r` and `x = ...` to the declared name
+                while let Some(inner) = declarator.child_by_field_name("declarator") {
+                    declarator = inner;
+                }
+                if !matches!(declarator.kind(), "identifier" | "field_identifier") {
+                    continue;
+                }
+
+                let inferred = value.and_then(|v| cpp_value_type(v, source_code));
+                let declared = match inferred {
+                    Some(inferred) if declared == "auto" => inferred,
+                    _ => cpp_held_type(&declared),
+                };
+                types.insert(text(declarator), declared);
+            }
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_cpp_declared_types(child, source_code, types);
+    }
+}
+
+fn cpp_value_type(value: tree_sitter::Node, source_code: &str) -> Option<String> {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+    match value.kind() {
+        "new_expression" => value
+            .child_by_field_name("type")
+            .map(|t| cpp_simple_name(&text(t))),
+        "call_expression" => {
+            let function = text(value.child_by_field_name("function")?);
+            if let Some((_, held)) = function.split_once('<') {
+                // std::make_unique<Foo>(...)
+                Some(cpp_simple_name(held.trim_end_matches('>')))
+            } else if let Some((scope, _)) = function.rsplit_once("::") {
+                Some(cpp_simple_name(scope))
+            } else {
+                Some(function)
+            }
+        }
+        _ => None,
+    }
+}
+
+// `std::unique_ptr<Foo>` gives "Foo", `const ns::Foo` gives "Foo"
+fn cpp_held_type(declared: &str) -> String {
+    let declared = declared.trim_start_matches("const ").trim();
+    for pointer in ["unique_ptr<", "shared_ptr<", "weak_ptr<"] {
+        if let Some((_, held)) = declared.split_once(pointer) {
+            return cpp_simple_name(held.trim_end_matches('>'));
+        }
+    }
+    cpp_simple_name(declared)
+}
+
+/// Resolves the calls made by GoogleTest, Catch2 and doctest tests. Member calls are
+/// matched to the class of the variable they are made on when its declared type is
+/// known, and to members of any class otherwise.
+pub fn extract_cpp_tested_methods(
+    repo: &str,
+    lang_settings: &LangSettings,
+    logic_methods: &[Method],
+) -> TestedMethods {
+    let mut tested_methods = TestedMethods::default();
+    let candidates: Vec<&Method> = logic_methods.iter().filter(|m| !m.is_test).collect();
+    let simple_class = |m: &Method| m.class_name.rsplit('.').next().unwrap_or("").to_string();
+    let classes: HashSet<String> = candidates.iter().map(|m| simple_class(m)).collect();
+
+    for entry in WalkDir::new(repo)
+        .into_iter()
+        .filter_entry(|e| !should_skip_dir(e))
+        .filter_map(Result::ok)
+        .filter(|e| lang_settings.is_source_file(e.path()))
+    {
+        let Ok(source_code) = fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let mut parser = get_parser(&file_extension(entry.path()));
+        let Some(tree) = parser.parse(&source_code, None) else {
+            continue;
+        };
+
+        let mut tests = Vec::new();
+        collect_cpp_tests(tree.root_node(), &source_code, None, &mut tests);
+        if tests.is_empty() {
+            continue;
+        }
+        let mut variables = HashMap::new();
+        collect_cpp_declared_types(tree.root_node(), &source_code, &mut variables);
+
+        for (test_name, body) in tests {
+            let mut calls = Vec::new();
+            collect_cpp_calls(body, &source_code, true, &mut calls);
+
+            for call in calls {
+                let matches = |m: &&&Method| match &call {
+                    CppCall::Unqualified(name) => {
+                        m.method_name == *name
+                            && (m.class_name.is_empty() || simple_class(m) == *name)
+                    }
+                    CppCall::Scoped(scope, name) if classes.contains(scope) => {
+                        m.method_name == *name && simple_class(m) == *scope
+                    }
+                    // A namespace-qualified free function
+                    CppCall::Scoped(_, name) => m.method_name == *name && m.class_name.is_empty(),
+                    CppCall::Member(receiver, name) => {
+                        let class = receiver
+                            .as_ref()
+                            .and_then(|r| variables.get(r))
+                            .filter(|c| classes.contains(*c));
+                        m.method_name == *name
+                            && !m.class_name.is_empty()
+                            && class.is_none_or(|c| simple_class(m) == *c)
+                    }
+                    CppCall::Construct(class) => {
+                        m.method_name == *class && simple_class(m) == *class
+                    }
+                };
+                for method in candidates.iter().filter(matches) {
+                    tested_methods.record(CallTarget::exact(method), &test_name);
+                }
+            }
+        }
+    }
+
+    tested_methods
+}
+
+#[test]
+fn test_traverse_cpp_nodes() {
+    let header = r#"
+namespace math {
+class Calculator {
+public:
+    Calculator();
+    int add(int a, int b);
+    int sub(int a, int b) { return a - b; }
+    virtual int area() const = 0;
+    struct Memory { void clear() {} };
+};
+namespace detail { int helper(); }
+}
+int clamp(int x);
+"#;
+    let source_code = r#"
+#include "calc.hpp"
+namespace math {
+Calculator::Calculator() : precision_(2) {}
+int Calculator::add(int a, int b) { return a + b; }
+int detail::helper() { return 1; }
+}
+static const char *name(void) { return "calc"; }
+int clamp(int x) { return x < 0 ? 0 : x; }
+"#;
+    let mut declarations = CppDeclarations::default();
+    let mut methods = Vec::new();
+    for (file, source) in [("calc.hpp", header), ("calc.cpp", source_code)] {
+        let mut parser = get_parser("cpp");
+        let tree = parser.parse(source, None).unwrap();
+        declarations.collect(tree.root_node(), source);
+        traverse_cpp_nodes(
+            tree.root_node(),
+            source,
+            file,
+            false,
+            &declarations,
+            &mut methods,
+        );
+    }
+
+    let found: Vec<(&str, &str)> = methods
+        .iter()
+        .map(|m| (m.class_name.as_str(), m.method_name.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("Calculator", "sub"),
+            ("Calculator.Memory", "clear"),
+            ("Calculator", "Calculator"),
+            ("Calculator", "add"),
+            ("", "helper"),
+            ("", "name"),
+            ("", "clamp"),
+        ]
+    );
+    assert!(is_cpp_test_file(".", Path::new("tests/calc.cpp")));
+    assert!(is_cpp_test_file(".", Path::new("src/calc_test.cc")));
+    assert!(!is_cpp_test_file(".", Path::new("src/calc.cpp")));
+    // A repo checked out under someone's tests directory
+    assert!(!is_cpp_test_file(
+        "/home/me/tests/calc",
+        Path::new("/home/me/tests/calc/src/calc.cpp")
+    ));
+}
+
+#[test]
+fn test_extract_cpp_tested_methods() {
+    use crate::codeanalysis::create_lang_settings;
+    use crate::utils::Lang;
+    use std::fs::create_dir_all;
+    use tempfile::tempdir;
+
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    // Nested in a tests directory, which mustn't make the sources tests
+    let root = temp_dir.path().join("tests/calc");
+    create_dir_all(root.join("src")).unwrap();
+    create_dir_all(root.join("tests")).unwrap();
+    fs::write(
+        root.join("src/calc.hpp"),
+        r#"class Calculator {
+public:
+    int add(int a, int b) { return a + b; }
+    int sub(int a, int b) { return a - b; }
+    int mul(int a, int b) { return a * b; }
+    static Calculator make() { return Calculator(); }
+};
+int clamp(int x) { return x < 0 ? 0 : x; }
+int twice(int x) { return x * 2; }
+int unused() { return 0; }
+"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("tests/calc_test.cpp"),
+        r#"#include <gtest/gtest.h>
+TEST(CalcTest, Adds) {
+    auto calc = Calculator::make();
+    EXPECT_EQ(3, calc.add(1, 2));
+}
+TEST(CalcTest, DISABLED_Subs) { Calculator c; c.sub(2, 1); }
+TEST_CASE("clamps values", "[clamp]") {
+    SECTION("negative") { REQUIRE(clamp(-1) == 0); }
+    SECTION("doubled") { REQUIRE(twice(2) == 4); }
+}
+TEST_CASE("hidden", "[.]") { Calculator c; c.mul(1, 2); }
+"#,
+    )
+    .unwrap();
+
+    let lang_settings = create_lang_settings(&Lang::Cpp);
+    let declarations = CppDeclarations::load(&root.to_string_lossy(), &lang_settings);
+    let mut methods = Vec::new();
+    for file in [root.join("src/calc.hpp"), root.join("tests/calc_test.cpp")] {
+        let source_code = fs::read_to_string(&file).unwrap();
+        let mut parser = get_parser(&file_extension(&file));
+        let tree = parser.parse(&source_code, None).unwrap();
+        traverse_cpp_nodes(
+            tree.root_node(),
+            &source_code,
+            &file.to_string_lossy(),
+            is_cpp_test_file(&root.to_string_lossy(), &file),
+            &declarations,
+            &mut methods,
+        );
+    }
+
+    let tested = extract_cpp_tested_methods(&root.to_string_lossy(), &lang_settings, &methods);
+    let tests_for = |name: &str| {
+        let method = methods.iter().find(|m| m.method_name == name).unwrap();
+        tested.tests_for(method)
+    };
+
+    assert_eq!(tests_for("add"), vec!["CalcTest.Adds"]);
+    assert_eq!(tests_for("make"), vec!["CalcTest.Adds"]);
+    assert!(tests_for("sub").is_empty());
+    assert!(tests_for("mul").is_empty());
+    assert_eq!(tests_for("clamp"), vec!["clamps values > negative"]);
+    assert_eq!(tests_for("twice"), vec!["clamps values > doubled"]);
+    assert!(tests_for("unused").is_empty());
+}
+
+#[test]
+fn test_is_cpp_test_file() {
+    // Tests of a repo nested inside another project's tests directory
+    let repo = "/home/me/tests/calc";
+    assert!(is_cpp_test_file(
+        repo,
+        Path::new("/home/me/tests/calc/tests/calc.cpp")
+    ));
+    assert!(!is_cpp_test_file(
+        repo,
+        Path::new("/home/me/tests/calc/src/calc.cpp")
+    ));
+    // Files outside the repo root are judged on their whole path
+    assert!(is_cpp_test_file(repo, Path::new("/opt/test/vendor.cpp")));
+    assert!(!is_cpp_test_file(repo, Path::new("/opt/src/vendor.cpp")));
+}
+
+#[test]
+fn test_cpp_disabled_and_overloaded_tests() {
+    use crate::codeanalysis::create_lang_settings;
+    use crate::utils::Lang;
+    use tempfile::tempdir;
+
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let root = temp_dir.path();
+    fs::write(
+        root.join("calc.hpp"),
+        r#"class Calculator {
+public:
+    int add(int a, int b) { return a + b; }
+    double add(double a, double b) { return a + b; }
+    int sub(int a, int b) { return a - b; }
+};
+"#,
+    )
+    .unwrap();
+    let test_source = r#"TEST(CalcTest, Adds) { Calculator c; c.add(1, 2); }
+TEST(CalcTest, DISABLED_Subs) { Calculator c; c.sub(2, 1); }
+TEST_F(DISABLED_CalcFixture, Subs) { Calculator c; c.sub(2, 1); }
+TEST_CASE("tagged hidden", "[.integration]") { Calculator c; c.sub(2, 1); }
+TEST_CASE("hidden", "[!hide]") {
+    SECTION("inside") { Calculator c; c.sub(2, 1); }
+}
+TEST_CASE("skipped" * doctest::skip()) { Calculator c; c.sub(2, 1); }
+"#;
+    fs::write(root.join("calc_test.cpp"), test_source).unwrap();
+
+    let tree = get_parser("cpp").parse(test_source, None).unwrap();
+    let mut tests = Vec::new();
+    collect_cpp_tests(tree.root_node(), test_source, None, &mut tests);
+    let names: Vec<&str> = tests.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, vec!["CalcTest.Adds"]);
+
+    let lang_settings = create_lang_settings(&Lang::Cpp);
+    let declarations = CppDeclarations::load(&root.to_string_lossy(), &lang_settings);
+    let file = root.join("calc.hpp");
+    let source_code = fs::read_to_string(&file).unwrap();
+    let tree = get_parser("hpp").parse(&source_code, None).unwrap();
+    let mut methods = Vec::new();
+    traverse_cpp_nodes(
+        tree.root_node(),
+        &source_code,
+        &file.to_string_lossy(),
+        false,
+        &declarations,
+        &mut methods,
+    );
+
+    // Overloads aren't told apart by their arguments, so a call covers each of them
+    let tested = extract_cpp_tested_methods(&root.to_string_lossy(), &lang_settings, &methods);
+    let tests_for = |name: &str| -> Vec<Vec<String>> {
+        methods
+            .iter()
+            .filter(|m| m.method_name == name)
+            .map(|m| tested.tests_for(m))
+            .collect()
+    };
+    assert_eq!(
+        tests_for("add"),
+        vec![vec!["CalcTest.Adds"], vec!["CalcTest.Adds"]]
+    );
+    assert_eq!(tests_for("sub"), vec![Vec::<String>::new()]);
+}
@@ -1,16 +1,32 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
 
-use crate::utils::{extract_body, LangSettings, Method};
+use crate::utils::{
+    extract_body, get_parser, normalize_line, should_skip_dir, CallTarget, Command, LangSettings,
+    Method, TestedMethods,
+};
+
+// File name suffixes produced by designers, MSBuild and source generators
+const GENERATED_FILE_SUFFIXES: [&str; 4] = [".designer.cs", ".g.cs", ".g.i.cs", ".assemblyinfo.cs"];
 
 pub fn traverse_c_sharp_nodes(
     node: tree_sitter::Node,
-    cursor: &mut tree_sitter::TreeCursor,
     source_code: &str,
+    file_path: &str,
     lang_settings: &LangSettings,
+    include_generated: bool,
     methods: &mut Vec<Method>,
 ) {
-    // Process method declarations in C# syntax
-    if node.kind() == "method_declaration" {
+    // Generated members are skipped along with everything nested inside them
+    if !include_generated && is_generated_csharp_member(&node, source_code) {
+        return;
+    }
+
+    // Process method declarations in C# syntax. Interface members and abstract
+    // methods have no body, so there is nothing for a test to cover
+    if node.kind() == "method_declaration" && node.child_by_field_name("body").is_some() {
         let mut test = false;
         if is_csharp_test_method(&node, source_code, lang_settings) {
             test = true;
@@ -28,7 +44,9 @@ pub fn traverse_c_sharp_nodes(
                 class_name,
                 method_name,
                 body: extract_body(node, source_code),
+                start_line: node.start_position().row + 1,
                 is_test: test,
+                file_path: file_path.to_string(),
             });
         }
     } else if node.kind() == "constructor_declaration" {
@@ -43,7 +61,9 @@ pub fn traverse_c_sharp_nodes(
                 class_name,
                 method_name,
                 body: extract_body(node, source_code),
+                start_line: node.start_position().row + 1,
                 is_test: false, // Constructors are typically not tests
+                file_path: file_path.to_string(),
             });
         }
     }
@@ -51,9 +71,102 @@ pub fn traverse_c_sharp_nodes(
     // Recursively traverse child nodes
     for child_idx in 0..node.child_count() {
         if let Some(child) = node.child(child_idx) {
-            traverse_c_sharp_nodes(child, cursor, source_code, lang_settings, methods);
+            traverse_c_sharp_nodes(
+                child,
+                source_code,
+                file_path,
+                lang_settings,
+                include_generated,
+                methods,
+            );
+        }
+    }
+}
+
+pub fn is_generated_csharp_file(repo: &Path, path: &Path, source_code: &str) -> bool {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if GENERATED_FILE_SUFFIXES
+        .iter()
+        .any(|suffix| file_name.ends_with(suffix))
+    {
+        return true;
+    }
+
+    // Source generator and MSBuild output lands under obj/. Only directories inside
+    // the repo count, so a repo checked out under an obj/ directory isn't skipped.
+    let relative = path.strip_prefix(repo).unwrap_or(path);
+    if relative.components().any(|c| c.as_os_str() == "obj") {
+        return true;
+    }
+
+    // Generated files announce themselves in their leading comment block
+    for line in source_code.lines().map(str::trim) {
+        if line.is_empty() {
+            continue;
+        }
+        if !line.starts_with("//") {
+            break;
+        }
+        if line.contains("<auto-generated") {
+            return true;
+        }
+    }
+
+    false
+}
+
+fn is_generated_csharp_member(node: &tree_sitter::Node, source_code: &str) -> bool {
+    if !matches!(
+        node.kind(),
+        "method_declaration"
+            | "constructor_declaration"
+            | "class_declaration"
+            | "struct_declaration"
+            | "record_declaration"
+    ) {
+        return false;
+    }
+
+    csharp_attribute_names(node, source_code)
+        .iter()
+        .any(|name| name == "GeneratedCode" || name == "CompilerGenerated")
+}
+
+// Names of the attributes directly attached to a declaration, without namespace or
+// `Attribute` suffix, so `[System.CodeDom.Compiler.GeneratedCodeAttribute]` is
+// `GeneratedCode`
+fn csharp_attribute_names(node: &tree_sitter::Node, source_code: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut cursor = node.walk();
+    for list in node
+        .children(&mut cursor)
+        .filter(|child| child.kind() == "attribute_list")
+    {
+        let mut list_cursor = list.walk();
+        for attribute in list
+            .children(&mut list_cursor)
+            .filter(|child| child.kind() == "attribute")
+        {
+            if let Some(name) = attribute.child_by_field_name("name") {
+                let name = &source_code[name.start_byte()..name.end_byte()];
+                let name = name.rsplit(['.', ':']).next().unwrap_or(name);
+                names.push(name.strip_suffix("Attribute").unwrap_or(name).to_string());
+            }
         }
     }
+    names
+}
+
+// Concatenated text of every attribute list directly attached to a declaration
+fn csharp_attribute_text(node: &tree_sitter::Node, source_code: &str) -> String {
+    let mut cursor = node.walk();
+    node.children(&mut cursor)
+        .filter(|child| child.kind() == "attribute_list")
+        .map(|child| &source_code[child.start_byte()..child.end_byte()])
+        .collect()
 }
 
 fn find_csharp_class_name(node: &tree_sitter::Node, source: &str) -> String {
@@ -84,30 +197,234 @@ fn is_csharp_test_method(
     }
 
     // Look for attribute lists before the method
-    let mut cursor = node.walk();
-    if cursor.goto_first_child() {
-        loop {
-            let current = cursor.node();
-            if current.kind() == "attribute_list" {
-                let attribute_text = &source_code[current.start_byte()..current.end_byte()];
-
-                if attribute_text.contains(&lang_settings.test_pattern)
-                    || attribute_text.contains("[Test]")
-                    || attribute_text.contains("[TestMethod]")
-                    || attribute_text.contains("[Theory]")
-                    || attribute_text.contains("[Fact]")
-                {
+    let attribute_text = csharp_attribute_text(node, source_code);
+
+    attribute_text.contains(&lang_settings.test_pattern)
+        || attribute_text.contains("[Test]")
+        || attribute_text.contains("[TestMethod]")
+        || attribute_text.contains("[Theory]")
+        || attribute_text.contains("[Fact]")
+}
+
+#[derive(Debug, Default)]
+pub struct CsharpTypeIndex {
+    pub interfaces: HashSet<String>,
+    // Type name -> the base class and interfaces listed after the colon
+    pub base_types: HashMap<String, Vec<String>>,
+    // Extension method name -> (declaring static class, type of its `this` parameter)
+    pub extension_methods: HashMap<String, Vec<(String, String)>>,
+}
+
+impl CsharpTypeIndex {
+    pub fn implements(&self, type_name: &str, interface: &str) -> bool {
+        let mut pending = vec![type_name];
+        let mut seen = HashSet::new();
+
+        while let Some(current) = pending.pop() {
+            if !seen.insert(current) {
+                continue;
+            }
+            if let Some(bases) = self.base_types.get(current) {
+                if bases.iter().any(|b| b == interface) {
                     return true;
                 }
+                pending.extend(bases.iter().map(String::as_str));
             }
+        }
+
+        false
+    }
+
+    /// The static classes declaring an extension method with this name that can be
+    /// called on a value of the receiver type.
+    pub fn extension_classes(&self, method_name: &str, receiver_type: &str) -> Vec<&str> {
+        self.extension_methods
+            .get(method_name)
+            .into_iter()
+            .flatten()
+            .filter(|(_, this_type)| {
+                this_type == receiver_type || self.implements(receiver_type, this_type)
+            })
+            .map(|(class_name, _)| class_name.as_str())
+            .collect()
+    }
+}
+
+pub fn build_csharp_type_index(repo: &Command) -> CsharpTypeIndex {
+    let mut index = CsharpTypeIndex::default();
+    let mut parser = get_parser("cs");
 
-            if !cursor.goto_next_sibling() {
-                break;
+    for entry in WalkDir::new(&repo.repo)
+        .into_iter()
+        .filter_entry(|e| !should_skip_dir(e))
+        .filter_map(Result::ok)
+    {
+        if entry.path().extension().is_some_and(|ext| ext == "cs") {
+            if let Ok(source_code) = fs::read_to_string(entry.path()) {
+                if !repo.include_generated
+                    && is_generated_csharp_file(Path::new(&repo.repo), entry.path(), &source_code)
+                {
+                    continue;
+                }
+                if let Some(tree) = parser.parse(&source_code, None) {
+                    index_csharp_types(tree.root_node(), &source_code, &mut index);
+                }
             }
         }
     }
 
-    false
+    index
+}
+
+fn index_csharp_types(node: tree_sitter::Node, source_code: &str, index: &mut CsharpTypeIndex) {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+
+    match node.kind() {
+        "interface_declaration"
+        | "class_declaration"
+        | "struct_declaration"
+        | "record_declaration" => {
+            if let Some(name) = node.child_by_field_name("name") {
+                let type_name = text(name);
+                if node.kind() == "interface_declaration" {
+                    index.interfaces.insert(type_name.clone());
+                }
+
+                let mut cursor = node.walk();
+                for base_list in node
+                    .children(&mut cursor)
+                    .filter(|c| c.kind() == "base_list")
+                {
+                    let mut base_cursor = base_list.walk();
+                    let bases = base_list
+                        .named_children(&mut base_cursor)
+                        .map(|b| strip_generic_arguments(&text(b)).to_string());
+                    index
+                        .base_types
+                        .entry(type_name.clone())
+                        .or_default()
+                        .extend(bases);
+                }
+            }
+        }
+        "method_declaration" => {
+            let first_parameter = node
+                .child_by_field_name("parameters")
+                .and_then(|params| params.named_child(0));
+            if let (Some(parameter), Some(name)) =
+                (first_parameter, node.child_by_field_name("name"))
+            {
+                let mut cursor = parameter.walk();
+                let is_extension = parameter
+                    .children(&mut cursor)
+                    .any(|c| c.kind() == "modifier" && text(c) == "this");
+                if let (true, Some(this_type)) =
+                    (is_extension, parameter.child_by_field_name("type"))
+                {
+                    let this_type = text(this_type);
+                    let this_type = strip_generic_arguments(&this_type);
+                    let this_type = this_type.rsplit('.').next().unwrap_or(this_type);
+                    index
+                        .extension_methods
+                        .entry(text(name))
+                        .or_default()
+                        .push((
+                            find_csharp_class_name(&node, source_code),
+                            this_type.to_string(),
+                        ));
+                }
+            }
+        }
+        _ => {}
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        index_csharp_types(child, source_code, index);
+    }
+}
+
+/// Resolves the calls made by C# tests against the candidate logic methods. Calls
+/// through a variable declared as an interface mark the implementing methods as
+/// covered via interface instead of covering every method sharing the name.
+pub fn extract_csharp_tested_methods(
+    test_methods: &[&Method],
+    candidates: &[&Method],
+    index: &CsharpTypeIndex,
+) -> TestedMethods {
+    let logic_method_names: HashSet<String> =
+        candidates.iter().map(|m| m.method_name.clone()).collect();
+    // (called method, test), (declaring class, called method, test) and
+    // (interface, called member, test)
+    let mut called = Vec::new();
+    let mut called_exact = Vec::new();
+    let mut called_via_interface = Vec::new();
+
+    for method in test_methods {
+        let test_name = format!("{}.{}", method.class_name, method.method_name);
+        let receivers = csharp_receiver_types(&method.body);
+
+        for line in &method.body {
+            if normalize_line(line).contains("Assert.") {
+                let mut assert_targets = Vec::new();
+                extract_csharp_assert_targets(line, &logic_method_names, &mut assert_targets);
+                called.extend(assert_targets.into_iter().map(|m| (m, test_name.clone())));
+            }
+
+            for (qualifier, method_name) in csharp_invocations(line) {
+                if !logic_method_names.contains(&method_name) {
+                    continue;
+                }
+
+                let receiver_type = receivers.get(&qualifier);
+                let extension_classes = receiver_type
+                    .map(|receiver_type| index.extension_classes(&method_name, receiver_type))
+                    .unwrap_or_default();
+                if !extension_classes.is_empty() {
+                    called_exact.extend(extension_classes.into_iter().map(|class_name| {
+                        (
+                            class_name.to_string(),
+                            method_name.clone(),
+                            test_name.clone(),
+                        )
+                    }));
+                    continue;
+                }
+
+                match receiver_type {
+                    Some(receiver_type) if index.interfaces.contains(receiver_type) => {
+                        called_via_interface.push((
+                            receiver_type.clone(),
+                            method_name,
+                            test_name.clone(),
+                        ));
+                    }
+                    _ => called.push((method_name, test_name.clone())),
+                }
+            }
+        }
+    }
+
+    let mut tested_methods = TestedMethods::default();
+    for method in candidates {
+        for (name, test_name) in &called {
+            if *name == method.method_name {
+                tested_methods.record(CallTarget::exact(method), test_name);
+            }
+        }
+        for (class_name, name, test_name) in &called_exact {
+            if *class_name == method.class_name && *name == method.method_name {
+                tested_methods.record(CallTarget::exact(method), test_name);
+            }
+        }
+        for (interface, name, test_name) in &called_via_interface {
+            if *name == method.method_name && index.implements(&method.class_name, interface) {
+                tested_methods.record_via_interface(CallTarget::exact(method), test_name);
+            }
+        }
+    }
+
+    tested_methods
 }
 
 pub fn extract_csharp_assert_targets(
@@ -116,47 +433,28 @@ pub fn extract_csharp_assert_targets(
     tested_methods: &mut Vec<String>,
 ) {
     // Common C# assertion patterns: Assert.Equal(expected, actual), Assert.True(condition), etc.
+    // Invocations inside the arguments are handled by csharp_invocations, this only
+    // picks up methods passed as method groups, e.g. Assert.Throws<T>(calc.Divide)
     if let Some(args_start) = line.find('(') {
         if let Some(args_end) = line.rfind(')') {
             let args = &line[args_start + 1..args_end];
 
-            // Split arguments by comma and check each one for function calls
             for arg in args.split(',') {
-                // Look for method invocations inside arguments
-                if let Some(inner_call_end) = arg.rfind(')') {
-                    if let Some(inner_call_start) = arg[..inner_call_end].rfind('(') {
-                        let potential_method = arg[..inner_call_start].trim();
-
-                        // Handle obj.Method() pattern
-                        let method_name = if potential_method.contains('.') {
-                            potential_method.split('.').last().unwrap_or("").trim()
-                        } else {
-                            potential_method
-                        };
-
-                        if !method_name.is_empty() && logic_method_names.contains(method_name) {
-                            tested_methods.push(method_name.to_string());
-                        }
-                    }
-                }
-
-                // Also check direct method references
                 let trimmed_arg = arg.trim();
-                if logic_method_names.contains(trimmed_arg) {
-                    tested_methods.push(trimmed_arg.to_string());
+                let method_name = trimmed_arg.rsplit('.').next().unwrap_or("");
+                if logic_method_names.contains(method_name) {
+                    tested_methods.push(method_name.to_string());
                 }
             }
         }
     }
 }
 
-pub fn extract_csharp_method_calls(
-    line: &str,
-    logic_method_names: &HashSet<String>,
-    tested_methods: &mut Vec<String>,
-) {
-    // Look for all potential method calls in the line
-    // Pattern: something like "result = target.Method()" or just "Method()"
+// Finds every "target.Method(" or "Method(" in a line, returning the identifier
+// right before the last dot (empty when unqualified) and the method name
+fn csharp_invocations(line: &str) -> Vec<(String, String)> {
+    let is_identifier = |c: char| c.is_alphanumeric() || c == '_';
+    let mut invocations = Vec::new();
     let mut start_idx = 0;
 
     while let Some(paren_idx) = line[start_idx..].find('(') {
@@ -165,22 +463,284 @@ pub fn extract_csharp_method_calls(
 
         // Get the method name part
         let method_part = if call_substr.contains('=') {
-            call_substr.split('=').last().unwrap().trim()
+            call_substr.split('=').next_back().unwrap().trim()
         } else {
             call_substr.trim()
         };
 
-        // Handle obj.Method pattern
-        let method_name = if method_part.contains('.') {
-            method_part.split('.').last().unwrap_or("").trim()
-        } else {
-            method_part
+        let (qualifier, method_part) = match method_part.rsplit_once('.') {
+            Some((qualifier, method)) => (qualifier.rsplit(|c| !is_identifier(c)).next(), method),
+            None => (None, method_part),
         };
+        let method_name = method_part
+            .rsplit(|c| !is_identifier(c))
+            .next()
+            .unwrap_or("");
 
-        if !method_name.is_empty() && logic_method_names.contains(method_name) {
-            tested_methods.push(method_name.to_string());
+        if !method_name.is_empty() {
+            invocations.push((qualifier.unwrap_or("").to_string(), method_name.to_string()));
         }
 
         start_idx = actual_paren_idx + 1;
     }
+
+    invocations
+}
+
+// Maps local variables to their declared type, e.g. "IService svc = ..." or
+// "var svc = new Service()"
+fn csharp_receiver_types(body: &[String]) -> HashMap<String, String> {
+    let mut receivers = HashMap::new();
+
+    for line in body {
+        let Some((declaration, value)) = line.trim().split_once('=') else {
+            continue;
+        };
+        // Skip comparisons, lambdas and compound assignments
+        let declaration = declaration.trim_end();
+        if declaration.contains('(')
+            || value.starts_with(['=', '>'])
+            || declaration.ends_with(['!', '<', '>', '+', '-', '*', '/', '%', '|', '&', '?'])
+        {
+            continue;
+        }
+
+        let declaration = remove_generic_arguments(declaration);
+        let parts: Vec<&str> = declaration.split_whitespace().collect();
+        if parts.len() < 2 {
+            continue;
+        }
+        let variable = parts[parts.len() - 1];
+        let mut declared_type = parts[parts.len() - 2];
+
+        if declared_type == "var" {
+            match value.trim_start().strip_prefix("new ") {
+                Some(constructed) => {
+                    declared_type = constructed
+                        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+                        .next()
+                        .unwrap_or("");
+                }
+                None => continue,
+            }
+        }
+
+        let declared_type = strip_generic_arguments(declared_type);
+        let declared_type = declared_type.rsplit('.').next().unwrap_or(declared_type);
+        if !declared_type.is_empty() {
+            receivers.insert(variable.to_string(), declared_type.to_string());
+        }
+    }
+
+    receivers
+}
+
+// "Dictionary<string, int> map" -> "Dictionary map"
+fn remove_generic_arguments(text: &str) -> String {
+    let mut depth = 0;
+    text.chars()
+        .filter(|c| {
+            match c {
+                '<' => depth += 1,
+                '>' => depth -= 1,
+                _ => return depth == 0,
+            }
+            false
+        })
+        .collect()
+}
+
+fn strip_generic_arguments(type_name: &str) -> &str {
+    type_name.split('<').next().unwrap_or(type_name).trim()
+}
+
+#[test]
+fn test_is_generated_csharp_file() {
+    let repo = Path::new("/home/obj/repo");
+    assert!(is_generated_csharp_file(
+        repo,
+        Path::new("/home/obj/repo/src/App/MainForm.Designer.cs"),
+        ""
+    ));
+    assert!(is_generated_csharp_file(
+        repo,
+        Path::new("/home/obj/repo/src/App/View.g.i.cs"),
+        ""
+    ));
+    assert!(is_generated_csharp_file(
+        repo,
+        Path::new("/home/obj/repo/src/App/obj/Debug/net8.0/App.AssemblyInfo.cs"),
+        ""
+    ));
+    assert!(is_generated_csharp_file(
+        repo,
+        Path::new("/home/obj/repo/src/App/Client.cs"),
+        "// <auto-generated>\n//     This code was generated by a tool.\n// </auto-generated>\nclass Client {}"
+    ));
+    // Only the repo's own obj/ directories hold generated code
+    assert!(!is_generated_csharp_file(
+        repo,
+        Path::new("/home/obj/repo/src/App/Calculator.cs"),
+        "// Calculator helpers\nclass Calculator {}"
+    ));
+}
+
+#[test]
+fn test_traverse_skips_generated_members() {
+    use crate::utils::get_parser;
+
+    let source_code = r#"
+public class Calculator
+{
+    public int Add(int a, int b) { return a + b; }
+
+    [System.CodeDom.Compiler.GeneratedCode("tool", "1.0")]
+    public int Generated() { return 0; }
+
+    [GeneratedCodeAnalysis]
+    public int Analyzed() { return 0; }
+}
+
+[CompilerGenerated]
+internal class Hidden
+{
+    public void Run() { }
+}
+"#;
+    let mut parser = get_parser("cs");
+    let tree = parser.parse(source_code, None).unwrap();
+    let lang_settings = LangSettings {
+        ext: String::from("cs"),
+        extensions: vec![String::from("cs")],
+        uses_classes: true,
+        test_pattern: String::from("[Fact]"),
+        test_method_start: String::from("Public"),
+    };
+
+    let mut methods = Vec::new();
+    traverse_c_sharp_nodes(
+        tree.root_node(),
+        source_code,
+        "Calculator.cs",
+        &lang_settings,
+        false,
+        &mut methods,
+    );
+    let names: Vec<&str> = methods.iter().map(|m| m.method_name.as_str()).collect();
+    assert_eq!(names, vec!["Add", "Analyzed"]);
+
+    let mut methods = Vec::new();
+    traverse_c_sharp_nodes(
+        tree.root_node(),
+        source_code,
+        "Calculator.cs",
+        &lang_settings,
+        true,
+        &mut methods,
+    );
+    assert_eq!(methods.len(), 4);
+}
+
+#[test]
+fn test_extract_csharp_tested_methods_via_interface() {
+    use crate::utils::CoverageStatus;
+
+    let method = |class_name: &str, name: &str, body: &[&str], is_test: bool| Method {
+        class_name: class_name.to_string(),
+        method_name: name.to_string(),
+        body: body.iter().map(|l| l.to_string()).collect(),
+        start_line: 1,
+        is_test,
+        file_path: format!("{}.cs", class_name),
+    };
+    let methods = [
+        method("EmailService", "Send", &[], false),
+        method("SmsService", "Send", &[], false),
+        method("Logger", "Write", &[], false),
+        method("NotifierExtensions", "Shuffle", &[], false),
+        method("ListExtensions", "Shuffle", &[], false),
+        method(
+            "NotifierTests",
+            "SendsMessage",
+            &[
+                "INotifier notifier = new EmailService();",
+                "var logger = new Logger();",
+                "notifier.Send(\"hi\");",
+                "notifier.Shuffle();",
+                "Assert.True(logger.Write(\"done\"));",
+            ],
+            true,
+        ),
+    ];
+
+    let mut index = CsharpTypeIndex::default();
+    index.interfaces.insert(String::from("INotifier"));
+    index.base_types.insert(
+        String::from("EmailService"),
+        vec![String::from("INotifier")],
+    );
+    index
+        .base_types
+        .insert(String::from("SmsService"), vec![String::from("INotifier")]);
+    index.extension_methods.insert(
+        String::from("Shuffle"),
+        vec![
+            (
+                String::from("NotifierExtensions"),
+                String::from("INotifier"),
+            ),
+            (String::from("ListExtensions"), String::from("List")),
+        ],
+    );
+
+    let tests: Vec<&Method> = methods.iter().filter(|m| m.is_test).collect();
+    let candidates: Vec<&Method> = methods.iter().filter(|m| !m.is_test).collect();
+    let tested = extract_csharp_tested_methods(&tests, &candidates, &index);
+
+    assert_eq!(tested.status(&methods[0]), CoverageStatus::ViaInterface);
+    assert_eq!(tested.status(&methods[1]), CoverageStatus::ViaInterface);
+    assert_eq!(tested.status(&methods[2]), CoverageStatus::Covered);
+    // The extension on INotifier, not the one sharing its name on List
+    assert_eq!(tested.status(&methods[3]), CoverageStatus::Covered);
+    assert_eq!(tested.status(&methods[4]), CoverageStatus::Uncovered);
+}
+
+#[test]
+fn test_build_csharp_type_index() {
+    use tempfile::tempdir;
+
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    fs::write(
+        temp_dir.path().join("Services.cs"),
+        r#"
+public interface IService : IDisposable { void Run(); }
+public class Service : BaseService, IService { public void Run() { } }
+public static class ServiceExtensions
+{
+    public static void RunTwice(this IService service) { service.Run(); service.Run(); }
+}
+"#,
+    )
+    .unwrap();
+
+    let repo = Command {
+        repo: temp_dir.path().to_string_lossy().to_string(),
+        ..Default::default()
+    };
+    let index = build_csharp_type_index(&repo);
+
+    assert!(index.interfaces.contains("IService"));
+    assert!(index.implements("Service", "IService"));
+    assert!(!index.implements("ServiceExtensions", "IService"));
+    assert_eq!(
+        index.extension_methods["RunTwice"],
+        vec![(String::from("ServiceExtensions"), String::from("IService"))]
+    );
+    assert_eq!(
+        index.extension_classes("RunTwice", "Service"),
+        vec!["ServiceExtensions"]
+    );
+    assert!(index
+        .extension_classes("RunTwice", "BaseService")
+        .is_empty());
 }
@@ -0,0 +1,305 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::csharp::{extract_csharp_tested_methods, CsharpTypeIndex};
+use crate::utils::{normalize_path, should_skip_dir, Method, TestedMethods};
+
+// Package references that mark a project as a test project
+const TEST_PACKAGES: [&str; 6] = [
+    "Microsoft.NET.Test.Sdk",
+    "xunit",
+    "xunit.core",
+    "NUnit",
+    "MSTest.TestFramework",
+    "MSTest",
+];
+
+#[derive(PartialEq, Debug)]
+pub struct DotnetProject {
+    pub name: String,
+    pub path: PathBuf,
+    pub is_test: bool,
+    pub references: Vec<PathBuf>,
+}
+
+impl DotnetProject {
+    pub fn dir(&self) -> &Path {
+        self.path.parent().unwrap_or(Path::new(""))
+    }
+}
+
+/// Finds the projects in a repo: the ones listed in its solution files, in solution
+/// order, followed by any other `.csproj` found on disk.
+pub fn discover_projects(repo: &str) -> Vec<DotnetProject> {
+    let mut solutions = Vec::new();
+    let mut csprojs = Vec::new();
+
+    for entry in WalkDir::new(repo)
+        .into_iter()
+        .filter_entry(|e| !should_skip_dir(e))
+        .filter_map(Result::ok)
+    {
+        match entry.path().extension().and_then(|ext| ext.to_str()) {
+            Some("sln") | Some("slnx") => solutions.push(entry.path().to_path_buf()),
+            Some("csproj") => csprojs.push(normalize_path(entry.path())),
+            _ => {}
+        }
+    }
+
+    let mut project_paths: Vec<PathBuf> = Vec::new();
+    // Projects a solution leaves out still own the sources next to them
+    for project in solutions
+        .iter()
+        .flat_map(|s| parse_solution(s))
+        .chain(csprojs)
+    {
+        if !project_paths.contains(&project) {
+            project_paths.push(project);
+        }
+    }
+
+    project_paths
+        .iter()
+        .filter_map(|path| parse_project(path))
+        .collect()
+}
+
+/// Reads the C# project paths out of a `.sln` or `.slnx` file.
+pub fn parse_solution(path: &Path) -> Vec<PathBuf> {
+    let solution_dir = path.parent().unwrap_or(Path::new(""));
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut relative_paths = Vec::new();
+    if path.extension().is_some_and(|ext| ext == "slnx") {
+        if let Ok(doc) = roxmltree::Document::parse(&contents) {
+            for node in doc.descendants().filter(|n| n.has_tag_name("Project")) {
+                if let Some(project_path) = node.attribute("Path") {
+                    relative_paths.push(project_path.to_string());
+                }
+            }
+        }
+    } else {
+        // Project("{type-guid}") = "Name", "relative\path\Name.csproj", "{project-guid}"
+        for line in contents.lines() {
+            let line = line.trim();
+            if !line.starts_with("Project(") {
+                continue;
+            }
+            if let Some((_, values)) = line.split_once('=') {
+                if let Some(project_path) = values.split(',').nth(1) {
+                    relative_paths.push(project_path.trim().trim_matches('"').to_string());
+                }
+            }
+        }
+    }
+
+    relative_paths
+        .iter()
+        .filter(|p| p.ends_with(".csproj"))
+        .map(|p| normalize_path(&solution_dir.join(p.replace('\\', "/"))))
+        .collect()
+}
+
+pub fn parse_project(path: &Path) -> Option<DotnetProject> {
+    let contents = fs::read_to_string(path).ok()?;
+    let doc = roxmltree::Document::parse(&contents).ok()?;
+    let project_dir = path.parent().unwrap_or(Path::new(""));
+
+    let mut is_test = doc
+        .root_element()
+        .attribute("Sdk")
+        .is_some_and(|sdk| sdk.starts_with("MSTest.Sdk"));
+    let mut references = Vec::new();
+
+    for node in doc.descendants().filter(|n| n.is_element()) {
+        match node.tag_name().name() {
+            "PackageReference" => {
+                let package = node
+                    .attribute("Include")
+                    .or_else(|| node.attribute("Update"))
+                    .unwrap_or("");
+                if TEST_PACKAGES
+                    .iter()
+                    .any(|p| package.eq_ignore_ascii_case(p))
+                {
+                    is_test = true;
+                }
+            }
+            "IsTestProject" if node.text().is_some_and(|t| t.trim() == "true") => {
+                is_test = true;
+            }
+            "ProjectReference" => {
+                if let Some(include) = node.attribute("Include") {
+                    references.push(normalize_path(
+                        &project_dir.join(include.replace('\\', "/")),
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Some(DotnetProject {
+        name: path.file_stem()?.to_string_lossy().to_string(),
+        path: path.to_path_buf(),
+        is_test,
+        references,
+    })
+}
+
+/// Returns the project owning a source file, preferring the most deeply nested one.
+pub fn project_for_file<'a>(
+    projects: &'a [DotnetProject],
+    file_path: &str,
+) -> Option<&'a DotnetProject> {
+    let file_path = normalize_path(Path::new(file_path));
+    projects
+        .iter()
+        .filter(|p| file_path.starts_with(p.dir()))
+        .max_by_key(|p| p.dir().components().count())
+}
+
+/// Resolves test calls per test project, so a test project only covers methods
+/// declared in the projects it references.
+pub fn extract_project_tested_methods(
+    projects: &[DotnetProject],
+    methods: &[Method],
+    index: &CsharpTypeIndex,
+) -> TestedMethods {
+    let mut tested_methods = TestedMethods::default();
+
+    for test_project in projects.iter().filter(|p| p.is_test) {
+        let tests: Vec<&Method> = methods
+            .iter()
+            .filter(|m| m.is_test && project_for_file(projects, &m.file_path) == Some(test_project))
+            .collect();
+
+        let candidates: Vec<&Method> = methods
+            .iter()
+            .filter(|m| !m.is_test)
+            .filter(|m| {
+                project_for_file(projects, &m.file_path)
+                    .is_some_and(|p| test_project.references.contains(&p.path))
+            })
+            .collect();
+
+        tested_methods.extend(extract_csharp_tested_methods(&tests, &candidates, index));
+    }
+
+    tested_methods
+}
+
+#[test]
+fn test_parse_solution_and_projects() {
+    use std::fs::create_dir_all;
+    use tempfile::tempdir;
+
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let root = temp_dir.path();
+    create_dir_all(root.join("src/App")).unwrap();
+    create_dir_all(root.join("tests/App.Tests")).unwrap();
+    create_dir_all(root.join("tools/Seeder")).unwrap();
+
+    fs::write(
+        root.join("App.sln"),
+        r#"
+Microsoft Visual Studio Solution File, Format Version 12.00
+Project("{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC}") = "App", "src\App\App.csproj", "{11111111-1111-1111-1111-111111111111}"
+EndProject
+Project("{FAE04EC0-301F-11D3-BF4B-00C04F79EFBC}") = "App.Tests", "tests\App.Tests\App.Tests.csproj", "{22222222-2222-2222-2222-222222222222}"
+EndProject
+"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("src/App/App.csproj"),
+        r#"<Project Sdk="Microsoft.NET.Sdk"></Project>"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("tests/App.Tests/App.Tests.csproj"),
+        r#"<Project Sdk="Microsoft.NET.Sdk">
+  <ItemGroup>
+    <PackageReference Include="Microsoft.NET.Test.Sdk" Version="17.0.0" />
+    <PackageReference Include="xunit" Version="2.4.0" />
+  </ItemGroup>
+  <ItemGroup>
+    <ProjectReference Include="..\..\src\App\App.csproj" />
+  </ItemGroup>
+</Project>"#,
+    )
+    .unwrap();
+    // Not in the solution
+    fs::write(
+        root.join("tools/Seeder/Seeder.csproj"),
+        r#"<Project Sdk="Microsoft.NET.Sdk"></Project>"#,
+    )
+    .unwrap();
+
+    let projects = discover_projects(&root.to_string_lossy());
+
+    assert_eq!(projects.len(), 3);
+    assert_eq!(projects[2].name, "Seeder");
+    assert_eq!(projects[0].name, "App");
+    assert!(!projects[0].is_test);
+    assert_eq!(projects[1].name, "App.Tests");
+    assert!(projects[1].is_test);
+    assert_eq!(projects[1].references, vec![projects[0].path.clone()]);
+
+    let file = root.join("src/App/Calculator.cs");
+    assert_eq!(
+        project_for_file(&projects, &file.to_string_lossy()).map(|p| p.name.as_str()),
+        Some("App")
+    );
+}
+
+#[test]
+fn test_extract_project_tested_methods_respects_references() {
+    let projects = vec![
+        DotnetProject {
+            name: String::from("App"),
+            path: PathBuf::from("/repo/App/App.csproj"),
+            is_test: false,
+            references: Vec::new(),
+        },
+        DotnetProject {
+            name: String::from("Other"),
+            path: PathBuf::from("/repo/Other/Other.csproj"),
+            is_test: false,
+            references: Vec::new(),
+        },
+        DotnetProject {
+            name: String::from("App.Tests"),
+            path: PathBuf::from("/repo/App.Tests/App.Tests.csproj"),
+            is_test: true,
+            references: vec![PathBuf::from("/repo/App/App.csproj")],
+        },
+    ];
+    let method = |file: &str, name: &str, body: &str, is_test: bool| Method {
+        class_name: String::new(),
+        method_name: name.to_string(),
+        body: vec![body.to_string()],
+        start_line: 1,
+        is_test,
+        file_path: file.to_string(),
+    };
+    let methods = vec![
+        method("/repo/App/Calc.cs", "Add", "", false),
+        method("/repo/Other/Calc.cs", "Add", "", false),
+        method(
+            "/repo/App.Tests/CalcTests.cs",
+            "AddWorks",
+            "var result = calc.Add(1, 2);",
+            true,
+        ),
+    ];
+
+    let tested = extract_project_tested_methods(&projects, &methods, &CsharpTypeIndex::default());
+
+    assert!(tested.covers(&methods[0]));
+    assert!(!tested.covers(&methods[1]));
+}
@@ -0,0 +1,645 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::utils::{
+    extract_body, get_parser, normalize_path, should_skip_dir, CallTarget, Method, TestedMethods,
+};
+
+// Test function prefixes and the `testing` type their single parameter must have
+const GO_TEST_KINDS: [(&str, &str); 3] = [("Test", "T"), ("Benchmark", "B"), ("Fuzz", "F")];
+
+pub fn is_go_test_file(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().ends_with("_test.go"))
+}
+
+/// Collects top-level functions, and methods named after their receiver type.
+pub fn traverse_go_nodes(
+    node: tree_sitter::Node,
+    source_code: &str,
+    file_path: &str,
+    is_test_file: bool,
+    methods: &mut Vec<Method>,
+) {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        let class_name = match child.kind() {
+            "function_declaration" => String::new(),
+            "method_declaration" => child
+                .child_by_field_name("receiver")
+                .and_then(|receiver| find_receiver_type(receiver, source_code))
+                .unwrap_or_default(),
+            _ => continue,
+        };
+        if let Some(name) = child.child_by_field_name("name") {
+            methods.push(Method {
+                class_name,
+                method_name: text(name),
+                body: extract_body(child, source_code),
+                start_line: child.start_position().row + 1,
+                // Helpers in _test.go files aren't logic to be covered
+                is_test: is_test_file,
+                file_path: file_path.to_string(),
+            });
+        }
+    }
+}
+
+// `(s *Stack[T])` gives "Stack"
+fn find_receiver_type(node: tree_sitter::Node, source_code: &str) -> Option<String> {
+    if node.kind() == "type_identifier" {
+        return Some(source_code[node.start_byte()..node.end_byte()].to_string());
+    }
+    let mut cursor = node.walk();
+    let found = node
+        .named_children(&mut cursor)
+        .find_map(|child| find_receiver_type(child, source_code));
+    found
+}
+
+// A test function or named subtest, with the calls made directly inside it
+#[derive(PartialEq, Debug)]
+pub struct GoTestCase {
+    pub name: String,
+    pub calls: Vec<GoCall>,
+}
+
+// `Reverse()` has no qualifier, `strutil.Reverse()` and `stack.Push()` do
+#[derive(PartialEq, Debug)]
+pub struct GoCall {
+    pub qualifier: Option<String>,
+    pub name: String,
+}
+
+/// Finds `TestXxx(t *testing.T)`, `BenchmarkXxx(b *testing.B)`, `FuzzXxx(f *testing.F)`
+/// and `ExampleXxx()` functions in a test file. Subtests started with `t.Run` and a
+/// literal name are reported as `TestXxx/name`, the way `go test` names them. Tests
+/// and subtests that always skip themselves are left out.
+pub fn collect_go_test_cases(
+    node: tree_sitter::Node,
+    source_code: &str,
+    test_cases: &mut Vec<GoTestCase>,
+) {
+    let mut cursor = node.walk();
+    for function in node
+        .named_children(&mut cursor)
+        .filter(|n| n.kind() == "function_declaration")
+    {
+        if let (Some(name), Some(body)) = (
+            function.child_by_field_name("name"),
+            function.child_by_field_name("body"),
+        ) {
+            let name = &source_code[name.start_byte()..name.end_byte()];
+            if is_go_test_function(function, name, source_code) {
+                collect_go_subtest(body, source_code, name.to_string(), test_cases);
+            }
+        }
+    }
+}
+
+fn is_go_test_function(function: tree_sitter::Node, name: &str, source_code: &str) -> bool {
+    let params: Vec<String> = function
+        .child_by_field_name("parameters")
+        .map(|list| {
+            let mut cursor = list.walk();
+            list.named_children(&mut cursor)
+                .filter_map(|p| p.child_by_field_name("type"))
+                .map(|t| source_code[t.start_byte()..t.end_byte()].replace(' ', ""))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    // `TestFoo` and `Test` are tests, `Testimony` is not
+    let has_prefix = |prefix: &str| {
+        name.strip_prefix(prefix)
+            .is_some_and(|rest| !rest.starts_with(|c: char| c.is_lowercase()))
+    };
+
+    if has_prefix("Example") {
+        return params.is_empty();
+    }
+    GO_TEST_KINDS.iter().any(|(prefix, testing_type)| {
+        has_prefix(prefix)
+            && params.len() == 1
+            && params[0].starts_with('*')
+            && params[0].ends_with(&format!(".{}", testing_type))
+    })
+}
+
+fn collect_go_subtest(
+    body: tree_sitter::Node,
+    source_code: &str,
+    name: String,
+    test_cases: &mut Vec<GoTestCase>,
+) {
+    if is_skipped_go_test(body, source_code) {
+        return;
+    }
+    let mut calls = Vec::new();
+    let mut subtests = Vec::new();
+    collect_go_calls(body, source_code, &mut calls, &mut subtests);
+
+    test_cases.push(GoTestCase {
+        name: name.clone(),
+        calls,
+    });
+    for (subtest_name, subtest_body) in subtests {
+        collect_go_subtest(
+            subtest_body,
+            source_code,
+            format!("{}/{}", name, subtest_name.replace(' ', "_")),
+            test_cases,
+        );
+    }
+}
+
+// `t.Skip()`, `t.Skipf()` or `t.SkipNow()` as a statement of the test's own body,
+// rather than under a condition such as `testing.Short()`
+fn is_skipped_go_test(body: tree_sitter::Node, source_code: &str) -> bool {
+    let mut cursor = body.walk();
+    let skipped = body.named_children(&mut cursor).any(|statement| {
+        statement.kind() == "expression_statement"
+            && statement
+                .named_child(0)
+                .filter(|call| call.kind() == "call_expression")
+                .and_then(|call| call.child_by_field_name("function"))
+                .filter(|function| function.kind() == "selector_expression")
+                .and_then(|function| function.child_by_field_name("field"))
+                .is_some_and(|field| {
+                    matches!(
+                        &source_code[field.byte_range()],
+                        "Skip" | "Skipf" | "SkipNow"
+                    )
+                })
+    });
+    skipped
+}
+
+// Records every call in a node, leaving the bodies of literally named subtests to be
+// collected on their own. Functions referenced as values in composite literals, as
+// test tables do, count as calls too.
+fn collect_go_calls<'a>(
+    node: tree_sitter::Node<'a>,
+    source_code: &str,
+    calls: &mut Vec<GoCall>,
+    subtests: &mut Vec<(String, tree_sitter::Node<'a>)>,
+) {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+
+    let callee = match node.kind() {
+        "call_expression" => {
+            if let Some((name, body)) = go_subtest(node, source_code) {
+                subtests.push((name, body));
+                return;
+            }
+            node.child_by_field_name("function")
+        }
+        "literal_element" => node.named_child(0),
+        _ => None,
+    };
+
+    let call = callee.and_then(|callee| match callee.kind() {
+        "identifier" => Some(GoCall {
+            qualifier: None,
+            name: text(callee),
+        }),
+        "selector_expression" => {
+            let operand = callee.child_by_field_name("operand")?;
+            Some(GoCall {
+                qualifier: Some(text(operand)),
+                name: text(callee.child_by_field_name("field")?),
+            })
+        }
+        _ => None,
+    });
+    if let Some(call) = call {
+        if !calls.contains(&call) {
+            calls.push(call);
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_go_calls(child, source_code, calls, subtests);
+    }
+}
+
+// `t.Run("name", func(t *testing.T) { ... })` gives the name and the function body
+fn go_subtest<'a>(
+    call: tree_sitter::Node<'a>,
+    source_code: &str,
+) -> Option<(String, tree_sitter::Node<'a>)> {
+    let function = call.child_by_field_name("function")?;
+    if function.kind() != "selector_expression"
+        || function
+            .child_by_field_name("field")
+            .is_none_or(|f| &source_code[f.byte_range()] != "Run")
+    {
+        return None;
+    }
+
+    let arguments = call.child_by_field_name("arguments")?;
+    let (name, body) = (arguments.named_child(0)?, arguments.named_child(1)?);
+    if !matches!(
+        name.kind(),
+        "interpreted_string_literal" | "raw_string_literal"
+    ) || body.kind() != "func_literal"
+    {
+        return None;
+    }
+    let name = source_code[name.start_byte()..name.end_byte()]
+        .trim_matches(['"', '`'])
+        .to_string();
+    Some((name, body.child_by_field_name("body")?))
+}
+
+/// Resolves the calls made by Go tests. Unqualified calls reach functions in the test's
+/// own package (and dot imports), `pkg.Func()` reaches functions in the imported
+/// package, and other selector calls reach methods of either.
+pub fn extract_go_tested_methods(repo: &str, logic_methods: &[Method]) -> TestedMethods {
+    let mut tested_methods = TestedMethods::default();
+    let modules = find_go_modules(repo);
+
+    let mut methods_by_dir: HashMap<PathBuf, Vec<&Method>> = HashMap::new();
+    for method in logic_methods.iter().filter(|m| !m.is_test) {
+        methods_by_dir
+            .entry(package_dir(&method.file_path))
+            .or_default()
+            .push(method);
+    }
+
+    for entry in WalkDir::new(repo)
+        .into_iter()
+        .filter_entry(|e| !should_skip_dir(e))
+        .filter_map(Result::ok)
+        .filter(|e| is_go_test_file(e.path()))
+    {
+        let Ok(source_code) = fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let mut parser = get_parser("go");
+        let Some(tree) = parser.parse(&source_code, None) else {
+            continue;
+        };
+
+        let own_dir = package_dir(&entry.path().to_string_lossy());
+        let mut unqualified_dirs = vec![own_dir];
+        let mut imports = HashMap::new();
+        for (alias, import_path) in collect_go_imports(tree.root_node(), &source_code) {
+            if let Some(dir) = resolve_go_import(&modules, &import_path) {
+                match alias.as_str() {
+                    "." => unqualified_dirs.push(dir),
+                    "_" => {}
+                    _ => {
+                        imports.insert(alias, dir);
+                    }
+                }
+            }
+        }
+
+        let mut test_cases = Vec::new();
+        collect_go_test_cases(tree.root_node(), &source_code, &mut test_cases);
+
+        for test_case in test_cases {
+            for call in &test_case.calls {
+                let (dirs, is_method): (Vec<&PathBuf>, bool) = match &call.qualifier {
+                    None => (unqualified_dirs.iter().collect(), false),
+                    Some(package) if imports.contains_key(package) => {
+                        (vec![&imports[package]], false)
+                    }
+                    Some(_) => (
+                        unqualified_dirs.iter().chain(imports.values()).collect(),
+                        true,
+                    ),
+                };
+
+                for method in dirs
+                    .into_iter()
+                    .filter_map(|dir| methods_by_dir.get(dir))
+                    .flatten()
+                    .filter(|m| m.method_name == call.name && m.class_name.is_empty() != is_method)
+                {
+                    tested_methods.record(CallTarget::exact(method), &test_case.name);
+                }
+            }
+        }
+    }
+
+    tested_methods
+}
+
+fn package_dir(file_path: &str) -> PathBuf {
+    normalize_path(Path::new(file_path))
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default()
+}
+
+// Module paths declared by every go.mod in the repo, with their directories
+fn find_go_modules(repo: &str) -> Vec<(String, PathBuf)> {
+    let mut modules = Vec::new();
+    for entry in WalkDir::new(repo)
+        .into_iter()
+        .filter_entry(|e| !should_skip_dir(e))
+        .filter_map(Result::ok)
+        .filter(|e| e.file_name() == "go.mod")
+    {
+        if let Ok(contents) = fs::read_to_string(entry.path()) {
+            let module_path = contents
+                .lines()
+                .find_map(|line| line.trim().strip_prefix("module "))
+                .map(|m| m.trim().trim_matches('"').to_string());
+            if let Some(module_path) = module_path {
+                modules.push((module_path, package_dir(&entry.path().to_string_lossy())));
+            }
+        }
+    }
+    modules
+}
+
+fn resolve_go_import(modules: &[(String, PathBuf)], import_path: &str) -> Option<PathBuf> {
+    modules
+        .iter()
+        .filter_map(|(module_path, dir)| {
+            let rest = import_path.strip_prefix(module_path.as_str())?;
+            if rest.is_empty() {
+                Some((module_path.len(), dir.clone()))
+            } else {
+                rest.strip_prefix('/')
+                    .map(|rest| (module_path.len(), normalize_path(&dir.join(rest))))
+            }
+        })
+        // Nested modules win over the module containing them
+        .max_by_key(|(len, _)| *len)
+        .map(|(_, dir)| dir)
+}
+
+// (name the package is referred to by, import path) for each import
+fn collect_go_imports(node: tree_sitter::Node, source_code: &str) -> Vec<(String, String)> {
+    let mut imports = Vec::new();
+    if node.kind() == "import_spec" {
+        if let Some(path) = node.child_by_field_name("path") {
+            let import_path = source_code[path.start_byte()..path.end_byte()]
+                .trim_matches(['"', '`'])
+                .to_string();
+            let alias = match node.child_by_field_name("name") {
+                Some(name) => source_code[name.start_byte()..name.end_byte()].to_string(),
+                None => default_package_name(&import_path),
+            };
+            imports.push((alias, import_path));
+        }
+        return imports;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        imports.extend(collect_go_imports(child, source_code));
+    }
+    imports
+}
+
+// The last path element, skipping a major version suffix such as `/v2`
+fn default_package_name(import_path: &str) -> String {
+    let mut segments = import_path.rsplit('/');
+    let last = segments.next().unwrap_or_default();
+    let is_version =
+        last.len() > 1 && last.starts_with('v') && last[1..].chars().all(|c| c.is_ascii_digit());
+    match segments.next() {
+        Some(previous) if is_version => previous.to_string(),
+        _ => last.to_string(),
+    }
+}
+
+#[test]
+fn test_traverse_go_nodes() {
+    let source_code = r#"
+package stack
+
+func New() *Stack[int] { return &Stack[int]{} }
+
+func (s *Stack[T]) Push(v T) { s.items = append(s.items, v) }
+
+func (q Queue) Len() int { return len(q) }
+"#;
+    let mut parser = get_parser("go");
+    let tree = parser.parse(source_code, None).unwrap();
+
+    let mut methods = Vec::new();
+    traverse_go_nodes(
+        tree.root_node(),
+        source_code,
+        "stack.go",
+        false,
+        &mut methods,
+    );
+
+    let found: Vec<(&str, &str)> = methods
+        .iter()
+        .map(|m| (m.class_name.as_str(), m.method_name.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![("", "New"), ("Stack", "Push"), ("Queue", "Len")]
+    );
+}
+
+#[test]
+fn test_extract_go_tested_methods() {
+    use std::fs::create_dir_all;
+    use tempfile::tempdir;
+
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let root = temp_dir.path();
+    create_dir_all(root.join("strutil")).unwrap();
+    create_dir_all(root.join("mathx/v2")).unwrap();
+    fs::write(root.join("go.mod"), "module example.com/mod\n\ngo 1.22\n").unwrap();
+    fs::write(
+        root.join("strutil/strutil.go"),
+        "package strutil\n\nfunc Reverse(s string) string { return s }\n\nfunc Upper(s string) string { return s }\n\nfunc Lower(s string) string { return s }\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("mathx/v2/abs.go"),
+        "package mathx\n\nfunc Abs(x int) int { return x }\n\nfunc (v Vec) Len() int { return 0 }\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("strutil/strutil_test.go"),
+        r#"package strutil
+
+import (
+	"testing"
+	"example.com/mod/mathx/v2"
+)
+
+func TestReverse(t *testing.T) {
+	tests := []struct {
+		name string
+		fn   func(string) string
+	}{
+		{"reverse", Reverse},
+	}
+	for _, tc := range tests {
+		t.Run(tc.name, func(t *testing.T) { tc.fn("a") })
+	}
+	t.Run("abs value", func(t *testing.T) {
+		mathx.Abs(-1)
+		var v mathx.Vec
+		v.Len()
+	})
+}
+
+func BenchmarkUpper(b *testing.B) { Upper("a") }
+
+func helper(t *testing.T) { Lower("a") }
+"#,
+    )
+    .unwrap();
+
+    let mut methods = Vec::new();
+    for file in ["strutil/strutil.go", "mathx/v2/abs.go"] {
+        let path = root.join(file);
+        let source_code = fs::read_to_string(&path).unwrap();
+        let tree = get_parser("go").parse(&source_code, None).unwrap();
+        traverse_go_nodes(
+            tree.root_node(),
+            &source_code,
+            &path.to_string_lossy(),
+            false,
+            &mut methods,
+        );
+    }
+
+    let tested = extract_go_tested_methods(&root.to_string_lossy(), &methods);
+    let tests_for = |name: &str| {
+        let method = methods.iter().find(|m| m.method_name == name).unwrap();
+        tested.tests_for(method)
+    };
+
+    assert_eq!(tests_for("Reverse"), vec!["TestReverse"]);
+    assert_eq!(tests_for("Abs"), vec!["TestReverse/abs_value"]);
+    assert_eq!(tests_for("Len"), vec!["TestReverse/abs_value"]);
+    assert_eq!(tests_for("Upper"), vec!["BenchmarkUpper"]);
+    assert!(tests_for("Lower").is_empty());
+}
+
+#[test]
+fn test_go_skipped_tests() {
+    let source_code = r#"
+package calc
+
+func TestSkipped(t *testing.T) { t.Skip("flaky"); Add(1, 2) }
+
+func TestShort(t *testing.T) {
+	if testing.Short() {
+		t.SkipNow()
+	}
+	Add(1, 2)
+}
+
+func TestOps(t *testing.T) {
+	t.Run("sub", func(t *testing.T) { t.Skipf("issue %d", 12); Sub(2, 1) })
+	t.Run("mul", func(t *testing.T) { Mul(2, 3) })
+}
+
+func BenchmarkAdd(b *testing.B) { b.SkipNow(); Add(1, 2) }
+"#;
+    let tree = get_parser("go").parse(source_code, None).unwrap();
+    let mut test_cases = Vec::new();
+    collect_go_test_cases(tree.root_node(), source_code, &mut test_cases);
+
+    let names: Vec<&str> = test_cases.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, vec!["TestShort", "TestOps", "TestOps/mul"]);
+}
+
+#[test]
+fn test_go_calls_by_package() {
+    use std::fs::create_dir_all;
+    use tempfile::tempdir;
+
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    // A repo inside another module's testdata, with a nested module of its own
+    let root = temp_dir.path().join("testdata/mod");
+    create_dir_all(root.join("calc")).unwrap();
+    create_dir_all(root.join("strutil")).unwrap();
+    create_dir_all(root.join("tools/fmtx")).unwrap();
+    fs::write(root.join("go.mod"), "module example.com/mod\n").unwrap();
+    fs::write(root.join("tools/go.mod"), "module example.com/tools\n").unwrap();
+    // Go has no overloads, but functions and methods share names across types and
+    // packages
+    fs::write(
+        root.join("calc/calc.go"),
+        "package calc\n\nfunc Len(s []int) int { return 0 }\n\nfunc (s Stack) Len() int { return 0 }\n\nfunc (q Queue) Len() int { return 0 }\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("strutil/strutil.go"),
+        "package strutil\n\nfunc Len(s string) int { return 0 }\n\nfunc Reverse(s string) string { return s }\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("tools/fmtx/fmtx.go"),
+        "package fmtx\n\nfunc Pad(s string) string { return s }\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("calc/calc_test.go"),
+        r#"package calc
+
+import (
+	"testing"
+	"example.com/tools/fmtx"
+	"github.com/acme/strutil"
+)
+
+func TestLen(t *testing.T) { Len(nil) }
+
+func TestStack(t *testing.T) {
+	var s Stack
+	s.Len()
+}
+
+func TestFormat(t *testing.T) {
+	fmtx.Pad("a")
+	strutil.Reverse("a")
+}
+"#,
+    )
+    .unwrap();
+
+    let mut methods = Vec::new();
+    for file in ["calc/calc.go", "strutil/strutil.go", "tools/fmtx/fmtx.go"] {
+        let path = root.join(file);
+        let source_code = fs::read_to_string(&path).unwrap();
+        let tree = get_parser("go").parse(&source_code, None).unwrap();
+        traverse_go_nodes(
+            tree.root_node(),
+            &source_code,
+            &path.to_string_lossy(),
+            false,
+            &mut methods,
+        );
+    }
+
+    let tested = extract_go_tested_methods(&root.to_string_lossy(), &methods);
+    let tests_for = |file: &str, class: &str, name: &str| {
+        let method = methods
+            .iter()
+            .find(|m| m.file_path.ends_with(file) && m.class_name == class && m.method_name == name)
+            .unwrap();
+        tested.tests_for(method)
+    };
+
+    assert_eq!(tests_for("calc.go", "", "Len"), vec!["TestLen"]);
+    // Method calls aren't told apart by the receiver's type
+    assert_eq!(tests_for("calc.go", "Stack", "Len"), vec!["TestStack"]);
+    assert_eq!(tests_for("calc.go", "Queue", "Len"), vec!["TestStack"]);
+    assert!(tests_for("strutil.go", "", "Len").is_empty());
+    assert_eq!(tests_for("fmtx.go", "", "Pad"), vec!["TestFormat"]);
+    // A package from outside the repo that shares a name with one inside it
+    assert!(tests_for("strutil.go", "", "Reverse").is_empty());
+}
@@ -0,0 +1,414 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use crate::cobertura::xml_escape;
+use crate::report::{directory, language_name, CoverageResults, MethodCoverage, MethodTotals};
+use crate::utils::CoverageStatus;
+
+const STYLE: &str = r##"
+body { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; margin: 2em; color: #24292f; }
+a { color: #0969da; text-decoration: none; }
+a:hover { text-decoration: underline; }
+table { border-collapse: collapse; margin-bottom: 2em; }
+th, td { padding: 4px 12px; text-align: left; border-bottom: 1px solid #d0d7de; }
+th.sortable { cursor: pointer; user-select: none; }
+th.sortable::after { content: " \2195"; color: #8c959f; }
+td.num { text-align: right; }
+.covered { background: #dafbe1; }
+.interface { background: #ddf4ff; }
+.uncovered { background: #ffebe9; }
+.filters { margin-bottom: 1em; }
+.filters input { width: 20em; }
+.source { font-family: ui-monospace, Menlo, Consolas, monospace; font-size: 13px; border: none; }
+.source td { border: none; padding: 0 8px; white-space: pre; }
+.source td.line { color: #8c959f; text-align: right; user-select: none; }
+.source tr.annotation td { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; white-space: normal; padding: 4px 8px; border-top: 1px solid #d0d7de; }
+:target td { outline: 2px solid #bf8700; }
+"##;
+
+const SCRIPT: &str = r##"
+document.querySelectorAll("th.sortable").forEach(function (th) {
+  th.addEventListener("click", function () {
+    var table = th.closest("table");
+    var body = table.tBodies[0];
+    var index = Array.prototype.indexOf.call(th.parentNode.children, th);
+    var ascending = th.dataset.order !== "asc";
+    th.dataset.order = ascending ? "asc" : "desc";
+    var key = function (row) {
+      var cell = row.children[index];
+      return cell.dataset.sort !== undefined ? parseFloat(cell.dataset.sort) : cell.textContent.toLowerCase();
+    };
+    Array.from(body.rows)
+      .sort(function (a, b) {
+        var x = key(a), y = key(b);
+        return (x < y ? -1 : x > y ? 1 : 0) * (ascending ? 1 : -1);
+      })
+      .forEach(function (row) { body.appendChild(row); });
+  });
+});
+var filterInput = document.getElementById("filter");
+var statusSelect = document.getElementById("status");
+function applyFilters() {
+  var text = filterInput.value.toLowerCase();
+  var status = statusSelect.value;
+  document.querySelectorAll("#methods tbody tr").forEach(function (row) {
+    var shown = row.dataset.search.indexOf(text) !== -1 && (status === "" || row.dataset.status === status);
+    row.style.display = shown ? "" : "none";
+  });
+}
+if (filterInput) {
+  filterInput.addEventListener("input", applyFilters);
+  statusSelect.addEventListener("change", applyFilters);
+}
+"##;
+
+fn status_class(status: &CoverageStatus) -> &'static str {
+    match status {
+        CoverageStatus::Covered => "covered",
+        CoverageStatus::ViaInterface => "interface",
+        CoverageStatus::Uncovered => "uncovered",
+    }
+}
+
+fn status_label(status: &CoverageStatus) -> &'static str {
+    match status {
+        CoverageStatus::Covered => "Covered",
+        CoverageStatus::ViaInterface => "Via interface",
+        CoverageStatus::Uncovered => "Uncovered",
+    }
+}
+
+// The page of a source file, relative to the report directory. Files outside the
+// repo keep their place under files/ rather than escaping the report directory.
+fn file_page(path: &str) -> String {
+    let parts: Vec<String> = Path::new(path)
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+            Component::ParentDir => Some(String::from("__")),
+            _ => None,
+        })
+        .collect();
+    format!("files/{}.html", parts.join("/"))
+}
+
+// The way back to the report directory from a page
+fn root_prefix(page: &str) -> String {
+    "../".repeat(page.matches('/').count())
+}
+
+fn page(title: &str, root: &str, body: &str) -> String {
+    let mut html = String::new();
+    writeln!(html, "<!DOCTYPE html>").unwrap();
+    writeln!(html, "<html lang=\"en\">").unwrap();
+    writeln!(html, "<head>").unwrap();
+    writeln!(html, "<meta charset=\"utf-8\">").unwrap();
+    writeln!(html, "<title>{}</title>", xml_escape(title)).unwrap();
+    writeln!(html, "<style>{}</style>", STYLE).unwrap();
+    writeln!(html, "</head>").unwrap();
+    writeln!(html, "<body>").unwrap();
+    if !root.is_empty() {
+        writeln!(
+            html,
+            "<p><a href=\"{}index.html\">&larr; Summary</a></p>",
+            root
+        )
+        .unwrap();
+    }
+    html.push_str(body);
+    writeln!(html, "<script>{}</script>", SCRIPT).unwrap();
+    writeln!(html, "</body>").unwrap();
+    writeln!(html, "</html>").unwrap();
+    html
+}
+
+fn totals_table(heading: &str, groups: &BTreeMap<String, MethodTotals>, html: &mut String) {
+    writeln!(html, "<h2>By {}</h2>", heading.to_lowercase()).unwrap();
+    writeln!(html, "<table>").unwrap();
+    writeln!(
+        html,
+        "<thead><tr><th class=\"sortable\">{}</th><th class=\"sortable\">Methods</th><th class=\"sortable\">Covered</th><th class=\"sortable\">Via interface</th><th class=\"sortable\">Coverage</th></tr></thead>",
+        heading
+    )
+    .unwrap();
+    writeln!(html, "<tbody>").unwrap();
+    for (name, totals) in groups {
+        writeln!(
+            html,
+            "<tr><td>{}</td><td class=\"num\" data-sort=\"{total}\">{total}</td><td class=\"num\" data-sort=\"{covered}\">{covered}</td><td class=\"num\" data-sort=\"{interface}\">{interface}</td><td class=\"num\" data-sort=\"{percent}\">{percent:.2}%</td></tr>",
+            xml_escape(name),
+            total = totals.total,
+            covered = totals.covered,
+            interface = totals.via_interface,
+            percent = totals.percent()
+        )
+        .unwrap();
+    }
+    writeln!(html, "</tbody>").unwrap();
+    writeln!(html, "</table>").unwrap();
+}
+
+fn index_page(results: &CoverageResults) -> String {
+    let mut totals = MethodTotals::default();
+    let mut languages: BTreeMap<String, MethodTotals> = BTreeMap::new();
+    let mut directories: BTreeMap<String, MethodTotals> = BTreeMap::new();
+    for method in &results.methods {
+        totals.add(method);
+        languages
+            .entry(language_name(&method.path))
+            .or_default()
+            .add(method);
+        directories
+            .entry(directory(&method.path))
+            .or_default()
+            .add(method);
+    }
+
+    let mut body = String::new();
+    writeln!(body, "<h1>Test Coverage Report</h1>").unwrap();
+    writeln!(
+        body,
+        "<p><code>{}</code></p>",
+        xml_escape(&results.root.to_string_lossy())
+    )
+    .unwrap();
+    writeln!(
+        body,
+        "<p>Method coverage: <strong>{:.2}%</strong> ({} of {} methods)</p>",
+        totals.percent(),
+        totals.covered,
+        totals.total
+    )
+    .unwrap();
+    if totals.via_interface > 0 {
+        writeln!(
+            body,
+            "<p>Including interface dispatch: {:.2}%</p>",
+            crate::coverage::coverage_percent(totals.covered + totals.via_interface, totals.total)
+        )
+        .unwrap();
+    }
+
+    totals_table("Language", &languages, &mut body);
+    totals_table("Directory", &directories, &mut body);
+
+    writeln!(body, "<h2>Methods</h2>").unwrap();
+    writeln!(
+        body,
+        "<div class=\"filters\"><input id=\"filter\" type=\"search\" placeholder=\"Filter methods and files\"> <select id=\"status\"><option value=\"\">All</option><option value=\"covered\">Covered</option><option value=\"interface\">Via interface</option><option value=\"uncovered\">Uncovered</option></select></div>"
+    )
+    .unwrap();
+    writeln!(body, "<table id=\"methods\">").unwrap();
+    writeln!(
+        body,
+        "<thead><tr><th class=\"sortable\">Method</th><th class=\"sortable\">File</th><th class=\"sortable\">Status</th><th class=\"sortable\">Tests</th></tr></thead>"
+    )
+    .unwrap();
+    writeln!(body, "<tbody>").unwrap();
+    for method in &results.methods {
+        let link = format!("{}#L{}", file_page(&method.path), method.method.start_line);
+        writeln!(
+            body,
+            "<tr class=\"{class}\" data-status=\"{class}\" data-search=\"{search}\"><td><a href=\"{link}\">{id}</a></td><td><a href=\"{link}\">{path}:{line}</a></td><td>{label}</td><td class=\"num\" data-sort=\"{tests}\">{tests}</td></tr>",
+            class = status_class(&method.status),
+            search = xml_escape(&format!("{} {}", method.id, method.path).to_lowercase()),
+            link = xml_escape(&link),
+            id = xml_escape(&method.id),
+            path = xml_escape(&method.path),
+            line = method.method.start_line,
+            label = status_label(&method.status),
+            tests = method.tests.len()
+        )
+        .unwrap();
+    }
+    writeln!(body, "</tbody>").unwrap();
+    writeln!(body, "</table>").unwrap();
+
+    page("Test Coverage Report", "", &body)
+}
+
+fn source_page(results: &CoverageResults, path: &str, methods: &[&MethodCoverage]) -> String {
+    let page_path = file_page(path);
+    let root = root_prefix(&page_path);
+    let source = fs::read_to_string(results.root.join(path)).unwrap_or_default();
+
+    // Methods starting later are nested in, or follow, the ones before them, so they
+    // decide the class of the lines they span
+    let mut line_classes: BTreeMap<usize, &str> = BTreeMap::new();
+    let mut annotations: BTreeMap<usize, Vec<&MethodCoverage>> = BTreeMap::new();
+    let mut ordered = methods.to_vec();
+    ordered.sort_by_key(|m| m.method.start_line);
+    for method in ordered {
+        for line in method.method.start_line..=method.method.end_line() {
+            line_classes.insert(line, status_class(&method.status));
+        }
+        annotations
+            .entry(method.method.start_line)
+            .or_default()
+            .push(method);
+    }
+
+    let mut body = String::new();
+    writeln!(body, "<h1>{}</h1>", xml_escape(path)).unwrap();
+    if !methods.is_empty() {
+        let mut totals = MethodTotals::default();
+        for method in methods {
+            totals.add(method);
+        }
+        writeln!(
+            body,
+            "<p>Method coverage: <strong>{:.2}%</strong> ({} of {} methods)</p>",
+            totals.percent(),
+            totals.covered,
+            totals.total
+        )
+        .unwrap();
+    }
+    writeln!(body, "<table class=\"source\">").unwrap();
+    for (i, line) in source.lines().enumerate() {
+        let number = i + 1;
+        for method in annotations.get(&number).into_iter().flatten() {
+            write!(
+                body,
+                "<tr class=\"annotation {}\"><td></td><td><strong>{}</strong> &mdash; ",
+                status_class(&method.status),
+                xml_escape(&method.id)
+            )
+            .unwrap();
+            if method.tests.is_empty() {
+                body.push_str("not called by any test");
+            } else {
+                let links: Vec<String> = method
+                    .tests
+                    .iter()
+                    .map(|test| match results.test_locations.get(test) {
+                        Some((test_path, test_line)) => format!(
+                            "<a href=\"{}{}#L{}\">{}</a>",
+                            root,
+                            xml_escape(&file_page(test_path)),
+                            test_line,
+                            xml_escape(test)
+                        ),
+                        None => xml_escape(test),
+                    })
+                    .collect();
+                write!(
+                    body,
+                    "{} by {}",
+                    status_label(&method.status).to_lowercase(),
+                    links.join(", ")
+                )
+                .unwrap();
+            }
+            writeln!(body, "</td></tr>").unwrap();
+        }
+        let class = line_classes
+            .get(&number)
+            .map(|c| format!(" class=\"{}\"", c))
+            .unwrap_or_default();
+        writeln!(
+            body,
+            "<tr id=\"L{n}\"{}><td class=\"line\"><a href=\"#L{n}\">{n}</a></td><td>{}</td></tr>",
+            class,
+            xml_escape(line),
+            n = number
+        )
+        .unwrap();
+    }
+    writeln!(body, "</table>").unwrap();
+
+    page(path, &root, &body)
+}
+
+/// Writes the results as a static HTML site into `dir`: an index.html summary with
+/// coverage per language and directory and a list of the methods, and a page per
+/// source file showing its code with the methods' coverage. Styles and scripts are
+/// inlined, so the directory can be archived and opened offline.
+pub fn write_html_report(results: &CoverageResults, dir: &Path) -> io::Result<()> {
+    let mut files: BTreeMap<&str, Vec<&MethodCoverage>> = BTreeMap::new();
+    for method in &results.methods {
+        files.entry(&method.path).or_default().push(method);
+    }
+    // Test files get a page too, for the links from the methods they call
+    let test_files: BTreeSet<&str> = results
+        .methods
+        .iter()
+        .flat_map(|m| &m.tests)
+        .filter_map(|test| results.test_locations.get(test))
+        .map(|(path, _)| path.as_str())
+        .collect();
+    for path in test_files {
+        files.entry(path).or_default();
+    }
+
+    fs::create_dir_all(dir)?;
+    fs::write(dir.join("index.html"), index_page(results))?;
+    for (path, methods) in &files {
+        let page_path: PathBuf = dir.join(file_page(path));
+        if let Some(parent) = page_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(page_path, source_page(results, path, methods))?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_write_html_report() {
+    use crate::codeanalysis::create_lang_settings;
+    use crate::utils::{CallTarget, Lang, Method, TestedMethods};
+
+    let repo = tempfile::tempdir().unwrap();
+    fs::create_dir_all(repo.path().join("src")).unwrap();
+    fs::create_dir_all(repo.path().join("tests")).unwrap();
+    fs::write(
+        repo.path().join("src/cart.rb"),
+        "class Cart\n  def add(item)\n    @items << item\n  end\n  def empty?\n    @items.size < 1\n  end\nend\n",
+    )
+    .unwrap();
+    fs::write(
+        repo.path().join("tests/cart_test.rb"),
+        "class CartTest < Minitest::Test\n  def test_add\n    Cart.new.add(1)\n  end\nend\n",
+    )
+    .unwrap();
+
+    let method = |file: &str, class: &str, name: &str, start_line: usize, lines: usize| Method {
+        class_name: class.to_string(),
+        method_name: name.to_string(),
+        body: vec![String::new(); lines],
+        start_line,
+        is_test: file.starts_with("tests"),
+        file_path: repo.path().join(file).to_string_lossy().to_string(),
+    };
+    let methods = vec![
+        method("src/cart.rb", "Cart", "add", 2, 3),
+        method("src/cart.rb", "Cart", "empty?", 5, 3),
+        method("tests/cart_test.rb", "CartTest", "test_add", 2, 3),
+    ];
+    let mut tests = TestedMethods::default();
+    tests.record(CallTarget::exact(&methods[0]), "CartTest.test_add");
+
+    let lang_settings = create_lang_settings(&Lang::Ruby);
+    let repo_path = repo.path().to_string_lossy().to_string();
+    let results = CoverageResults::new(&repo_path, &methods, &tests, &lang_settings);
+    let out = tempfile::tempdir().unwrap();
+    write_html_report(&results, out.path()).unwrap();
+
+    let index = fs::read_to_string(out.path().join("index.html")).unwrap();
+    assert!(index.contains("<strong>50.00%</strong> (1 of 2 methods)"));
+    assert!(index.contains("<td>Ruby</td>"));
+    assert!(index.contains("<a href=\"files/src/cart.rb.html#L5\">Cart.empty?</a>"));
+
+    let source = fs::read_to_string(out.path().join("files/src/cart.rb.html")).unwrap();
+    assert!(source.contains("<p><a href=\"../../index.html\">"));
+    assert!(source.contains("<tr id=\"L3\" class=\"covered\">"));
+    assert!(source.contains("<tr id=\"L6\" class=\"uncovered\">"));
+    assert!(source.contains("@items.size &lt; 1"));
+    assert!(
+        source.contains("<a href=\"../../files/tests/cart_test.rb.html#L2\">CartTest.test_add</a>")
+    );
+    assert!(out.path().join("files/tests/cart_test.rb.html").exists());
+}
@@ -0,0 +1,751 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+use crate::utils::{
+    extract_body, get_parser, repo_relative_path, should_skip_dir, CallTarget, Method,
+    TestedMethods,
+};
+
+// JUnit 4/5 and TestNG test annotations
+const JAVA_TEST_ANNOTATIONS: [&str; 4] =
+    ["Test", "ParameterizedTest", "RepeatedTest", "TestFactory"];
+const JAVA_DISABLED_ANNOTATIONS: [&str; 2] = ["Disabled", "Ignore"];
+const JAVA_TYPE_DECLARATIONS: [&str; 4] = [
+    "class_declaration",
+    "interface_declaration",
+    "enum_declaration",
+    "record_declaration",
+];
+
+/// Maven and Gradle keep test sources under `src/test/`.
+pub fn is_java_test_source(repo: &str, path: &Path) -> bool {
+    let path = repo_relative_path(repo, path)
+        .to_string_lossy()
+        .replace('\\', "/");
+    path.starts_with("src/test/") || path.contains("/src/test/")
+}
+
+/// Collects methods and constructors, named after their package-qualified class
+/// (`com.example.Calculator`). Constructors take the name of their class.
+pub fn traverse_java_nodes(
+    node: tree_sitter::Node,
+    source_code: &str,
+    file_path: &str,
+    is_test_source: bool,
+    methods: &mut Vec<Method>,
+) {
+    let package = java_package(node, source_code);
+    collect_java_members(
+        node,
+        source_code,
+        file_path,
+        &package,
+        &mut Vec::new(),
+        is_test_source,
+        methods,
+    );
+}
+
+fn collect_java_members(
+    node: tree_sitter::Node,
+    source_code: &str,
+    file_path: &str,
+    package: &str,
+    classes: &mut Vec<String>,
+    is_test_source: bool,
+    methods: &mut Vec<Method>,
+) {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+
+    if JAVA_TYPE_DECLARATIONS.contains(&node.kind()) {
+        if let Some(name) = node.child_by_field_name("name") {
+            classes.push(text(name));
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                collect_java_members(
+                    child,
+                    source_code,
+                    file_path,
+                    package,
+                    classes,
+                    is_test_source,
+                    methods,
+                );
+            }
+            classes.pop();
+        }
+        return;
+    }
+
+    // Abstract and interface methods have no body to cover
+    if matches!(
+        node.kind(),
+        "method_declaration" | "constructor_declaration"
+    ) && node.child_by_field_name("body").is_some()
+    {
+        if let Some(name) = node.child_by_field_name("name") {
+            let annotations = java_annotations(node, source_code);
+            methods.push(Method {
+                class_name: qualify(package, &classes.join(".")),
+                method_name: text(name),
+                body: extract_body(node, source_code),
+                start_line: node.start_position().row + 1,
+                // Helpers in test sources aren't logic to be covered
+                is_test: is_test_source
+                    || annotations
+                        .iter()
+                        .any(|a| JAVA_TEST_ANNOTATIONS.contains(&a.as_str())),
+                file_path: file_path.to_string(),
+            });
+        }
+        // Anonymous and local classes belong to the method they are declared in
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_java_members(
+            child,
+            source_code,
+            file_path,
+            package,
+            classes,
+            is_test_source,
+            methods,
+        );
+    }
+}
+
+fn qualify(package: &str, name: &str) -> String {
+    if package.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", package, name)
+    }
+}
+
+fn java_package(root: tree_sitter::Node, source_code: &str) -> String {
+    let mut cursor = root.walk();
+    let package = root
+        .named_children(&mut cursor)
+        .find(|n| n.kind() == "package_declaration")
+        .and_then(|n| n.named_child(0))
+        .map(|n| source_code[n.start_byte()..n.end_byte()].to_string())
+        .unwrap_or_default();
+    package
+}
+
+// Simple names of the annotations on a declaration; `@org.junit.Test` gives "Test"
+fn java_annotations(node: tree_sitter::Node, source_code: &str) -> Vec<String> {
+    let mut cursor = node.walk();
+    let Some(modifiers) = node.children(&mut cursor).find(|n| n.kind() == "modifiers") else {
+        return Vec::new();
+    };
+
+    let mut cursor = modifiers.walk();
+    let annotations = modifiers
+        .named_children(&mut cursor)
+        .filter(|n| matches!(n.kind(), "marker_annotation" | "annotation"))
+        .filter_map(|n| n.child_by_field_name("name"))
+        .map(|n| {
+            let name = &source_code[n.start_byte()..n.end_byte()];
+            name.rsplit('.').next().unwrap_or(name).to_string()
+        })
+        .collect();
+    annotations
+}
+
+// `@Disabled`, `@Ignore` or TestNG's `@Test(enabled = false)`
+fn is_java_disabled(node: tree_sitter::Node, source_code: &str) -> bool {
+    if java_annotations(node, source_code)
+        .iter()
+        .any(|a| JAVA_DISABLED_ANNOTATIONS.contains(&a.as_str()))
+    {
+        return true;
+    }
+
+    let mut cursor = node.walk();
+    let modifiers = node.children(&mut cursor).find(|n| n.kind() == "modifiers");
+    modifiers.is_some_and(|modifiers| {
+        source_code[modifiers.start_byte()..modifiers.end_byte()]
+            .replace(char::is_whitespace, "")
+            .contains("enabled=false")
+    })
+}
+
+// Names a test file can refer to classes and static methods by
+#[derive(Debug, Default)]
+struct JavaScope {
+    package: String,
+    // Simple class name -> qualified name, from single-type imports
+    imports: HashMap<String, String>,
+    // Static method name -> qualified class, from `import static a.B.method`
+    static_methods: HashMap<String, String>,
+    // Qualified classes from `import static a.B.*`
+    static_classes: Vec<String>,
+}
+
+// A call made by a test. Constructors are named after their class.
+#[derive(PartialEq, Debug)]
+struct JavaCall {
+    receiver: JavaReceiver,
+    name: String,
+}
+
+#[derive(PartialEq, Debug)]
+enum JavaReceiver {
+    // `add(1, 2)`, which can only reach logic through a static import
+    Unqualified,
+    // `calc.add(1, 2)` on a variable of known type, or `Calculator.create()`
+    Type(String),
+    // Chained calls and other expressions whose type isn't worked out
+    Unknown,
+}
+
+/// Resolves the calls made by enabled tests to the methods of the classes they are
+/// made on, using the declared types of fields, parameters and locals. Calls whose
+/// receiver type can't be worked out fall back to matching by method name.
+pub fn extract_java_tested_methods(repo: &str, logic_methods: &[Method]) -> TestedMethods {
+    let mut tested_methods = TestedMethods::default();
+    let candidates: Vec<&Method> = logic_methods.iter().filter(|m| !m.is_test).collect();
+
+    for entry in WalkDir::new(repo)
+        .into_iter()
+        .filter_entry(|e| !should_skip_dir(e))
+        .filter_map(Result::ok)
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "java"))
+    {
+        let Ok(source_code) = fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let mut parser = get_parser("java");
+        let Some(tree) = parser.parse(&source_code, None) else {
+            continue;
+        };
+
+        let scope = java_scope(tree.root_node(), &source_code);
+        let mut tests = Vec::new();
+        collect_java_tests(
+            tree.root_node(),
+            &source_code,
+            &mut Vec::new(),
+            &HashMap::new(),
+            false,
+            &mut tests,
+        );
+
+        for (test_name, calls) in tests {
+            for call in calls {
+                let classes = match &call.receiver {
+                    JavaReceiver::Unqualified => match scope.static_methods.get(&call.name) {
+                        Some(class) => Some(vec![class.clone()]),
+                        None => Some(scope.static_classes.clone()),
+                    },
+                    JavaReceiver::Type(class) => {
+                        Some(resolve_java_class(class, &scope, &candidates))
+                    }
+                    JavaReceiver::Unknown => None,
+                };
+                for method in candidates.iter().filter(|m| {
+                    m.method_name == call.name
+                        && classes.as_ref().is_none_or(|c| c.contains(&m.class_name))
+                }) {
+                    tested_methods.record(CallTarget::exact(method), &test_name);
+                }
+            }
+        }
+    }
+
+    tested_methods
+}
+
+fn java_scope(root: tree_sitter::Node, source_code: &str) -> JavaScope {
+    let mut scope = JavaScope {
+        package: java_package(root, source_code),
+        ..Default::default()
+    };
+
+    let mut cursor = root.walk();
+    for import in root
+        .named_children(&mut cursor)
+        .filter(|n| n.kind() == "import_declaration")
+    {
+        let mut cursor = import.walk();
+        let children: Vec<tree_sitter::Node> = import.children(&mut cursor).collect();
+        let is_static = children.iter().any(|c| c.kind() == "static");
+        let is_wildcard = children.iter().any(|c| c.kind() == "asterisk");
+        let Some(path) = children
+            .iter()
+            .find(|c| matches!(c.kind(), "scoped_identifier" | "identifier"))
+            .map(|c| source_code[c.start_byte()..c.end_byte()].to_string())
+        else {
+            continue;
+        };
+
+        match (is_static, is_wildcard) {
+            (true, true) => scope.static_classes.push(path),
+            (true, false) => {
+                if let Some((class, method)) = path.rsplit_once('.') {
+                    scope
+                        .static_methods
+                        .insert(method.to_string(), class.to_string());
+                }
+            }
+            (false, false) => {
+                let simple = path.rsplit('.').next().unwrap_or(&path).to_string();
+                scope.imports.insert(simple, path);
+            }
+            (false, true) => {}
+        }
+    }
+    scope
+}
+
+// Qualified names of the logic classes a type name used in a test can refer to
+fn resolve_java_class(class: &str, scope: &JavaScope, candidates: &[&Method]) -> Vec<String> {
+    let simple = class.rsplit('.').next().unwrap_or(class);
+    let exists = |qualified: &str| candidates.iter().any(|m| m.class_name == qualified);
+
+    if exists(class) {
+        return vec![class.to_string()];
+    }
+    if let Some(imported) = scope.imports.get(simple).filter(|i| exists(i)) {
+        return vec![imported.clone()];
+    }
+    let same_package = qualify(&scope.package, simple);
+    if exists(&same_package) {
+        return vec![same_package];
+    }
+
+    let mut classes: Vec<String> = candidates
+        .iter()
+        .filter(|m| m.class_name == simple || m.class_name.ends_with(&format!(".{}", simple)))
+        .map(|m| m.class_name.clone())
+        .collect();
+    classes.sort();
+    classes.dedup();
+    classes
+}
+
+// Finds enabled test methods, returning each test's name (`CalculatorTest.adds`) with
+// the calls made in it. Field types of the enclosing classes are passed down so
+// calls on fields can be resolved.
+fn collect_java_tests(
+    node: tree_sitter::Node,
+    source_code: &str,
+    classes: &mut Vec<String>,
+    fields: &HashMap<String, String>,
+    disabled: bool,
+    tests: &mut Vec<(String, Vec<JavaCall>)>,
+) {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+
+    if JAVA_TYPE_DECLARATIONS.contains(&node.kind()) {
+        let Some(name) = node.child_by_field_name("name") else {
+            return;
+        };
+        let mut fields = fields.clone();
+        if let Some(body) = node.child_by_field_name("body") {
+            let mut cursor = body.walk();
+            for field in body
+                .named_children(&mut cursor)
+                .filter(|n| n.kind() == "field_declaration")
+            {
+                collect_java_declared_types(field, source_code, &mut fields);
+            }
+        }
+
+        classes.push(text(name));
+        let disabled = disabled || is_java_disabled(node, source_code);
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            collect_java_tests(child, source_code, classes, &fields, disabled, tests);
+        }
+        classes.pop();
+        return;
+    }
+
+    if node.kind() == "method_declaration" {
+        let is_test = java_annotations(node, source_code)
+            .iter()
+            .any(|a| JAVA_TEST_ANNOTATIONS.contains(&a.as_str()));
+        if let (true, Some(name), Some(body)) = (
+            is_test && !disabled && !is_java_disabled(node, source_code),
+            node.child_by_field_name("name"),
+            node.child_by_field_name("body"),
+        ) {
+            let mut variables = fields.clone();
+            if let Some(parameters) = node.child_by_field_name("parameters") {
+                collect_java_declared_types(parameters, source_code, &mut variables);
+            }
+            collect_java_declared_types(body, source_code, &mut variables);
+
+            let mut calls = Vec::new();
+            collect_java_calls(body, source_code, &variables, &mut calls);
+            tests.push((format!("{}.{}", classes.join("."), text(name)), calls));
+        }
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_java_tests(child, source_code, classes, fields, disabled, tests);
+    }
+}
+
+// Records `Type name` declarations (fields, parameters, locals, for-each variables),
+// taking `var x = new Type()` from its initializer
+fn collect_java_declared_types(
+    node: tree_sitter::Node,
+    source_code: &str,
+    types: &mut HashMap<String, String>,
+) {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+
+    if let Some(declared) = node
+        .child_by_field_name("type")
+        .map(|t| java_type_name(t, source_code))
+    {
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            let (name, value) = match child.kind() {
+                "variable_declarator" => (
+                    child.child_by_field_name("name"),
+                    child.child_by_field_name("value"),
+                ),
+                "identifier" if node.child_by_field_name("name") == Some(child) => {
+                    (Some(child), None)
+                }
+                _ => continue,
+            };
+            let inferred = value
+                .filter(|v| v.kind() == "object_creation_expression")
+                .and_then(|v| v.child_by_field_name("type"))
+                .map(|t| java_type_name(t, source_code));
+            let declared = match inferred {
+                Some(inferred) if declared == "var" => inferred,
+                _ => declared.clone(),
+            };
+            if let Some(name) = name {
+                types.insert(text(name), declared);
+            }
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        // Nested classes declare their own fields
+        if !JAVA_TYPE_DECLARATIONS.contains(&child.kind()) {
+            collect_java_declared_types(child, source_code, types);
+        }
+    }
+}
+
+// `List<Foo>` gives "List", `com.example.Foo` stays qualified
+fn java_type_name(node: tree_sitter::Node, source_code: &str) -> String {
+    let name = &source_code[node.start_byte()..node.end_byte()];
+    name.split('<').next().unwrap_or(name).trim().to_string()
+}
+
+fn collect_java_calls(
+    node: tree_sitter::Node,
+    source_code: &str,
+    variables: &HashMap<String, String>,
+    calls: &mut Vec<JavaCall>,
+) {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+
+    // Type of the value a call is made on: a typed variable or field, a class name
+    // for static calls, or a freshly constructed object
+    let receiver_type = |receiver: tree_sitter::Node| match receiver.kind() {
+        "identifier" => {
+            let name = text(receiver);
+            variables
+                .get(&name)
+                .cloned()
+                .or_else(|| name.starts_with(|c: char| c.is_uppercase()).then_some(name))
+        }
+        "field_access" => receiver
+            .child_by_field_name("field")
+            .and_then(|f| variables.get(&text(f)).cloned()),
+        "object_creation_expression" => receiver
+            .child_by_field_name("type")
+            .map(|t| java_type_name(t, source_code)),
+        "scoped_identifier" | "type_identifier" => Some(text(receiver)),
+        _ => None,
+    };
+
+    let typed = |class: Option<String>| class.map_or(JavaReceiver::Unknown, JavaReceiver::Type);
+    let constructor = |class: String| JavaCall {
+        name: class.rsplit('.').next().unwrap_or(&class).to_string(),
+        receiver: JavaReceiver::Type(class),
+    };
+
+    let call = match node.kind() {
+        "method_invocation" => node.child_by_field_name("name").map(|name| JavaCall {
+            receiver: match node.child_by_field_name("object") {
+                Some(object) => typed(receiver_type(object)),
+                None => JavaReceiver::Unqualified,
+            },
+            name: text(name),
+        }),
+        "object_creation_expression" => node
+            .child_by_field_name("type")
+            .map(|t| constructor(java_type_name(t, source_code))),
+        // Calculator::add, calc::add and Calculator::new
+        "method_reference" => {
+            let receiver = node.named_child(0).and_then(receiver_type);
+            let name = node.named_child(node.named_child_count().saturating_sub(1));
+            match (receiver, name) {
+                (Some(class), _) if text(node).ends_with("::new") => Some(constructor(class)),
+                (receiver, Some(name)) if name.kind() == "identifier" => Some(JavaCall {
+                    receiver: typed(receiver),
+                    name: text(name),
+                }),
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+
+    if let Some(call) = call {
+        if !calls.contains(&call) {
+            calls.push(call);
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_java_calls(child, source_code, variables, calls);
+    }
+}
+
+#[test]
+fn test_traverse_java_nodes() {
+    let source_code = r#"
+package com.example;
+
+public class Calculator {
+    public Calculator(int precision) { }
+    public int add(int a, int b) { return a + b; }
+    abstract static class Op { abstract int apply(int a); }
+    static class Memory { void clear() { } }
+}
+
+interface Shape { double area(); default String name() { return "shape"; } }
+"#;
+    let mut parser = get_parser("java");
+    let tree = parser.parse(source_code, None).unwrap();
+
+    let mut methods = Vec::new();
+    traverse_java_nodes(
+        tree.root_node(),
+        source_code,
+        "src/main/java/com/example/Calculator.java",
+        false,
+        &mut methods,
+    );
+
+    let found: Vec<(&str, &str)> = methods
+        .iter()
+        .map(|m| (m.class_name.as_str(), m.method_name.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("com.example.Calculator", "Calculator"),
+            ("com.example.Calculator", "add"),
+            ("com.example.Calculator.Memory", "clear"),
+            ("com.example.Shape", "name"),
+        ]
+    );
+    assert!(is_java_test_source(
+        ".",
+        Path::new("app/src/test/java/com/example/CalculatorTest.java")
+    ));
+    assert!(!is_java_test_source(
+        ".",
+        Path::new("app/src/main/java/com/example/Calculator.java")
+    ));
+    // A repo checked out inside another project's test sources
+    assert!(!is_java_test_source(
+        "/work/src/test/fixture",
+        Path::new("/work/src/test/fixture/src/main/java/Calculator.java")
+    ));
+}
+
+#[test]
+fn test_extract_java_tested_methods() {
+    use std::fs::create_dir_all;
+    use tempfile::tempdir;
+
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    // Nested in another checkout's test sources, which mustn't make it all test code
+    let root = temp_dir.path().join("src/test/fixture");
+    let main = root.join("src/main/java/com/example");
+    let other = root.join("src/main/java/com/other");
+    let test = root.join("src/test/java/com/example");
+    for dir in [&main, &other, &test] {
+        create_dir_all(dir).unwrap();
+    }
+    fs::write(
+        main.join("Calculator.java"),
+        r#"package com.example;
+public class Calculator {
+    public Calculator() { }
+    public int add(int a, int b) { return a + b; }
+    public int sub(int a, int b) { return a - b; }
+    public static int abs(int a) { return a; }
+}"#,
+    )
+    .unwrap();
+    fs::write(
+        other.join("Calculator.java"),
+        r#"package com.other;
+public class Calculator { public int add(int a, int b) { return a + b; } }"#,
+    )
+    .unwrap();
+    fs::write(
+        test.join("CalculatorTest.java"),
+        r#"package com.example;
+import static com.example.Calculator.abs;
+import static org.junit.jupiter.api.Assertions.*;
+
+class CalculatorTest {
+    private Calculator calc;
+
+    @Test
+    void adds() {
+        calc = new Calculator();
+        assertEquals(3, calc.add(1, 2));
+    }
+
+    @ParameterizedTest
+    @ValueSource(ints = {1, -1})
+    void absolute(int x) { abs(x); }
+
+    @Disabled
+    @Test
+    void subtracts() { calc.sub(2, 1); }
+
+    @org.testng.annotations.Test(enabled = false)
+    void disabledInTestNg() { calc.sub(2, 1); }
+}"#,
+    )
+    .unwrap();
+
+    let mut methods = Vec::new();
+    for entry in WalkDir::new(&root)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "java"))
+    {
+        let source_code = fs::read_to_string(entry.path()).unwrap();
+        let tree = get_parser("java").parse(&source_code, None).unwrap();
+        traverse_java_nodes(
+            tree.root_node(),
+            &source_code,
+            &entry.path().to_string_lossy(),
+            is_java_test_source(&root.to_string_lossy(), entry.path()),
+            &mut methods,
+        );
+    }
+
+    let tested = extract_java_tested_methods(&root.to_string_lossy(), &methods);
+    let tests_for = |class: &str, name: &str| {
+        let method = methods
+            .iter()
+            .find(|m| m.class_name == class && m.method_name == name)
+            .unwrap();
+        tested.tests_for(method)
+    };
+
+    assert_eq!(
+        tests_for("com.example.Calculator", "Calculator"),
+        vec!["CalculatorTest.adds"]
+    );
+    assert_eq!(
+        tests_for("com.example.Calculator", "add"),
+        vec!["CalculatorTest.adds"]
+    );
+    assert!(tests_for("com.other.Calculator", "add").is_empty());
+    assert_eq!(
+        tests_for("com.example.Calculator", "abs"),
+        vec!["CalculatorTest.absolute"]
+    );
+    assert!(tests_for("com.example.Calculator", "sub").is_empty());
+}
+
+#[test]
+fn test_is_java_test_source() {
+    // Test sources of a repo nested inside another project's test sources
+    let repo = "/work/src/test/resources/app";
+    assert!(is_java_test_source(
+        repo,
+        Path::new("/work/src/test/resources/app/src/test/java/AppTest.java")
+    ));
+    assert!(!is_java_test_source(
+        &format!("{}/", repo),
+        Path::new("/work/src/test/resources/app/src/main/java/App.java")
+    ));
+    // Files outside the repo root are judged on their whole path
+    assert!(is_java_test_source(
+        repo,
+        Path::new("/elsewhere/src/test/java/OtherTest.java")
+    ));
+    assert!(!is_java_test_source(
+        repo,
+        Path::new("/elsewhere/src/main/java/Other.java")
+    ));
+}
+
+#[test]
+fn test_java_disabled_and_overloaded_tests() {
+    let source_code = r#"
+package com.example;
+
+class CalculatorTest {
+    Calculator calc = new Calculator();
+
+    @Test void addsInts() { calc.add(1, 2); }
+    @Test void addsDoubles() { calc.add(1.0, 2.0); }
+    @Ignore("flaky") @Test void junit4() { calc.sub(2, 1); }
+    @org.junit.jupiter.api.Disabled @Test void qualified() { calc.sub(2, 1); }
+    @Test(enabled = false, groups = "slow") void testNg() { calc.sub(2, 1); }
+
+    @Disabled
+    @Nested
+    class Memory {
+        @Test void clears() { calc.clear(); }
+    }
+}
+"#;
+    let tree = get_parser("java").parse(source_code, None).unwrap();
+    let mut tests = Vec::new();
+    collect_java_tests(
+        tree.root_node(),
+        source_code,
+        &mut Vec::new(),
+        &HashMap::new(),
+        false,
+        &mut tests,
+    );
+
+    let names: Vec<&str> = tests.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec!["CalculatorTest.addsInts", "CalculatorTest.addsDoubles"]
+    );
+    // Overloads aren't told apart by their arguments, so both calls are to `add`
+    for (_, calls) in &tests {
+        assert_eq!(
+            calls,
+            &vec![JavaCall {
+                receiver: JavaReceiver::Type(String::from("Calculator")),
+                name: String::from("add"),
+            }]
+        );
+    }
+}
@@ -1,84 +1,412 @@
-use std::collections::HashSet;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::{fs::File, io::BufReader};
 
 use walkdir::WalkDir;
 
-use crate::codeanalysis::read_to_string_buffered;
+use crate::codeanalysis::{file_extension, read_to_string_buffered};
+use crate::jsmodules::{collect_js_imports, JsModules};
 use crate::utils::{
-    extract_body, get_parser, normalize_line, should_skip_dir, LangSettings, Method,
+    extract_body, get_parser, glob_to_regex, normalize_path, should_skip_dir, CallTarget,
+    LangSettings, Method, TestedMethods,
 };
 
+// Node kinds that introduce a new function scope
+const JS_FUNCTION_KINDS: [&str; 6] = [
+    "function_declaration",
+    "generator_function_declaration",
+    "function_expression",
+    "generator_function",
+    "arrow_function",
+    "method_definition",
+];
+
+// Test file conventions applied on top of any Jest configuration; `{ext}` is
+// replaced by the language's extensions
+const JS_TEST_FILE_GLOBS: [&str; 3] = ["**/*.{test,spec}.{ext}", "**/__tests__/**", "**/test/**"];
+const JEST_CONFIG_FILES: [&str; 3] = ["jest.config.js", "jest.config.cjs", "jest.config.mjs"];
+
+/// Decides which files in a JavaScript repo hold tests, from the usual naming
+/// conventions plus Jest's `testMatch`/`testRegex` when they can be read statically.
+#[derive(Debug)]
+pub struct JsTestFiles {
+    root: PathBuf,
+    patterns: Vec<Regex>,
+}
+
+impl JsTestFiles {
+    pub fn load(repo: &str, extensions: &[String]) -> JsTestFiles {
+        let root = Path::new(repo);
+        let mut globs: Vec<String> = JS_TEST_FILE_GLOBS
+            .iter()
+            .map(|g| g.replace("{ext}", &format!("{{{}}}", extensions.join(","))))
+            .collect();
+        let mut regexes = Vec::new();
+
+        if let Ok(package_json) = fs::read_to_string(root.join("package.json")) {
+            if let Ok(package) = serde_json::from_str::<serde_json::Value>(&package_json) {
+                let jest = &package["jest"];
+                globs.extend(json_strings(&jest["testMatch"]));
+                regexes.extend(json_strings(&jest["testRegex"]));
+            }
+        }
+
+        for config_file in JEST_CONFIG_FILES {
+            if let Ok(source_code) = fs::read_to_string(root.join(config_file)) {
+                let mut parser = get_parser("js");
+                if let Some(tree) = parser.parse(&source_code, None) {
+                    globs.extend(jest_config_strings(
+                        tree.root_node(),
+                        &source_code,
+                        "testMatch",
+                    ));
+                    regexes.extend(jest_config_strings(
+                        tree.root_node(),
+                        &source_code,
+                        "testRegex",
+                    ));
+                }
+            }
+        }
+
+        let mut patterns: Vec<Regex> = globs
+            .iter()
+            .map(|g| g.trim_start_matches("<rootDir>/"))
+            .filter_map(glob_to_regex)
+            .collect();
+        patterns.extend(regexes.iter().filter_map(|r| Regex::new(r).ok()));
+
+        JsTestFiles {
+            root: normalize_path(root),
+            patterns,
+        }
+    }
+
+    pub fn is_test_file(&self, path: &Path) -> bool {
+        let path = normalize_path(path);
+        let relative = path
+            .strip_prefix(&self.root)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        self.patterns.iter().any(|p| p.is_match(&relative))
+    }
+}
+
+pub(crate) fn json_strings(value: &serde_json::Value) -> Vec<String> {
+    match value {
+        serde_json::Value::String(s) => vec![s.clone()],
+        serde_json::Value::Array(items) => items
+            .iter()
+            .filter_map(|item| item.as_str().map(String::from))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+// String literal values of a `key: "..."` or `key: ["...", ...]` property anywhere
+// in a Jest config module
+fn jest_config_strings(node: tree_sitter::Node, source_code: &str, key: &str) -> Vec<String> {
+    let text = |n: tree_sitter::Node| &source_code[n.start_byte()..n.end_byte()];
+    let mut values = Vec::new();
+
+    if node.kind() == "pair" {
+        let key_matches = node
+            .child_by_field_name("key")
+            .is_some_and(|k| text(k).trim_matches(['"', '\'']) == key);
+        if let (true, Some(value)) = (key_matches, node.child_by_field_name("value")) {
+            let mut cursor = value.walk();
+            let literals: Vec<tree_sitter::Node> = if value.kind() == "array" {
+                value.named_children(&mut cursor).collect()
+            } else {
+                vec![value]
+            };
+            for literal in literals {
+                if literal.kind() == "string" {
+                    let quoted = text(literal);
+                    values.push(unescape_js_string(&quoted[1..quoted.len() - 1]));
+                } else if literal.kind() == "regex" {
+                    if let Some(pattern) = literal.child_by_field_name("pattern") {
+                        values.push(text(pattern).to_string());
+                    }
+                }
+            }
+            return values;
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        values.extend(jest_config_strings(child, source_code, key));
+    }
+    values
+}
+
+fn unescape_js_string(raw: &str) -> String {
+    let mut value = String::new();
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => value.push('\n'),
+            Some('t') => value.push('\t'),
+            Some(escaped) => value.push(escaped),
+            None => {}
+        }
+    }
+    value
+}
+
+pub fn traverse_js_nodes(
+    node: tree_sitter::Node,
+    source_code: &str,
+    file_path: &str,
+    is_test_file: bool,
+    methods: &mut Vec<Method>,
+) {
+    // Functions declared inside other functions can't be called by a test directly
+    if is_nested_in_function(&node) {
+        return;
+    }
+
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+    let mut found = None;
+
+    match node.kind() {
+        "function_declaration" | "generator_function_declaration" => {
+            if let Some(name) = node.child_by_field_name("name") {
+                found = Some((String::new(), text(name)));
+            }
+        }
+        "method_definition" => {
+            if let (Some(name), Some(owner)) = (node.child_by_field_name("name"), node.parent()) {
+                found = Some((find_js_owner_name(&owner, source_code), text(name)));
+            }
+        }
+        // TypeScript names class fields `public_field_definition`
+        "field_definition" | "public_field_definition" => {
+            if let (Some(property), Some(value), Some(owner)) = (
+                node.child_by_field_name("property")
+                    .or_else(|| node.child_by_field_name("name")),
+                node.child_by_field_name("value"),
+                node.parent(),
+            ) {
+                if is_js_function_value(&value) {
+                    found = Some((find_js_owner_name(&owner, source_code), text(property)));
+                }
+            }
+        }
+        "variable_declarator" => {
+            if let (Some(name), Some(value)) = (
+                node.child_by_field_name("name"),
+                node.child_by_field_name("value"),
+            ) {
+                if name.kind() == "identifier"
+                    && (is_js_function_value(&value) || is_wrapped_component(&value, source_code))
+                {
+                    found = Some((String::new(), text(name)));
+                }
+            }
+        }
+        "pair" => {
+            if let (Some(key), Some(value), Some(owner)) = (
+                node.child_by_field_name("key"),
+                node.child_by_field_name("value"),
+                node.parent(),
+            ) {
+                if is_js_function_value(&value) {
+                    let key = text(key).trim_matches(['"', '\'', '`']).to_string();
+                    found = Some((find_js_owner_name(&owner, source_code), key));
+                }
+            }
+        }
+        "assignment_expression" => {
+            // module.exports.f = ..., exports.f = ..., Foo.prototype.f = ...
+            if let (Some(left), Some(right)) = (
+                node.child_by_field_name("left"),
+                node.child_by_field_name("right"),
+            ) {
+                if left.kind() == "member_expression" && is_js_function_value(&right) {
+                    if let (Some(object), Some(property)) = (
+                        left.child_by_field_name("object"),
+                        left.child_by_field_name("property"),
+                    ) {
+                        let owner = text(object);
+                        let owner = owner.strip_suffix(".prototype").unwrap_or(&owner);
+                        found = Some((owner.to_string(), text(property)));
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+
+    if let Some((class_name, method_name)) = found {
+        methods.push(Method {
+            class_name,
+            method_name,
+            body: extract_body(node, source_code),
+            start_line: node.start_position().row + 1,
+            // Helpers declared in test files aren't logic to be covered
+            is_test: is_test_file,
+            file_path: file_path.to_string(),
+        });
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        traverse_js_nodes(child, source_code, file_path, is_test_file, methods);
+    }
+}
+
+fn is_js_function_value(node: &tree_sitter::Node) -> bool {
+    matches!(
+        node.kind(),
+        "arrow_function" | "function_expression" | "generator_function"
+    )
+}
+
+// React components declared through `memo(...)` or `forwardRef(...)`
+fn is_wrapped_component(node: &tree_sitter::Node, source_code: &str) -> bool {
+    if node.kind() != "call_expression" {
+        return false;
+    }
+    let wrapper = node
+        .child_by_field_name("function")
+        .map(|f| &source_code[f.start_byte()..f.end_byte()]);
+    let wraps_function = node
+        .child_by_field_name("arguments")
+        .and_then(|args| args.named_child(0))
+        .is_some_and(|arg| is_js_function_value(&arg));
+
+    wraps_function
+        && matches!(
+            wrapper,
+            Some("memo" | "forwardRef" | "React.memo" | "React.forwardRef")
+        )
+}
+
+fn is_nested_in_function(node: &tree_sitter::Node) -> bool {
+    let mut current = node.parent();
+    while let Some(parent) = current {
+        if JS_FUNCTION_KINDS.contains(&parent.kind()) {
+            return true;
+        }
+        current = parent.parent();
+    }
+    false
+}
+
+// Name of the class or object literal a member belongs to, given the class body
+// or object node that contains it
+fn find_js_owner_name(owner: &tree_sitter::Node, source: &str) -> String {
+    let text = |n: tree_sitter::Node| source[n.start_byte()..n.end_byte()].to_string();
+
+    let container = if owner.kind() == "class_body" {
+        match owner.parent() {
+            Some(class) => {
+                if let Some(name) = class.child_by_field_name("name") {
+                    return text(name);
+                }
+                class
+            }
+            None => return String::new(),
+        }
+    } else {
+        *owner
+    };
+
+    // Anonymous classes and object literals take the name they are assigned to
+    match container.parent() {
+        Some(parent) if parent.kind() == "variable_declarator" => parent
+            .child_by_field_name("name")
+            .map(text)
+            .unwrap_or_default(),
+        Some(parent) if parent.kind() == "assignment_expression" => parent
+            .child_by_field_name("left")
+            .map(text)
+            .unwrap_or_default(),
+        Some(parent) if parent.kind() == "pair" => parent
+            .child_by_field_name("key")
+            .map(text)
+            .unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+// A test (or hook) found in a describe/it tree, named after its enclosing suites
+#[derive(PartialEq, Debug)]
+pub struct JsTestCase {
+    pub name: String,
+    pub calls: Vec<JsCall>,
+}
+
+// A call made by a test: `format(x)` has no receiver, `utils.format(x)` has "utils"
+#[derive(PartialEq, Debug)]
+pub struct JsCall {
+    pub receiver: Option<String>,
+    pub name: String,
+}
+
+enum JsTestBlock {
+    Suite,
+    Test,
+    Hook,
+}
+
 pub fn extract_js_tested_methods(
     repo: &String,
     lang_settings: &LangSettings,
-    logic_methods: &Vec<Method>,
-) -> Vec<String> {
-    let mut tested_methods = Vec::new();
-    let mut parser = get_parser(&lang_settings.ext);
+    logic_methods: &[Method],
+) -> TestedMethods {
+    let mut tested_methods = TestedMethods::default();
 
     let logic_method_names = extract_js_logic_method_names(logic_methods);
+    let mut modules = JsModules::default();
 
     for entry in WalkDir::new(repo)
         .into_iter()
         .filter_entry(|e| !should_skip_dir(e))
         .filter_map(Result::ok)
     {
-        if entry
-            .path()
-            .extension()
-            .map_or(false, |ext| *ext == *lang_settings.ext)
-        {
+        if lang_settings.is_source_file(entry.path()) {
             if let Ok(file) = File::open(entry.path()) {
                 let reader = BufReader::new(file);
                 let source_code = read_to_string_buffered(reader);
+                let mut parser = get_parser(&file_extension(entry.path()));
 
                 if let Some(tree) = parser.parse(&source_code, None) {
-                    let root_node = tree.root_node();
-                    let mut cursor = root_node.walk();
-
-                    for node in root_node.children(&mut cursor) {
-                        if node.kind() == "expression_statement" {
-                            let body = extract_body(node, &source_code);
-                            for line in body {
-                                let normalized_line = normalize_line(&line);
-                                if normalized_line.contains("expect")
-                                    || normalized_line.contains("assert")
-                                    || normalized_line.contains("should")
-                                    || normalized_line.contains("assertThat")
-                                    || normalized_line.contains("done")
-                                    || normalized_line.contains("beforeEach")
-                                    || normalized_line.contains("afterEach")
-                                    || normalized_line.contains("beforeAll")
-                                    || normalized_line.contains("afterAll")
-                                    || normalized_line.contains("not")
-                                {
-                                    if let Some(args_start) = line.find('(') {
-                                        if let Some(args_end) = line.rfind(')') {
-                                            let args = &line[args_start + 1..args_end];
-
-                                            for arg in args.split('(') {
-                                                let potential_method = arg.trim();
-
-                                                let method_name = if potential_method.contains('.')
-                                                {
-                                                    potential_method
-                                                        .split('.')
-                                                        .last()
-                                                        .unwrap_or("")
-                                                        .trim()
-                                                } else {
-                                                    potential_method
-                                                };
-
-                                                if !method_name.is_empty()
-                                                    && logic_method_names.contains(method_name)
-                                                {
-                                                    tested_methods.push(method_name.to_string());
-                                                }
-                                            }
-                                        }
-                                    }
+                    let mut test_cases = Vec::new();
+                    collect_js_test_cases(
+                        tree.root_node(),
+                        &source_code,
+                        &mut Vec::new(),
+                        &mut test_cases,
+                    );
+
+                    let mut imports = HashMap::new();
+                    collect_js_imports(
+                        tree.root_node(),
+                        &source_code,
+                        &normalize_path(entry.path()),
+                        &mut imports,
+                    );
+
+                    for test_case in test_cases {
+                        for call in test_case.calls {
+                            // Tests importing repo modules only cover what they import;
+                            // anything else is matched by name
+                            if !imports.is_empty() {
+                                for target in modules.call_targets(&call, &imports) {
+                                    tested_methods.record(target, &test_case.name);
                                 }
+                            } else if logic_method_names.contains(&call.name) {
+                                tested_methods.record(CallTarget::Name(call.name), &test_case.name);
                             }
                         }
                     }
@@ -89,7 +417,173 @@ pub fn extract_js_tested_methods(
     tested_methods
 }
 
-fn extract_js_logic_method_names(logic_methods: &Vec<Method>) -> HashSet<String> {
+/// Walks describe/it/test/suite/specify blocks (and their .each/.only/.skip/.concurrent
+/// variants), collecting every call made inside each test. Skipped and todo tests
+/// don't run, so they are left out.
+pub fn collect_js_test_cases(
+    node: tree_sitter::Node,
+    source_code: &str,
+    suites: &mut Vec<String>,
+    test_cases: &mut Vec<JsTestCase>,
+) {
+    if node.kind() == "call_expression" {
+        if let Some((block, skipped)) = node
+            .child_by_field_name("function")
+            .and_then(|callee| classify_js_test_block(callee, source_code))
+        {
+            let arguments = node.child_by_field_name("arguments");
+            let callback = arguments.and_then(|args| {
+                let mut cursor = args.walk();
+                args.named_children(&mut cursor)
+                    .filter(is_js_function_value)
+                    .last()
+            });
+
+            let mut name = arguments
+                .and_then(|args| args.named_child(0))
+                .filter(|first| !is_js_function_value(first))
+                .map(|first| js_test_title(first, source_code))
+                .unwrap_or_default();
+            if let JsTestBlock::Hook = block {
+                name = node
+                    .child_by_field_name("function")
+                    .map(|f| source_code[f.start_byte()..f.end_byte()].to_string())
+                    .unwrap_or_default();
+            }
+
+            if skipped {
+                return;
+            }
+            if let Some(callback) = callback {
+                match block {
+                    JsTestBlock::Suite => {
+                        suites.push(name);
+                        collect_js_test_cases(callback, source_code, suites, test_cases);
+                        suites.pop();
+                    }
+                    JsTestBlock::Test | JsTestBlock::Hook => {
+                        let mut full_name = suites.clone();
+                        full_name.push(name);
+
+                        let mut calls = Vec::new();
+                        collect_js_calls(callback, source_code, &mut calls);
+                        test_cases.push(JsTestCase {
+                            name: full_name.join(" > "),
+                            calls,
+                        });
+                    }
+                }
+            }
+            return;
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_js_test_cases(child, source_code, suites, test_cases);
+    }
+}
+
+// Recognizes `describe`, `it.only`, `test.concurrent.each`, `describe.each(table)` etc.
+// and reports whether the block is skipped
+fn classify_js_test_block(
+    callee: tree_sitter::Node,
+    source_code: &str,
+) -> Option<(JsTestBlock, bool)> {
+    let text = |n: tree_sitter::Node| &source_code[n.start_byte()..n.end_byte()];
+
+    // describe.each(table)("name", fn) calls the result of describe.each(table)
+    let mut current = if callee.kind() == "call_expression" {
+        callee.child_by_field_name("function")?
+    } else {
+        callee
+    };
+
+    let mut modifiers = Vec::new();
+    while current.kind() == "member_expression" {
+        modifiers.push(text(current.child_by_field_name("property")?));
+        current = current.child_by_field_name("object")?;
+    }
+    if current.kind() != "identifier" {
+        return None;
+    }
+    if !modifiers
+        .iter()
+        .all(|m| ["each", "only", "skip", "concurrent", "todo", "failing"].contains(m))
+    {
+        return None;
+    }
+
+    let root = text(current);
+    let block = match root {
+        "describe" | "context" | "suite" | "fdescribe" | "xdescribe" | "xcontext" => {
+            JsTestBlock::Suite
+        }
+        "it" | "test" | "specify" | "fit" | "xit" | "xtest" | "xspecify" => JsTestBlock::Test,
+        "beforeEach" | "afterEach" | "beforeAll" | "afterAll" | "before" | "after"
+            if modifiers.is_empty() =>
+        {
+            JsTestBlock::Hook
+        }
+        _ => return None,
+    };
+    let skipped = root.starts_with('x') || modifiers.iter().any(|m| *m == "skip" || *m == "todo");
+
+    Some((block, skipped))
+}
+
+fn js_test_title(node: tree_sitter::Node, source_code: &str) -> String {
+    source_code[node.start_byte()..node.end_byte()]
+        .trim_matches(['"', '\'', '`'])
+        .to_string()
+}
+
+// Every function called inside a node: `format(x)` gives "format" and
+// `parser.parse(x)` gives "parse" called on "parser". Rendering a component,
+// as in `<Button />`, counts as calling it.
+fn collect_js_calls(node: tree_sitter::Node, source_code: &str, calls: &mut Vec<JsCall>) {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+
+    let callee = match node.kind() {
+        "call_expression" => node.child_by_field_name("function"),
+        // Lowercase tags are DOM elements rather than components
+        "jsx_opening_element" | "jsx_self_closing_element" => node
+            .child_by_field_name("name")
+            .filter(|name| name.kind() == "member_expression" || is_component_name(&text(*name))),
+        _ => None,
+    };
+    if let Some(callee) = callee {
+        let call = match callee.kind() {
+            "identifier" => Some(JsCall {
+                receiver: None,
+                name: text(callee),
+            }),
+            "member_expression" => callee
+                .child_by_field_name("property")
+                .map(|property| JsCall {
+                    receiver: callee.child_by_field_name("object").map(text),
+                    name: text(property),
+                }),
+            _ => None,
+        };
+        if let Some(call) = call {
+            if !calls.contains(&call) {
+                calls.push(call);
+            }
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_js_calls(child, source_code, calls);
+    }
+}
+
+fn is_component_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_uppercase())
+}
+
+fn extract_js_logic_method_names(logic_methods: &[Method]) -> HashSet<String> {
     let logic_method_names: HashSet<String> = logic_methods
         .iter()
         .map(|m| m.method_name.clone())
@@ -97,3 +591,225 @@ fn extract_js_logic_method_names(logic_methods: &Vec<Method>) -> HashSet<String>
 
     logic_method_names
 }
+
+#[test]
+fn test_traverse_js_nodes() {
+    let source_code = r#"
+class Parser extends Base {
+    parse(input) { return input; }
+    static create() { return new Parser(); }
+    handle = () => {};
+}
+const format = (value) => String(value);
+const trim = function (value) { return value.trim(); };
+const helpers = {
+    pad() {},
+    wrap: (value) => `[${value}]`,
+};
+module.exports.load = function () {
+    const inner = () => 1;
+    return inner();
+};
+Widget.prototype.render = function () {};
+function main() {}
+"#;
+    let mut parser = get_parser("js");
+    let tree = parser.parse(source_code, None).unwrap();
+
+    let mut methods = Vec::new();
+    traverse_js_nodes(
+        tree.root_node(),
+        source_code,
+        "parser.js",
+        false,
+        &mut methods,
+    );
+
+    let found: Vec<(&str, &str)> = methods
+        .iter()
+        .map(|m| (m.class_name.as_str(), m.method_name.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("Parser", "parse"),
+            ("Parser", "create"),
+            ("Parser", "handle"),
+            ("", "format"),
+            ("", "trim"),
+            ("helpers", "pad"),
+            ("helpers", "wrap"),
+            ("module.exports", "load"),
+            ("Widget", "render"),
+            ("", "main"),
+        ]
+    );
+}
+
+#[test]
+fn test_traverse_ts_nodes() {
+    let source_code = r#"
+@Injectable()
+export abstract class Repository<T> {
+    private cache = new Map();
+    load = async (id: string): Promise<T> => this.fetch(id);
+    @Memoize() public static create(): void {}
+    protected abstract fetch(id: string): Promise<T>;
+    find(id: string): T;
+    find(id: number): T;
+    find(id: any): T { return this.cache.get(id); }
+}
+export function render(view: string): string;
+export function render(view: any): string { return String(view); }
+interface Renderer { render(view: string): string; }
+declare function external(): void;
+"#;
+    let mut parser = get_parser("ts");
+    let tree = parser.parse(source_code, None).unwrap();
+
+    let mut methods = Vec::new();
+    traverse_js_nodes(
+        tree.root_node(),
+        source_code,
+        "repository.ts",
+        false,
+        &mut methods,
+    );
+
+    let found: Vec<(&str, &str)> = methods
+        .iter()
+        .map(|m| (m.class_name.as_str(), m.method_name.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("Repository", "load"),
+            ("Repository", "create"),
+            ("Repository", "find"),
+            ("", "render"),
+        ]
+    );
+}
+
+#[test]
+fn test_collect_js_test_cases() {
+    let source_code = r#"
+describe('Parser', () => {
+    beforeEach(() => { reset(); });
+
+    it('handles empty input', () => {
+        expect(parse('')).toEqual([]);
+    });
+
+    describe.each([[1], [2]])('with %i items', (count) => {
+        test.concurrent('counts them', async () => {
+            expect(parser.count(items(count))).toBe(count);
+        });
+    });
+
+    it.skip('is not ready', () => { unfinished(); });
+    xit('is disabled', () => { disabled(); });
+});
+
+const expect = (value) => value; // not a test
+"#;
+    let mut parser = get_parser("js");
+    let tree = parser.parse(source_code, None).unwrap();
+
+    let mut test_cases = Vec::new();
+    collect_js_test_cases(
+        tree.root_node(),
+        source_code,
+        &mut Vec::new(),
+        &mut test_cases,
+    );
+
+    let found: Vec<(&str, Vec<&str>)> = test_cases
+        .iter()
+        .map(|t| {
+            (
+                t.name.as_str(),
+                t.calls.iter().map(|c| c.name.as_str()).collect(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("Parser > beforeEach", vec!["reset"]),
+            (
+                "Parser > handles empty input",
+                vec!["toEqual", "expect", "parse"]
+            ),
+            (
+                "Parser > with %i items > counts them",
+                vec!["toBe", "expect", "count", "items"]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_react_components_and_hooks() {
+    let component = r#"
+export function useCounter(start) { return start; }
+export const Button = React.memo(({ label }) => <button>{label}</button>);
+export const Input = forwardRef((props, ref) => <input ref={ref} />);
+export default function App() { return <Layout.Page><Button /></Layout.Page>; }
+"#;
+    let mut parser = get_parser("jsx");
+    let tree = parser.parse(component, None).unwrap();
+    let mut methods = Vec::new();
+    traverse_js_nodes(tree.root_node(), component, "App.jsx", false, &mut methods);
+    let names: Vec<&str> = methods.iter().map(|m| m.method_name.as_str()).collect();
+    assert_eq!(names, vec!["useCounter", "Button", "Input", "App"]);
+
+    let test = r#"
+it('renders', () => {
+    render(<App><Button label="ok" /><div /></App>);
+    renderHook(() => useCounter(1));
+});
+"#;
+    let tree = parser.parse(test, None).unwrap();
+    let mut test_cases = Vec::new();
+    collect_js_test_cases(tree.root_node(), test, &mut Vec::new(), &mut test_cases);
+    let calls: Vec<&str> = test_cases[0]
+        .calls
+        .iter()
+        .map(|c| c.name.as_str())
+        .collect();
+    assert_eq!(
+        calls,
+        vec!["render", "App", "Button", "renderHook", "useCounter"]
+    );
+}
+
+#[test]
+fn test_js_test_files() {
+    use std::fs::create_dir_all;
+    use tempfile::tempdir;
+
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let root = temp_dir.path();
+    create_dir_all(root.join("src")).unwrap();
+    fs::write(
+        root.join("package.json"),
+        r#"{ "name": "app", "jest": { "testMatch": ["**/checks/*.js"] } }"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("jest.config.js"),
+        r#"module.exports = { testRegex: "\\.e2e\\.js$" };"#,
+    )
+    .unwrap();
+
+    let test_files = JsTestFiles::load(&root.to_string_lossy(), &[String::from("js")]);
+
+    assert!(test_files.is_test_file(&root.join("src/parser.test.js")));
+    assert!(test_files.is_test_file(&root.join("src/parser.spec.js")));
+    assert!(test_files.is_test_file(&root.join("src/__tests__/parser.js")));
+    assert!(test_files.is_test_file(&root.join("test/parser.js")));
+    assert!(test_files.is_test_file(&root.join("checks/parser.js")));
+    assert!(test_files.is_test_file(&root.join("src/parser.e2e.js")));
+    assert!(!test_files.is_test_file(&root.join("src/parser.js")));
+}
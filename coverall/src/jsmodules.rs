@@ -0,0 +1,543 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::codeanalysis::file_extension;
+use crate::js::JsCall;
+use crate::utils::{get_parser, normalize_path, CallTarget};
+
+// Extensions tried, in order, when an import specifier leaves them out
+pub const JS_MODULE_EXTENSIONS: [&str; 8] = ["js", "jsx", "mjs", "cjs", "ts", "tsx", "mts", "cts"];
+
+/// What a local name in a test file is bound to by an `import` or `require`.
+#[derive(PartialEq, Debug, Clone)]
+pub enum JsImport {
+    /// A single export of a module; default imports use the name "default".
+    Named(PathBuf, String),
+    /// The whole module, from `import * as ns` or `const ns = require(...)`.
+    Namespace(PathBuf),
+}
+
+impl JsImport {
+    fn module(&self) -> &Path {
+        match self {
+            JsImport::Named(module, _) | JsImport::Namespace(module) => module,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+struct JsModuleExports {
+    // Exported name -> name it is declared with in the module
+    local: HashMap<String, String>,
+    // Exported name -> module and name it is re-exported from
+    forwarded: HashMap<String, (PathBuf, String)>,
+    // Modules re-exported wholesale with `export * from`
+    star: Vec<PathBuf>,
+}
+
+/// Lazily parsed exports of the modules test files import.
+#[derive(Debug, Default)]
+pub struct JsModules {
+    exports: HashMap<PathBuf, JsModuleExports>,
+}
+
+impl JsModules {
+    /// Follows re-exports to the files and names an export is declared with.
+    pub fn resolve_export(&mut self, module: &Path, name: &str) -> Vec<(PathBuf, String)> {
+        let mut found = Vec::new();
+        self.resolve_export_into(module, name, &mut Vec::new(), &mut found);
+        found
+    }
+
+    fn resolve_export_into(
+        &mut self,
+        module: &Path,
+        name: &str,
+        visited: &mut Vec<(PathBuf, String)>,
+        found: &mut Vec<(PathBuf, String)>,
+    ) {
+        let key = (module.to_path_buf(), name.to_string());
+        if visited.contains(&key) {
+            return;
+        }
+        visited.push(key);
+
+        let exports = self.exports_of(module);
+        if let Some(local) = exports.local.get(name) {
+            found.push((module.to_path_buf(), local.clone()));
+        } else if let Some((source, imported)) = exports.forwarded.get(name) {
+            self.resolve_export_into(source, imported, visited, found);
+        } else if name != "default" {
+            found.push((module.to_path_buf(), name.to_string()));
+            for source in &exports.star {
+                self.resolve_export_into(source, name, visited, found);
+            }
+        }
+    }
+
+    /// The module itself plus every module it re-exports from.
+    pub fn module_files(&mut self, module: &Path) -> Vec<PathBuf> {
+        let mut files = vec![module.to_path_buf()];
+        let mut i = 0;
+        while i < files.len() {
+            let exports = self.exports_of(&files[i].clone());
+            let sources = exports
+                .star
+                .iter()
+                .chain(exports.forwarded.values().map(|(source, _)| source));
+            for source in sources {
+                if !files.contains(source) {
+                    files.push(source.clone());
+                }
+            }
+            i += 1;
+        }
+        files
+    }
+
+    fn exports_of(&mut self, module: &Path) -> JsModuleExports {
+        if let Some(exports) = self.exports.get(module) {
+            return exports.clone();
+        }
+
+        let mut exports = JsModuleExports::default();
+        if let Ok(source_code) = fs::read_to_string(module) {
+            let mut parser = get_parser(&file_extension(module));
+            if let Some(tree) = parser.parse(&source_code, None) {
+                let root = tree.root_node();
+                let mut cursor = root.walk();
+                for statement in root.named_children(&mut cursor) {
+                    collect_js_exports(statement, &source_code, module, &mut exports);
+                }
+            }
+        }
+        self.exports.insert(module.to_path_buf(), exports.clone());
+        exports
+    }
+
+    /// Logic methods a test call can reach through the test file's imports.
+    pub fn call_targets(
+        &mut self,
+        call: &JsCall,
+        imports: &HashMap<String, JsImport>,
+    ) -> Vec<CallTarget> {
+        let in_file = |(file, name): (PathBuf, String)| {
+            CallTarget::InFile(file.to_string_lossy().to_string(), name)
+        };
+
+        match &call.receiver {
+            // format(), fmt() after `import { format as fmt }`, or a required module
+            // that exports a single function
+            None => match imports.get(&call.name) {
+                Some(JsImport::Named(module, export)) => self
+                    .resolve_export(module, export)
+                    .into_iter()
+                    .map(in_file)
+                    .collect(),
+                Some(JsImport::Namespace(module)) => self
+                    .resolve_export(module, "default")
+                    .into_iter()
+                    .map(in_file)
+                    .collect(),
+                None => Vec::new(),
+            },
+            // utils.format() or Parser.create() on an imported binding
+            Some(receiver) if imports.contains_key(receiver) => self
+                .module_files(imports[receiver].module())
+                .into_iter()
+                .map(|file| in_file((file, call.name.clone())))
+                .collect(),
+            // new Parser().parse() and other receivers of unknown origin may be
+            // anything the test imported
+            Some(_) => {
+                let mut targets = Vec::new();
+                for module in imports.values().map(JsImport::module) {
+                    for file in self.module_files(module) {
+                        let target = in_file((file, call.name.clone()));
+                        if !targets.contains(&target) {
+                            targets.push(target);
+                        }
+                    }
+                }
+                targets
+            }
+        }
+    }
+}
+
+fn collect_js_exports(
+    statement: tree_sitter::Node,
+    source_code: &str,
+    module: &Path,
+    exports: &mut JsModuleExports,
+) {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+
+    match statement.kind() {
+        "export_statement" => {
+            let source = statement
+                .child_by_field_name("source")
+                .and_then(|s| resolve_js_module(module, &js_string_value(s, source_code)));
+            let mut cursor = statement.walk();
+            let children: Vec<tree_sitter::Node> = statement.children(&mut cursor).collect();
+            let is_default = children.iter().any(|c| c.kind() == "default");
+            let clause = children.iter().find(|c| c.kind() == "export_clause");
+
+            if let Some(clause) = clause {
+                let mut cursor = clause.walk();
+                for specifier in clause.named_children(&mut cursor) {
+                    let Some(name) = specifier.child_by_field_name("name").map(text) else {
+                        continue;
+                    };
+                    let exported = specifier
+                        .child_by_field_name("alias")
+                        .map(text)
+                        .unwrap_or_else(|| name.clone());
+                    match &source {
+                        Some(source) => {
+                            exports.forwarded.insert(exported, (source.clone(), name));
+                        }
+                        None => {
+                            exports.local.insert(exported, name);
+                        }
+                    }
+                }
+            } else if let Some(source) = source {
+                // `export * from`, but not `export * as ns from`
+                if children.iter().any(|c| c.kind() == "*") {
+                    exports.star.push(source);
+                }
+            } else if let Some(declaration) = statement.child_by_field_name("declaration") {
+                for name in declared_names(declaration, source_code) {
+                    let exported = if is_default {
+                        String::from("default")
+                    } else {
+                        name.clone()
+                    };
+                    exports.local.insert(exported, name);
+                }
+            } else if let Some(value) = statement.child_by_field_name("value") {
+                if value.kind() == "identifier" {
+                    exports.local.insert(String::from("default"), text(value));
+                }
+            }
+        }
+        "expression_statement" => {
+            let Some(assignment) = statement
+                .named_child(0)
+                .filter(|n| n.kind() == "assignment_expression")
+            else {
+                return;
+            };
+            let (Some(left), Some(right)) = (
+                assignment.child_by_field_name("left"),
+                assignment.child_by_field_name("right"),
+            ) else {
+                return;
+            };
+            let target = text(left);
+
+            if target == "module.exports" {
+                match right.kind() {
+                    "identifier" => {
+                        exports.local.insert(String::from("default"), text(right));
+                    }
+                    // Recorded as a method named after the `exports` property
+                    "function_expression" | "arrow_function" => {
+                        exports
+                            .local
+                            .insert(String::from("default"), String::from("exports"));
+                    }
+                    "object" => {
+                        let mut cursor = right.walk();
+                        for property in right.named_children(&mut cursor) {
+                            if property.kind() == "shorthand_property_identifier" {
+                                exports.local.insert(text(property), text(property));
+                            } else if let (Some(key), Some(value)) = (
+                                property.child_by_field_name("key"),
+                                property.child_by_field_name("value"),
+                            ) {
+                                if value.kind() == "identifier" {
+                                    exports.local.insert(text(key), text(value));
+                                }
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            } else if let Some(exported) = target
+                .strip_prefix("module.exports.")
+                .or_else(|| target.strip_prefix("exports."))
+            {
+                if right.kind() == "identifier" {
+                    exports.local.insert(exported.to_string(), text(right));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+// Names introduced by an exported function, class or variable declaration
+fn declared_names(declaration: tree_sitter::Node, source_code: &str) -> Vec<String> {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+
+    if let Some(name) = declaration.child_by_field_name("name") {
+        return vec![text(name)];
+    }
+    let mut cursor = declaration.walk();
+    declaration
+        .named_children(&mut cursor)
+        .filter(|n| n.kind() == "variable_declarator")
+        .filter_map(|n| n.child_by_field_name("name"))
+        .filter(|n| n.kind() == "identifier")
+        .map(text)
+        .collect()
+}
+
+/// Binds the local names of a test file to the repo modules they are imported
+/// from. Package imports and specifiers that don't resolve to a file are ignored.
+pub fn collect_js_imports(
+    node: tree_sitter::Node,
+    source_code: &str,
+    file_path: &Path,
+    imports: &mut HashMap<String, JsImport>,
+) {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+
+    match node.kind() {
+        "import_statement" => {
+            let Some(module) = node
+                .child_by_field_name("source")
+                .and_then(|s| resolve_js_module(file_path, &js_string_value(s, source_code)))
+            else {
+                return;
+            };
+            let mut cursor = node.walk();
+            for clause in node
+                .named_children(&mut cursor)
+                .filter(|c| c.kind() == "import_clause")
+            {
+                let mut cursor = clause.walk();
+                for binding in clause.named_children(&mut cursor) {
+                    match binding.kind() {
+                        "identifier" => {
+                            imports.insert(
+                                text(binding),
+                                JsImport::Named(module.clone(), String::from("default")),
+                            );
+                        }
+                        "namespace_import" => {
+                            if let Some(name) = binding.named_child(0) {
+                                imports.insert(text(name), JsImport::Namespace(module.clone()));
+                            }
+                        }
+                        "named_imports" => {
+                            let mut cursor = binding.walk();
+                            for specifier in binding.named_children(&mut cursor) {
+                                if let Some(name) = specifier.child_by_field_name("name") {
+                                    let local =
+                                        specifier.child_by_field_name("alias").unwrap_or(name);
+                                    imports.insert(
+                                        text(local),
+                                        JsImport::Named(module.clone(), text(name)),
+                                    );
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            return;
+        }
+        "variable_declarator" => {
+            if let (Some(name), Some(value)) = (
+                node.child_by_field_name("name"),
+                node.child_by_field_name("value"),
+            ) {
+                if let Some((module, property)) = required_module(value, source_code, file_path) {
+                    match (name.kind(), property) {
+                        // const format = require('./format').format
+                        ("identifier", Some(property)) => {
+                            imports.insert(text(name), JsImport::Named(module, property));
+                        }
+                        ("identifier", None) => {
+                            imports.insert(text(name), JsImport::Namespace(module));
+                        }
+                        // const { format, parse: parseInput } = require('./format')
+                        ("object_pattern", None) => {
+                            let mut cursor = name.walk();
+                            for property in name.named_children(&mut cursor) {
+                                if property.kind() == "shorthand_property_identifier_pattern" {
+                                    imports.insert(
+                                        text(property),
+                                        JsImport::Named(module.clone(), text(property)),
+                                    );
+                                } else if let (Some(key), Some(value)) = (
+                                    property.child_by_field_name("key"),
+                                    property.child_by_field_name("value"),
+                                ) {
+                                    imports.insert(
+                                        text(value),
+                                        JsImport::Named(module.clone(), text(key)),
+                                    );
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_js_imports(child, source_code, file_path, imports);
+    }
+}
+
+// The module of a `require('./x')` or `require('./x').y` expression
+fn required_module(
+    value: tree_sitter::Node,
+    source_code: &str,
+    file_path: &Path,
+) -> Option<(PathBuf, Option<String>)> {
+    let (call, property) = match value.kind() {
+        "member_expression" => (
+            value.child_by_field_name("object")?,
+            value
+                .child_by_field_name("property")
+                .map(|p| source_code[p.start_byte()..p.end_byte()].to_string()),
+        ),
+        _ => (value, None),
+    };
+
+    let callee = call.child_by_field_name("function")?;
+    if call.kind() != "call_expression" || &source_code[callee.byte_range()] != "require" {
+        return None;
+    }
+    let specifier = call.child_by_field_name("arguments")?.named_child(0)?;
+    if specifier.kind() != "string" {
+        return None;
+    }
+    let module = resolve_js_module(file_path, &js_string_value(specifier, source_code))?;
+    Some((module, property))
+}
+
+fn js_string_value(node: tree_sitter::Node, source_code: &str) -> String {
+    source_code[node.start_byte()..node.end_byte()]
+        .trim_matches(['"', '\'', '`'])
+        .to_string()
+}
+
+/// Resolves a relative import specifier the way Node does: the exact file, then
+/// the file with a known extension added, then the directory's index file.
+pub fn resolve_js_module(from_file: &Path, specifier: &str) -> Option<PathBuf> {
+    if !(specifier.starts_with("./") || specifier.starts_with("../")) {
+        return None;
+    }
+    let base = normalize_path(&from_file.parent()?.join(specifier));
+
+    let mut candidates = vec![base.clone()];
+    // TypeScript sources import each other with the extension they compile to
+    if let Some(stem) = base.to_str().and_then(|b| b.strip_suffix(".js")) {
+        candidates.push(PathBuf::from(format!("{}.ts", stem)));
+        candidates.push(PathBuf::from(format!("{}.tsx", stem)));
+    }
+    for ext in JS_MODULE_EXTENSIONS {
+        candidates.push(PathBuf::from(format!("{}.{}", base.display(), ext)));
+    }
+    for ext in JS_MODULE_EXTENSIONS {
+        candidates.push(base.join(format!("index.{}", ext)));
+    }
+
+    // Only sources can be followed; `./data.json` and the like are left alone
+    candidates
+        .into_iter()
+        .find(|c| c.is_file() && JS_MODULE_EXTENSIONS.contains(&file_extension(c).as_str()))
+}
+
+#[test]
+fn test_resolve_js_imports_and_exports() {
+    use std::fs::create_dir_all;
+    use tempfile::tempdir;
+
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let root = normalize_path(temp_dir.path());
+    create_dir_all(root.join("src/utils")).unwrap();
+    create_dir_all(root.join("test")).unwrap();
+    fs::write(
+        root.join("src/format.js"),
+        "export function format(v) { return v; }\nexport default function pad(v) { return v; }\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("src/utils/strings.js"),
+        "function trim(v) { return v; }\nmodule.exports = { trim, strip: trim };\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("src/utils/index.js"),
+        "export * from './strings';\nexport { format as fmt } from '../format';\n",
+    )
+    .unwrap();
+
+    let test_file = root.join("test/format.test.js");
+    let source_code = r#"
+import pad, { format as fmt } from '../src/format';
+import * as utils from '../src/utils';
+const { strip } = require('../src/utils/strings.js');
+const lodash = require('lodash');
+"#;
+    let mut parser = get_parser("js");
+    let tree = parser.parse(source_code, None).unwrap();
+    let mut imports = HashMap::new();
+    collect_js_imports(tree.root_node(), source_code, &test_file, &mut imports);
+
+    let format_js = root.join("src/format.js");
+    let strings_js = root.join("src/utils/strings.js");
+    let index_js = root.join("src/utils/index.js");
+    assert_eq!(imports.len(), 4);
+    assert_eq!(
+        imports["fmt"],
+        JsImport::Named(format_js.clone(), String::from("format"))
+    );
+    assert_eq!(imports["utils"], JsImport::Namespace(index_js.clone()));
+
+    let mut modules = JsModules::default();
+    let target = |file: &Path, name: &str| {
+        CallTarget::InFile(file.to_string_lossy().to_string(), name.to_string())
+    };
+    let call = |receiver: Option<&str>, name: &str| JsCall {
+        receiver: receiver.map(String::from),
+        name: name.to_string(),
+    };
+
+    assert_eq!(
+        modules.call_targets(&call(None, "fmt"), &imports),
+        vec![target(&format_js, "format")]
+    );
+    assert_eq!(
+        modules.call_targets(&call(None, "pad"), &imports),
+        vec![target(&format_js, "pad")]
+    );
+    assert_eq!(
+        modules.call_targets(&call(None, "strip"), &imports),
+        vec![target(&strings_js, "trim")]
+    );
+    assert_eq!(
+        modules.call_targets(&call(Some("utils"), "trim"), &imports),
+        vec![
+            target(&index_js, "trim"),
+            target(&strings_js, "trim"),
+            target(&format_js, "trim")
+        ]
+    );
+    assert!(modules
+        .call_targets(&call(None, "expect"), &imports)
+        .is_empty());
+}
@@ -0,0 +1,975 @@
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::LazyLock;
+use walkdir::WalkDir;
+
+use crate::report::split_package;
+use crate::utils::{
+    extract_body, get_parser, repo_relative_path, should_skip_dir, CallTarget, Method,
+    TestedMethods,
+};
+
+const KOTLIN_TEST_ANNOTATIONS: [&str; 4] =
+    ["Test", "ParameterizedTest", "RepeatedTest", "TestFactory"];
+const KOTLIN_DISABLED_ANNOTATIONS: [&str; 2] = ["Disabled", "Ignore"];
+// Kotest blocks that are tests, and blocks that only group tests
+const KOTEST_TESTS: [&str; 6] = ["test", "should", "it", "expect", "then", "scenario"];
+const KOTEST_CONTAINERS: [&str; 5] = ["context", "describe", "given", "feature", "when"];
+// Declarations whose members are collected; function bodies, lambdas and property
+// initializers are left alone, so local functions aren't counted
+const KOTLIN_MEMBER_SCOPES: [&str; 4] = [
+    "source_file",
+    "class_body",
+    "enum_class_body",
+    "companion_object",
+];
+
+static TEST_SOURCE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(^|/)src/\w*[tT]est/").unwrap());
+
+/// Gradle and Maven keep test sources under `src/test/`, `src/androidTest/`,
+/// `src/commonTest/` and so on.
+pub fn is_kotlin_test_source(repo: &str, path: &Path) -> bool {
+    let path = repo_relative_path(repo, path)
+        .to_string_lossy()
+        .replace('\\', "/");
+    TEST_SOURCE.is_match(&path)
+}
+
+/// Collects top-level functions, class, object and companion members, and extension
+/// functions. Class names are package-qualified; companion members belong to their
+/// class, extension functions are named after their receiver type and top-level
+/// functions only carry the package.
+pub fn traverse_kotlin_nodes(
+    node: tree_sitter::Node,
+    source_code: &str,
+    file_path: &str,
+    is_test_source: bool,
+    methods: &mut Vec<Method>,
+) {
+    let package = kotlin_package(node, source_code);
+    collect_kotlin_members(
+        node,
+        source_code,
+        file_path,
+        &package,
+        &mut Vec::new(),
+        is_test_source,
+        methods,
+    );
+}
+
+fn collect_kotlin_members(
+    node: tree_sitter::Node,
+    source_code: &str,
+    file_path: &str,
+    package: &str,
+    classes: &mut Vec<String>,
+    is_test_source: bool,
+    methods: &mut Vec<Method>,
+) {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+
+    match node.kind() {
+        "class_declaration" | "object_declaration" => {
+            let Some(name) = node.child_by_field_name("name") else {
+                return;
+            };
+            classes.push(text(name));
+            let mut cursor = node.walk();
+            for body in node
+                .children(&mut cursor)
+                .filter(|c| matches!(c.kind(), "class_body" | "enum_class_body"))
+            {
+                collect_kotlin_members(
+                    body,
+                    source_code,
+                    file_path,
+                    package,
+                    classes,
+                    is_test_source,
+                    methods,
+                );
+            }
+            classes.pop();
+        }
+        // Abstract and interface functions have no body to cover
+        "function_declaration" => {
+            let (Some(name), true) = (
+                node.child_by_field_name("name"),
+                has_child(node, "function_body"),
+            ) else {
+                return;
+            };
+            let class = match (kotlin_receiver(node, source_code), classes.is_empty()) {
+                (Some(receiver), true) => receiver,
+                _ => classes.join("."),
+            };
+            methods.push(Method {
+                class_name: qualify(package, &class),
+                method_name: text(name).trim_matches('`').to_string(),
+                body: extract_body(node, source_code),
+                start_line: node.start_position().row + 1,
+                // Helpers in test sources aren't logic to be covered
+                is_test: is_test_source
+                    || kotlin_annotations(node, source_code)
+                        .iter()
+                        .any(|a| KOTLIN_TEST_ANNOTATIONS.contains(&a.as_str())),
+                file_path: file_path.to_string(),
+            });
+        }
+        kind if KOTLIN_MEMBER_SCOPES.contains(&kind) => {
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                collect_kotlin_members(
+                    child,
+                    source_code,
+                    file_path,
+                    package,
+                    classes,
+                    is_test_source,
+                    methods,
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+fn has_child(node: tree_sitter::Node, kind: &str) -> bool {
+    let mut cursor = node.walk();
+    let found = node.children(&mut cursor).any(|c| c.kind() == kind);
+    found
+}
+
+fn qualify(package: &str, name: &str) -> String {
+    match (package.is_empty(), name.is_empty()) {
+        (true, _) => name.to_string(),
+        (false, true) => package.to_string(),
+        (false, false) => format!("{}.{}", package, name),
+    }
+}
+
+fn kotlin_package(root: tree_sitter::Node, source_code: &str) -> String {
+    let mut cursor = root.walk();
+    let package = root
+        .named_children(&mut cursor)
+        .find(|n| n.kind() == "package_header")
+        .and_then(|n| n.named_child(0))
+        .map(|n| source_code[n.start_byte()..n.end_byte()].to_string())
+        .unwrap_or_default();
+    package
+}
+
+// The type before the name of an extension function, `String` in `fun String?.shout()`
+fn kotlin_receiver(node: tree_sitter::Node, source_code: &str) -> Option<String> {
+    let name = node.child_by_field_name("name")?;
+    let mut cursor = node.walk();
+    let receiver = node
+        .children(&mut cursor)
+        .take_while(|c| *c != name)
+        .filter(|c| matches!(c.kind(), "user_type" | "nullable_type"))
+        .last()
+        .map(|c| {
+            let receiver = &source_code[c.start_byte()..c.end_byte()];
+            receiver
+                .split('<')
+                .next()
+                .unwrap_or(receiver)
+                .trim_end_matches('?')
+                .to_string()
+        });
+    receiver
+}
+
+// Simple names of the annotations on a declaration; `@org.junit.Test` gives "Test"
+fn kotlin_annotations(node: tree_sitter::Node, source_code: &str) -> Vec<String> {
+    let mut cursor = node.walk();
+    let Some(modifiers) = node.children(&mut cursor).find(|n| n.kind() == "modifiers") else {
+        return Vec::new();
+    };
+
+    let mut cursor = modifiers.walk();
+    let annotations = modifiers
+        .named_children(&mut cursor)
+        .filter(|n| n.kind() == "annotation")
+        .filter_map(|annotation| {
+            let mut cursor = annotation.walk();
+            let target = annotation
+                .named_children(&mut cursor)
+                .find_map(|n| match n.kind() {
+                    "user_type" => Some(n),
+                    "constructor_invocation" => n.named_child(0),
+                    _ => None,
+                });
+            target
+        })
+        .map(|n| {
+            let name = &source_code[n.start_byte()..n.end_byte()];
+            name.rsplit('.').next().unwrap_or(name).to_string()
+        })
+        .collect();
+    annotations
+}
+
+// A call made in a test body
+#[derive(PartialEq, Debug)]
+enum KotlinCall {
+    // `slugify(x)`
+    Unqualified(String),
+    // `calc.add(1, 2)` or `Calculator.create()`
+    On(String, String),
+    // `"text".slugify()`, `repo().save(x)` and other receivers
+    OnExpression(String),
+}
+
+impl KotlinCall {
+    fn name(&self) -> &str {
+        match self {
+            KotlinCall::Unqualified(name)
+            | KotlinCall::On(_, name)
+            | KotlinCall::OnExpression(name) => name,
+        }
+    }
+}
+
+/// Finds JUnit and kotlin.test `@Test` functions (including `runTest` coroutine tests)
+/// and Kotest `StringSpec`/`FunSpec` style tests, and resolves their calls using the
+/// declared types of variables and the file's imports.
+pub fn extract_kotlin_tested_methods(repo: &str, logic_methods: &[Method]) -> TestedMethods {
+    let mut tested_methods = TestedMethods::default();
+    let candidates: Vec<(&Method, String, String)> = logic_methods
+        .iter()
+        .filter(|m| !m.is_test)
+        .map(|m| {
+            let (package, class) = split_package(&m.class_name);
+            (m, package, class)
+        })
+        .collect();
+
+    for entry in WalkDir::new(repo)
+        .into_iter()
+        .filter_entry(|e| !should_skip_dir(e))
+        .filter_map(Result::ok)
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "kt"))
+    {
+        let Ok(source_code) = fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let mut parser = get_parser("kt");
+        let Some(tree) = parser.parse(&source_code, None) else {
+            continue;
+        };
+        let root = tree.root_node();
+
+        let package = kotlin_package(root, &source_code);
+        let imports = kotlin_imports(root, &source_code);
+        let mut variables = HashMap::new();
+        collect_kotlin_variable_types(root, &source_code, &mut variables);
+        let mut tests = Vec::new();
+        collect_kotlin_tests(root, &source_code, &mut Vec::new(), false, &mut tests);
+
+        for (test_name, body) in tests {
+            let mut calls = Vec::new();
+            collect_kotlin_calls(body, &source_code, &mut calls);
+            for call in calls {
+                let receiver_type = match &call {
+                    KotlinCall::On(on, _) => variables.get(on).cloned().or_else(|| {
+                        on.starts_with(|c: char| c.is_uppercase())
+                            .then(|| on.clone())
+                    }),
+                    _ => None,
+                };
+
+                let matching: Vec<&(&Method, String, String)> = candidates
+                    .iter()
+                    .filter(|(m, _, class)| {
+                        m.method_name == call.name()
+                            && match (&call, &receiver_type) {
+                                (KotlinCall::Unqualified(_), _) => class.is_empty(),
+                                (_, Some(receiver_type)) => {
+                                    class.rsplit('.').next() == Some(receiver_type.as_str())
+                                }
+                                _ => !class.is_empty(),
+                            }
+                    })
+                    .collect();
+
+                // Prefer top-level functions from the test's own package or its imports
+                let visible: Vec<&&(&Method, String, String)> = matching
+                    .iter()
+                    .filter(|(m, method_package, _)| {
+                        *method_package == package
+                            || imports.iter().any(|i| {
+                                *i == format!("{}.{}", method_package, m.method_name)
+                                    || *i == format!("{}.*", method_package)
+                            })
+                    })
+                    .collect();
+                let resolved: Vec<&Method> =
+                    if matches!(call, KotlinCall::Unqualified(_)) && !visible.is_empty() {
+                        visible.iter().map(|(m, _, _)| *m).collect()
+                    } else {
+                        matching.iter().map(|(m, _, _)| *m).collect()
+                    };
+
+                for method in resolved {
+                    tested_methods.record(CallTarget::exact(method), &test_name);
+                }
+            }
+        }
+    }
+
+    tested_methods
+}
+
+// `import com.example.clamp` and `import com.example.*`, as written
+fn kotlin_imports(root: tree_sitter::Node, source_code: &str) -> Vec<String> {
+    let mut cursor = root.walk();
+    let imports = root
+        .named_children(&mut cursor)
+        .filter(|n| n.kind() == "import")
+        .filter_map(|import| {
+            let path = import.named_child(0)?;
+            let path = &source_code[path.start_byte()..path.end_byte()];
+            Some(
+                if source_code[import.start_byte()..import.end_byte()].ends_with('*') {
+                    format!("{}.*", path)
+                } else {
+                    path.to_string()
+                },
+            )
+        })
+        .collect();
+    imports
+}
+
+// Simple class name of a type or constructor call, e.g. `Calc<Int>?` gives "Calc",
+// `com.example.Cart.Line` gives "Line" and `Cart.empty` gives "Cart"
+fn kotlin_type_name(name: &str) -> Option<String> {
+    let name = name.split('<').next().unwrap_or(name).trim_end_matches('?');
+    name.split('.')
+        .map(str::trim)
+        .skip_while(|s| !s.starts_with(|c: char| c.is_uppercase()))
+        .take_while(|s| s.starts_with(|c: char| c.is_uppercase()))
+        .last()
+        .map(String::from)
+}
+
+// Type of a value a variable is initialized with: a constructor call, a companion
+// factory such as `Cart.empty()`, taken to return a `Cart`, or `by lazy { Cart() }`
+fn kotlin_expression_type(node: tree_sitter::Node, source_code: &str) -> Option<String> {
+    let text = |n: tree_sitter::Node| &source_code[n.start_byte()..n.end_byte()];
+
+    match node.kind() {
+        "call_expression" => {
+            let callee = node.named_child(0)?;
+            if callee.kind() == "identifier" && text(callee) == "lazy" {
+                let lambda = node.named_child(node.named_child_count().checked_sub(1)?)?;
+                let lambda = lambda.named_child(0)?;
+                let last = lambda.named_child(lambda.named_child_count().checked_sub(1)?)?;
+                return kotlin_expression_type(last, source_code);
+            }
+            matches!(callee.kind(), "identifier" | "navigation_expression")
+                .then(|| kotlin_type_name(text(callee)))
+                .flatten()
+        }
+        "property_delegate" => kotlin_expression_type(node.named_child(0)?, source_code),
+        _ => None,
+    }
+}
+
+// Declared or constructed types of the properties, locals and parameters in a file
+fn collect_kotlin_variable_types(
+    node: tree_sitter::Node,
+    source_code: &str,
+    types: &mut HashMap<String, String>,
+) {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+    let type_of = |n: tree_sitter::Node| kotlin_type_name(&text(n));
+    let is_type = |n: &tree_sitter::Node| matches!(n.kind(), "user_type" | "nullable_type");
+
+    match node.kind() {
+        "parameter" | "class_parameter" => {
+            let mut cursor = node.walk();
+            let children: Vec<tree_sitter::Node> = node.named_children(&mut cursor).collect();
+            let name = children.iter().find(|c| c.kind() == "identifier");
+            let declared = children
+                .iter()
+                .find(|c| is_type(c))
+                .and_then(|t| type_of(*t));
+            if let (Some(name), Some(declared)) = (name, declared) {
+                types.insert(text(*name), declared);
+            }
+        }
+        "property_declaration" => {
+            let mut cursor = node.walk();
+            let children: Vec<tree_sitter::Node> = node.named_children(&mut cursor).collect();
+            if let Some(variable) = children.iter().find(|c| c.kind() == "variable_declaration") {
+                let mut cursor = variable.walk();
+                let parts: Vec<tree_sitter::Node> = variable.named_children(&mut cursor).collect();
+                let declared = parts.iter().find(|c| is_type(c)).and_then(|t| type_of(*t));
+                let inferred = || {
+                    children
+                        .iter()
+                        .skip_while(|c| *c != variable)
+                        .skip(1)
+                        .find_map(|value| kotlin_expression_type(*value, source_code))
+                };
+                if let Some(declared) = declared.or_else(inferred) {
+                    types.insert(text(parts[0]), declared);
+                }
+            }
+        }
+        _ => {}
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_kotlin_variable_types(child, source_code, types);
+    }
+}
+
+// Finds enabled `@Test` functions and Kotest tests, returning each test's name with
+// the node holding its body
+fn collect_kotlin_tests<'a>(
+    node: tree_sitter::Node<'a>,
+    source_code: &str,
+    classes: &mut Vec<String>,
+    disabled: bool,
+    tests: &mut Vec<(String, tree_sitter::Node<'a>)>,
+) {
+    let is_disabled = |n: tree_sitter::Node| {
+        kotlin_annotations(n, source_code)
+            .iter()
+            .any(|a| KOTLIN_DISABLED_ANNOTATIONS.contains(&a.as_str()))
+    };
+
+    match node.kind() {
+        "class_declaration" | "object_declaration" => {
+            let Some(name) = node.child_by_field_name("name") else {
+                return;
+            };
+            let name = source_code[name.start_byte()..name.end_byte()].to_string();
+            // Kotest tests only live inside a spec class
+            if is_kotest_spec(node, source_code) {
+                collect_kotest_tests(node, source_code, &mut vec![name.clone()], false, tests);
+            }
+
+            classes.push(name);
+            let disabled = disabled || is_disabled(node);
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                collect_kotlin_tests(child, source_code, classes, disabled, tests);
+            }
+            classes.pop();
+        }
+        "function_declaration" => {
+            let is_test = kotlin_annotations(node, source_code)
+                .iter()
+                .any(|a| KOTLIN_TEST_ANNOTATIONS.contains(&a.as_str()));
+            let mut cursor = node.walk();
+            let body = node
+                .children(&mut cursor)
+                .find(|c| c.kind() == "function_body");
+            if let (true, Some(name), Some(body)) = (
+                is_test && !disabled && !is_disabled(node),
+                node.child_by_field_name("name"),
+                body,
+            ) {
+                let mut path = classes.clone();
+                path.push(
+                    source_code[name.start_byte()..name.end_byte()]
+                        .trim_matches('`')
+                        .to_string(),
+                );
+                tests.push((path.join("."), body));
+            }
+        }
+        _ => {
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                collect_kotlin_tests(child, source_code, classes, disabled, tests);
+            }
+        }
+    }
+}
+
+// Whether a class extends a Kotest spec such as `StringSpec` or `FunSpec`
+fn is_kotest_spec(node: tree_sitter::Node, source_code: &str) -> bool {
+    let mut cursor = node.walk();
+    let Some(supertypes) = node
+        .children(&mut cursor)
+        .find(|c| c.kind() == "delegation_specifiers")
+    else {
+        return false;
+    };
+
+    let mut cursor = supertypes.walk();
+    let is_spec = supertypes.named_children(&mut cursor).any(|supertype| {
+        let supertype = match supertype.named_child(0) {
+            Some(invocation) if invocation.kind() == "constructor_invocation" => {
+                invocation.named_child(0)
+            }
+            other => other,
+        };
+        supertype.is_some_and(|t| {
+            let name = &source_code[t.start_byte()..t.end_byte()];
+            let name = name.split('<').next().unwrap_or(name);
+            name.rsplit('.').next().unwrap_or(name).ends_with("Spec")
+        })
+    });
+    is_spec
+}
+
+// A Kotest block taking a lambda: `"name" { }`, `test("name") { }` or either with
+// `.config(...)`. Gives the function (empty for a bare string), the title and whether
+// the block is switched off with an `x` prefix or `.config(enabled = false)`.
+fn kotest_block(node: tree_sitter::Node, source_code: &str) -> Option<(String, String, bool)> {
+    let text = |n: tree_sitter::Node| &source_code[n.start_byte()..n.end_byte()];
+    let title = |n: tree_sitter::Node| text(n).trim_matches('"').to_string();
+
+    let lambda = node.named_child(node.named_child_count().checked_sub(1)?)?;
+    if node.kind() != "call_expression" || lambda.kind() != "annotated_lambda" {
+        return None;
+    }
+
+    let mut callee = node.named_child(0)?;
+    let mut config = None;
+    if let Some(navigation) = callee
+        .named_child(0)
+        .filter(|n| callee.kind() == "call_expression" && n.kind() == "navigation_expression")
+    {
+        let member = navigation.named_child(navigation.named_child_count() - 1)?;
+        if text(member) == "config" {
+            config = callee.named_child(1).map(text);
+            callee = navigation.named_child(0)?;
+        }
+    }
+
+    let (function, title) = match callee.kind() {
+        "string_literal" => (String::new(), title(callee)),
+        "call_expression" => {
+            let function = callee.named_child(0).filter(|f| f.kind() == "identifier")?;
+            let first_argument = callee.named_child(1)?.named_child(0)?.named_child(0)?;
+            if first_argument.kind() != "string_literal" {
+                return None;
+            }
+            (text(function).to_string(), title(first_argument))
+        }
+        _ => return None,
+    };
+    let skipped = function.starts_with('x')
+        || config.is_some_and(|c| c.replace(char::is_whitespace, "").contains("enabled=false"));
+    Some((function, title, skipped))
+}
+
+// Kotest tests are named after the spec and the containers above them, e.g.
+// `ClampSpec > clamp > keeps values in range`
+fn collect_kotest_tests<'a>(
+    node: tree_sitter::Node<'a>,
+    source_code: &str,
+    path: &mut Vec<String>,
+    skipped: bool,
+    tests: &mut Vec<(String, tree_sitter::Node<'a>)>,
+) {
+    if let Some((function, title, block_skipped)) = kotest_block(node, source_code) {
+        let lambda = node.named_child(node.named_child_count() - 1).unwrap();
+        let kind = function.trim_start_matches('x');
+        let skipped = skipped || block_skipped;
+        if function.is_empty() || KOTEST_TESTS.contains(&kind) {
+            if !skipped {
+                tests.push((format!("{} > {}", path.join(" > "), title), lambda));
+            }
+            return;
+        }
+        if KOTEST_CONTAINERS.contains(&kind) {
+            path.push(title);
+            collect_kotest_tests(lambda, source_code, path, skipped, tests);
+            path.pop();
+            return;
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_kotest_tests(child, source_code, path, skipped, tests);
+    }
+}
+
+fn collect_kotlin_calls(node: tree_sitter::Node, source_code: &str, calls: &mut Vec<KotlinCall>) {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+
+    let call = match node.named_child(0) {
+        Some(callee) if node.kind() == "call_expression" => match callee.kind() {
+            "identifier" => Some(KotlinCall::Unqualified(text(callee))),
+            "navigation_expression" => {
+                let name = callee.named_child(callee.named_child_count() - 1);
+                let receiver = callee
+                    .named_child(0)
+                    .and_then(|receiver| match receiver.kind() {
+                        "identifier" => Some(receiver),
+                        // `this.calc.add()` is made on the `calc` property
+                        "navigation_expression"
+                            if receiver
+                                .named_child(0)
+                                .is_some_and(|r| r.kind() == "this_expression") =>
+                        {
+                            receiver.named_child(receiver.named_child_count() - 1)
+                        }
+                        _ => None,
+                    });
+                match (receiver, name) {
+                    (_, Some(name)) if name.kind() != "identifier" => None,
+                    (Some(receiver), Some(name)) => {
+                        Some(KotlinCall::On(text(receiver), text(name)))
+                    }
+                    (None, Some(name)) => Some(KotlinCall::OnExpression(text(name))),
+                    _ => None,
+                }
+            }
+            _ => None,
+        },
+        _ => None,
+    };
+
+    if let Some(call) = call {
+        if !calls.contains(&call) {
+            calls.push(call);
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_kotlin_calls(child, source_code, calls);
+    }
+}
+
+#[test]
+fn test_traverse_kotlin_nodes() {
+    let source_code = r#"
+package com.example
+
+import kotlin.math.abs
+
+fun String.slugify(): String = lowercase().replace(" ", "-")
+
+fun clamp(x: Int, min: Int, max: Int): Int {
+    fun helper() = 1 // local, not counted
+    return if (x < min) min else if (x > max) max else x
+}
+
+class Calculator(private val precision: Int) {
+    fun add(a: Int, b: Int) = a + b
+
+    /* fun commented(): Int = 0 */
+    val label = "fun notAFunction() {"
+
+    companion object {
+        fun create(): Calculator = Calculator(2)
+    }
+
+    inner class Memory { fun clear() { } }
+}
+
+interface Shape {
+    fun area(): Double
+    fun name(): String = "shape"
+}
+
+object Registry {
+    fun <T> register(item: T) {
+        listOf(item).forEach { println(it) }
+    }
+}
+"#;
+    let mut parser = get_parser("kt");
+    let tree = parser.parse(source_code, None).unwrap();
+    let mut methods = Vec::new();
+    traverse_kotlin_nodes(
+        tree.root_node(),
+        source_code,
+        "src/main/kotlin/Calculator.kt",
+        false,
+        &mut methods,
+    );
+
+    let found: Vec<(&str, &str)> = methods
+        .iter()
+        .map(|m| (m.class_name.as_str(), m.method_name.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("com.example.String", "slugify"),
+            ("com.example", "clamp"),
+            ("com.example.Calculator", "add"),
+            ("com.example.Calculator", "create"),
+            ("com.example.Calculator.Memory", "clear"),
+            ("com.example.Shape", "name"),
+            ("com.example.Registry", "register"),
+        ]
+    );
+    assert_eq!(methods[2].start_line, 14);
+    assert_eq!(methods[2].body, vec!["fun add(a: Int, b: Int) = a + b"]);
+    assert_eq!(
+        split_package("com.example.Calculator.Memory"),
+        (
+            String::from("com.example"),
+            String::from("Calculator.Memory")
+        )
+    );
+    assert!(is_kotlin_test_source(
+        ".",
+        Path::new("app/src/androidTest/java/com/example/CalculatorTest.kt")
+    ));
+    assert!(!is_kotlin_test_source(
+        ".",
+        Path::new("app/src/main/java/com/example/Calculator.kt")
+    ));
+    // A repo checked out inside another project's test sources
+    assert!(!is_kotlin_test_source(
+        "/work/src/test/fixture",
+        Path::new("/work/src/test/fixture/src/main/kotlin/Calculator.kt")
+    ));
+}
+
+#[test]
+fn test_extract_kotlin_tested_methods() {
+    use std::fs::create_dir_all;
+    use tempfile::tempdir;
+
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    // Nested in another checkout's test sources, which mustn't make it all test code
+    let root = temp_dir.path().join("src/test/fixture");
+    let main = root.join("src/main/kotlin/com/example");
+    let test = root.join("src/test/kotlin/com/example");
+    create_dir_all(&main).unwrap();
+    create_dir_all(&test).unwrap();
+    fs::write(
+        main.join("Calculator.kt"),
+        r#"package com.example
+
+fun String.slugify(): String = lowercase()
+
+fun clamp(x: Int): Int = x
+
+class Calculator {
+    fun add(a: Int, b: Int) = a + b
+    fun sub(a: Int, b: Int) = a - b
+    fun mul(a: Int, b: Int) = a * b
+    fun div(a: Int, b: Int) = a / b
+    suspend fun load(): Int = 1
+    fun reset() { }
+}
+
+class Memory {
+    fun reset() { }
+}
+"#,
+    )
+    .unwrap();
+    fs::write(
+        test.join("CalculatorTest.kt"),
+        r#"package com.example
+
+class CalculatorTest {
+    private val calc = Calculator()
+    private val memory by lazy { Memory() }
+
+    @Test
+    fun `adds numbers`() {
+        assertEquals(3, calc.add(1, 2))
+        memory.reset()
+    }
+
+    @Test
+    fun loads() = runTest {
+        this.calc.load()
+    }
+
+    @Ignore
+    @Test
+    fun subtracts() { calc.sub(2, 1) }
+
+    fun helper() = clamp(1)
+}
+
+class CalculatorSpec : StringSpec({
+    "multiplies" {
+        Calculator().mul(2, 3)
+        "A B".slugify()
+    }
+    "divides".config(enabled = false) { Calculator().div(4, 2) }
+})
+
+class ClampSpec : FunSpec({
+    context("clamp") {
+        test("keeps values in range") { clamp(1) }
+        xtest("skipped") { clamp(2) }
+    }
+})
+"#,
+    )
+    .unwrap();
+
+    let mut methods = Vec::new();
+    for file in [main.join("Calculator.kt"), test.join("CalculatorTest.kt")] {
+        let source_code = fs::read_to_string(&file).unwrap();
+        let tree = get_parser("kt").parse(&source_code, None).unwrap();
+        traverse_kotlin_nodes(
+            tree.root_node(),
+            &source_code,
+            &file.to_string_lossy(),
+            is_kotlin_test_source(&root.to_string_lossy(), &file),
+            &mut methods,
+        );
+    }
+
+    let tested = extract_kotlin_tested_methods(&root.to_string_lossy(), &methods);
+    let tests_for = |class: &str, name: &str| {
+        let method = methods
+            .iter()
+            .find(|m| !m.is_test && m.class_name.ends_with(class) && m.method_name == name)
+            .unwrap();
+        tested.tests_for(method)
+    };
+
+    assert_eq!(
+        tests_for("Calculator", "add"),
+        vec!["CalculatorTest.adds numbers"]
+    );
+    assert_eq!(
+        tests_for("Calculator", "load"),
+        vec!["CalculatorTest.loads"]
+    );
+    assert!(tests_for("Calculator", "sub").is_empty());
+    // Only the reset on the lazily created Memory is called
+    assert_eq!(
+        tests_for("Memory", "reset"),
+        vec!["CalculatorTest.adds numbers"]
+    );
+    assert!(tests_for("Calculator", "reset").is_empty());
+    assert_eq!(
+        tests_for("Calculator", "mul"),
+        vec!["CalculatorSpec > multiplies"]
+    );
+    assert_eq!(
+        tests_for("String", "slugify"),
+        vec!["CalculatorSpec > multiplies"]
+    );
+    assert!(tests_for("Calculator", "div").is_empty());
+    assert_eq!(
+        tests_for("example", "clamp"),
+        vec!["ClampSpec > clamp > keeps values in range"]
+    );
+}
+
+#[test]
+fn test_is_kotlin_test_source() {
+    // Test sources of a repo nested inside another project's test sources
+    let repo = "/work/src/androidTest/assets/app";
+    assert!(is_kotlin_test_source(
+        repo,
+        Path::new("/work/src/androidTest/assets/app/src/commonTest/kotlin/AppTest.kt")
+    ));
+    assert!(!is_kotlin_test_source(
+        repo,
+        Path::new("/work/src/androidTest/assets/app/src/main/kotlin/App.kt")
+    ));
+    // Files outside the repo root are judged on their whole path
+    assert!(is_kotlin_test_source(
+        repo,
+        Path::new("/elsewhere/src/test/kotlin/OtherTest.kt")
+    ));
+    assert!(!is_kotlin_test_source(
+        repo,
+        Path::new("/elsewhere/src/main/kotlin/Other.kt")
+    ));
+}
+
+#[test]
+fn test_kotlin_disabled_and_overloaded_tests() {
+    use tempfile::tempdir;
+
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let root = temp_dir.path();
+    fs::write(
+        root.join("Calculator.kt"),
+        r#"class Calculator {
+    fun add(a: Int, b: Int) = a + b
+    fun add(a: Double, b: Double) = a + b
+    fun sub(a: Int, b: Int) = a - b
+}
+"#,
+    )
+    .unwrap();
+    let test_source = r#"class CalculatorTest {
+    @Test fun adds() { Calculator().add(1, 2) }
+    @org.junit.Ignore @Test fun ignored() { Calculator().sub(2, 1) }
+    @Disabled("later") @Test fun disabled() { Calculator().sub(2, 1) }
+}
+
+@Disabled
+class SkippedTest {
+    @Test fun subtracts() { Calculator().sub(2, 1) }
+}
+
+class CalculatorSpec : DescribeSpec({
+    describe("sub") {
+        xit("skipped") { Calculator().sub(2, 1) }
+        it("disabled").config(enabled = false) { Calculator().sub(2, 1) }
+    }
+    xdescribe("skipped group") {
+        it("inside") { Calculator().sub(2, 1) }
+    }
+    describe("add") {
+        it("runs") { Calculator().add(1, 2) }
+    }
+})
+"#;
+    fs::write(root.join("CalculatorTest.kt"), test_source).unwrap();
+
+    let tree = get_parser("kt").parse(test_source, None).unwrap();
+    let mut tests = Vec::new();
+    collect_kotlin_tests(
+        tree.root_node(),
+        test_source,
+        &mut Vec::new(),
+        false,
+        &mut tests,
+    );
+    let names: Vec<&str> = tests.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec!["CalculatorTest.adds", "CalculatorSpec > add > runs"]
+    );
+
+    let mut methods = Vec::new();
+    let file = root.join("Calculator.kt");
+    let source_code = fs::read_to_string(&file).unwrap();
+    let tree = get_parser("kt").parse(&source_code, None).unwrap();
+    traverse_kotlin_nodes(
+        tree.root_node(),
+        &source_code,
+        &file.to_string_lossy(),
+        false,
+        &mut methods,
+    );
+
+    // Overloads aren't told apart by their arguments, so a call covers each of them
+    let tested = extract_kotlin_tested_methods(&root.to_string_lossy(), &methods);
+    let adds: Vec<Vec<String>> = methods
+        .iter()
+        .filter(|m| m.method_name == "add")
+        .map(|m| tested.tests_for(m))
+        .collect();
+    assert_eq!(adds.len(), 2);
+    assert!(adds
+        .iter()
+        .all(|tests| tests.contains(&String::from("CalculatorTest.adds"))));
+    let sub = methods.iter().find(|m| m.method_name == "sub").unwrap();
+    assert!(tested.tests_for(sub).is_empty());
+}
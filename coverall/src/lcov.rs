@@ -0,0 +1,136 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+use crate::report::{CoverageResults, LineHits, MethodCoverage};
+
+// Function names have to be unique within a file, so methods keep their class even
+// in languages whose text report leaves it out
+fn function_name(method: &MethodCoverage) -> String {
+    if method.method.class_name.is_empty() {
+        method.method.method_name.clone()
+    } else {
+        format!("{}.{}", method.method.class_name, method.method.method_name)
+    }
+}
+
+// Overloads share a name, so each of them gets its start line appended
+fn function_names(methods: &[&MethodCoverage]) -> Vec<String> {
+    let names: Vec<String> = methods.iter().map(|m| function_name(m)).collect();
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for name in &names {
+        *counts.entry(name).or_default() += 1;
+    }
+    names
+        .iter()
+        .zip(methods)
+        .map(|(name, method)| match counts[name.as_str()] {
+            1 => name.clone(),
+            _ => format!("{}:{}", name, method.method.start_line),
+        })
+        .collect()
+}
+
+/// Renders the results as an LCOV tracefile, with a record per source file listing
+/// its methods. A method's `FNDA` count is the number of tests calling it. With
+/// `with_lines`, `DA` records give every line of a method's span the same count.
+pub fn lcov_report(results: &CoverageResults, with_lines: bool) -> String {
+    let mut files: BTreeMap<&str, Vec<&MethodCoverage>> = BTreeMap::new();
+    for method in &results.methods {
+        files.entry(&method.path).or_default().push(method);
+    }
+
+    let mut lcov = String::new();
+    for (path, methods) in files {
+        writeln!(lcov, "TN:").unwrap();
+        writeln!(lcov, "SF:{}", results.root.join(path).display()).unwrap();
+        let names = function_names(&methods);
+        for (method, name) in methods.iter().zip(&names) {
+            writeln!(lcov, "FN:{},{}", method.method.start_line, name).unwrap();
+        }
+        for (method, name) in methods.iter().zip(&names) {
+            writeln!(lcov, "FNDA:{},{}", method.hits(), name).unwrap();
+        }
+        writeln!(lcov, "FNF:{}", methods.len()).unwrap();
+        writeln!(
+            lcov,
+            "FNH:{}",
+            methods.iter().filter(|m| m.is_covered()).count()
+        )
+        .unwrap();
+
+        if with_lines {
+            let mut lines = LineHits::default();
+            for method in &methods {
+                lines.add(method);
+            }
+            for (line, hits) in lines.iter() {
+                writeln!(lcov, "DA:{},{}", line, hits).unwrap();
+            }
+            writeln!(lcov, "LF:{}", lines.valid()).unwrap();
+            writeln!(lcov, "LH:{}", lines.covered()).unwrap();
+        }
+        writeln!(lcov, "end_of_record").unwrap();
+    }
+    lcov
+}
+
+#[test]
+fn test_lcov_report() {
+    use crate::codeanalysis::create_lang_settings;
+    use crate::utils::{CallTarget, Lang, Method, TestedMethods};
+
+    let method = |file: &str, class: &str, name: &str, start_line: usize, lines: usize| Method {
+        class_name: class.to_string(),
+        method_name: name.to_string(),
+        body: vec![String::new(); lines],
+        start_line,
+        is_test: false,
+        file_path: format!("/repo/{}", file),
+    };
+    let methods = vec![
+        method("src/cart.rs", "Cart", "add", 3, 2),
+        method("src/cart.rs", "Cart", "total", 6, 3),
+        method("src/main.rs", "", "main", 1, 2),
+        method("src/price.cs", "Price", "Round", 4, 1),
+        method("src/price.cs", "Price", "Round", 6, 1),
+    ];
+    let mut tests = TestedMethods::default();
+    tests.record(CallTarget::exact(&methods[0]), "test_add");
+    tests.record(CallTarget::exact(&methods[0]), "test_total");
+
+    let lang_settings = create_lang_settings(&Lang::Rust);
+    let results = CoverageResults::new("/repo", &methods, &tests, &lang_settings);
+
+    assert_eq!(
+        lcov_report(&results, false),
+        "TN:
+SF:/repo/src/cart.rs
+FN:3,Cart.add
+FN:6,Cart.total
+FNDA:2,Cart.add
+FNDA:0,Cart.total
+FNF:2
+FNH:1
+end_of_record
+TN:
+SF:/repo/src/main.rs
+FN:1,main
+FNDA:0,main
+FNF:1
+FNH:0
+end_of_record
+TN:
+SF:/repo/src/price.cs
+FN:4,Price.Round:4
+FN:6,Price.Round:6
+FNDA:0,Price.Round:4
+FNDA:0,Price.Round:6
+FNF:2
+FNH:0
+end_of_record
+"
+    );
+
+    let with_lines = lcov_report(&results, true);
+    assert!(with_lines.contains("FNH:1\nDA:3,2\nDA:4,2\nDA:6,0\nDA:7,0\nDA:8,0\nLF:5\nLH:2\n"));
+}
@@ -1,46 +1,177 @@
 use std::env;
+use std::path::{Path, PathBuf};
 use std::result::Result::Ok;
 
 use clap::ArgMatches;
 use cli::get_cli_args;
 
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
 use codeanalysis::start_analysis;
 use colored::*;
 
+pub mod annotations;
+pub mod badge;
 pub mod cli;
+pub mod cobertura;
 pub mod codeanalysis;
 pub mod coverage;
+pub mod cpp;
 pub mod csharp;
+pub mod dotnet;
+pub mod go;
+pub mod html;
+pub mod java;
 pub mod js;
+pub mod jsmodules;
+pub mod kotlin;
+pub mod lcov;
+pub mod markdown;
+pub mod php;
+pub mod querylang;
+pub mod report;
+pub mod ruby;
+pub mod sarif;
+pub mod swift;
 pub mod utils;
 
 fn run() -> Result<()> {
     let cli_args = get_cli_args(env::args_os());
 
-    let command = unwrap_command(cli_args);
+    let command = unwrap_command(cli_args)?;
 
     start_analysis(command);
 
     Ok(())
 }
 
-fn unwrap_command(cli_args: ArgMatches) -> utils::Command {
-    let cmd_lang = cli_args.get_one::<String>("language").unwrap().clone();
-    utils::Command {
-        repo: cli_args.get_one::<String>("repo").unwrap().clone(),
-        lang: if cmd_lang == "csharp" {
-            utils::Lang::Csharp
-        } else if cmd_lang == "python" {
-            utils::Lang::Python
-        } else if cmd_lang == "js" {
-            utils::Lang::JS
-        } else if cmd_lang == "rust" {
-            utils::Lang::Rust
-        } else {
-            utils::Lang::Undefined
-        },
+fn unwrap_command(cli_args: ArgMatches) -> Result<utils::Command> {
+    let cmd_lang = cli_args.get_one::<String>("language").cloned();
+    let config_dir = cli_args
+        .get_one::<String>("config-dir")
+        .map(PathBuf::from)
+        .or_else(querylang::config_dir);
+
+    let badge_args = cli_args.subcommand_matches("badge");
+    if badge_args.is_some() && cli_args.contains_id("format") {
+        bail!("--format can't be used with the badge subcommand");
+    }
+    let format = match cli_args.get_one::<String>("format").map(String::as_str) {
+        _ if badge_args.is_some() => utils::ReportFormat::Badge,
+        Some("cobertura") => utils::ReportFormat::Cobertura,
+        Some("lcov") => utils::ReportFormat::Lcov,
+        Some("html") => utils::ReportFormat::Html,
+        Some("markdown") => utils::ReportFormat::Markdown,
+        Some("sarif") => utils::ReportFormat::Sarif,
+        Some("github") => utils::ReportFormat::Github,
+        Some("annotations") => utils::ReportFormat::Annotations,
+        _ => utils::ReportFormat::Text,
+    };
+    let output = badge_args
+        .unwrap_or(&cli_args)
+        .get_one::<String>("output")
+        .cloned();
+    match (format, &output) {
+        (utils::ReportFormat::Html, None) => {
+            bail!("--format html needs an --output directory to write the report to")
+        }
+        (utils::ReportFormat::Text, Some(_)) => {
+            bail!("--output can't be used with the text report")
+        }
+        _ => {}
     }
+    let baseline = match cli_args.get_one::<String>("baseline") {
+        Some(_)
+            if format != utils::ReportFormat::Markdown && format != utils::ReportFormat::Sarif =>
+        {
+            bail!("--baseline can only be used with --format markdown or sarif")
+        }
+        Some(path) => Some(report::Baseline::load(Path::new(path))?),
+        None => None,
+    };
+    let sarif_levels = cli_args
+        .get_many::<String>("sarif-level")
+        .unwrap_or_default()
+        .map(|value| sarif::parse_rule_level(value))
+        .collect::<Result<Vec<_>>>()?;
+    // A badge is usually made for the checkout it's run in
+    let repo = cli_args
+        .get_one::<String>("repo")
+        .cloned()
+        .or_else(|| badge_args.map(|_| String::from(".")))
+        .ok_or_else(|| anyhow!("--repo is required"))?;
+    let changed_files = match cli_args.get_one::<String>("changed-since") {
+        Some(_)
+            if format != utils::ReportFormat::Github
+                && format != utils::ReportFormat::Annotations =>
+        {
+            bail!("--changed-since can only be used with --format github or annotations")
+        }
+        Some(base) => Some(annotations::changed_files(&repo, base)?),
+        None => None,
+    };
+
+    // Left undefined when not given, so the language is detected from the repo. A
+    // language defined in the config directory takes precedence over a built-in one
+    // of the same name
+    let query_language = match (&cmd_lang, &config_dir) {
+        (Some(name), Some(dir)) => querylang::load_query_language(dir, name)?,
+        _ => None,
+    };
+    let lang = match cmd_lang {
+        None => utils::Lang::Undefined,
+        Some(name) => match query_language {
+            Some(language) => {
+                language.validate()?;
+                utils::Lang::Query(Box::new(language))
+            }
+            None => match name.as_str() {
+                "csharp" => utils::Lang::Csharp,
+                "js" | "javascript" => utils::Lang::JS,
+                "ts" | "typescript" => utils::Lang::TypeScript,
+                "go" => utils::Lang::Go,
+                "java" => utils::Lang::Java,
+                "kotlin" | "kt" => utils::Lang::Kotlin,
+                "c" | "cpp" | "c++" => utils::Lang::Cpp,
+                "ruby" | "rb" => utils::Lang::Ruby,
+                "php" => utils::Lang::Php,
+                "swift" => utils::Lang::Swift,
+                "rust" => utils::Lang::Rust,
+                "python" => bail!("Python isn't supported yet"),
+                _ => bail!(
+                    "Unknown language {}, expected one of rust, csharp, js, ts, go, java, kotlin, cpp, ruby, php, swift or a language in the config directory",
+                    name
+                ),
+            },
+        },
+    };
+
+    Ok(utils::Command {
+        repo,
+        lang,
+        include_generated: cli_args.get_flag("include-generated"),
+        show_tests: cli_args.get_flag("verbose"),
+        format,
+        lcov_lines: cli_args.get_flag("lcov-lines"),
+        output,
+        baseline,
+        sarif_levels,
+        changed_files,
+        badge: match badge_args {
+            Some(args) => badge::Badge {
+                label: args
+                    .get_one::<String>("label")
+                    .cloned()
+                    .unwrap_or_else(|| String::from("coverage")),
+                thresholds: badge::parse_thresholds(
+                    args.get_one::<String>("thresholds")
+                        .map(String::as_str)
+                        .unwrap_or(badge::DEFAULT_THRESHOLDS),
+                )?,
+            },
+            None => badge::Badge::default(),
+        },
+        config_dir,
+    })
 }
 
 fn main() {
@@ -58,7 +189,7 @@ fn test_unwrap_command() {
     use clap::Arg;
     use clap::Command;
 
-    let matches = Command::new("test")
+    let cli = Command::new("test")
         .arg(
             Arg::new("language")
                 .long("language")
@@ -66,16 +197,79 @@ fn test_unwrap_command() {
                 .num_args(1),
         )
         .arg(Arg::new("repo").long("repo").required(true).num_args(1))
-        .get_matches_from(vec![
-            "test",
-            "--language",
-            "rust",
-            "--repo",
-            "/path/to/repo",
-        ]);
+        .arg(
+            Arg::new("include-generated")
+                .long("include-generated")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("verbose")
+                .short('v')
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(Arg::new("config-dir").long("config-dir").num_args(1))
+        .arg(Arg::new("format").long("format").num_args(1))
+        .arg(Arg::new("output").long("output").num_args(1))
+        .arg(Arg::new("baseline").long("baseline").num_args(1))
+        .arg(
+            Arg::new("sarif-level")
+                .long("sarif-level")
+                .action(clap::ArgAction::Append),
+        )
+        .arg(Arg::new("changed-since").long("changed-since").num_args(1))
+        .subcommand(
+            Command::new("badge")
+                .arg(Arg::new("output").long("output").num_args(1))
+                .arg(Arg::new("label").long("label").num_args(1))
+                .arg(Arg::new("thresholds").long("thresholds").num_args(1)),
+        )
+        .arg(
+            Arg::new("lcov-lines")
+                .long("lcov-lines")
+                .action(clap::ArgAction::SetTrue),
+        );
+    let matches = cli.clone().get_matches_from(vec![
+        "test",
+        "--language",
+        "rust",
+        "--repo",
+        "/path/to/repo",
+        "-v",
+        "--config-dir",
+        "/path/to/config",
+        "--format",
+        "cobertura",
+        "--output",
+        "coverage.xml",
+        "--sarif-level",
+        "untested-method=error",
+    ]);
 
-    let command = unwrap_command(matches);
+    let command = unwrap_command(matches).unwrap();
 
     assert_eq!(command.repo, "/path/to/repo");
     assert_eq!(command.lang, utils::Lang::Rust);
+    assert!(!command.include_generated);
+    assert!(command.show_tests);
+    assert_eq!(command.format, utils::ReportFormat::Cobertura);
+    assert_eq!(command.output.as_deref(), Some("coverage.xml"));
+    assert_eq!(command.config_dir, Some(PathBuf::from("/path/to/config")));
+    assert_eq!(
+        command.sarif_levels,
+        vec![(
+            String::from("coverme/untested-method"),
+            String::from("error")
+        )]
+    );
+
+    let matches = cli.get_matches_from(vec![
+        "test",
+        "--language",
+        "cobol",
+        "--repo",
+        "/path/to/repo",
+        "--config-dir",
+        "/path/to/config",
+    ]);
+    assert!(unwrap_command(matches).is_err());
 }
@@ -0,0 +1,295 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::report::{
+    directory, language_name, Baseline, CoverageResults, MethodCoverage, MethodTotals,
+};
+use crate::utils::CoverageStatus;
+
+// GitHub's limit on the length of a comment, which is the tightest of the code hosts
+const MAX_LENGTH: usize = 65536;
+// Room kept for the closing lines of a report once its lists are cut short
+const RESERVED_LENGTH: usize = 512;
+const TOP_UNCOVERED: usize = 5;
+// Directory tables longer than this are folded away
+const MAX_DIRECTORIES: usize = 10;
+
+fn code(text: &str) -> String {
+    format!("`{}`", text.replace('`', "'"))
+}
+
+fn status_icon(status: &CoverageStatus) -> &'static str {
+    match status {
+        CoverageStatus::Covered => "✅",
+        CoverageStatus::ViaInterface => "🔷",
+        CoverageStatus::Uncovered => "❌",
+    }
+}
+
+fn delta(current: &MethodTotals, baseline: Option<&MethodTotals>) -> String {
+    match baseline {
+        Some(baseline) => format!("{:+.2}%", current.percent() - baseline.percent()),
+        None => String::from("new"),
+    }
+}
+
+fn totals_table(
+    heading: &str,
+    groups: &BTreeMap<String, MethodTotals>,
+    baseline: Option<&BTreeMap<String, MethodTotals>>,
+    markdown: &mut String,
+) {
+    let header = format!("| {} | Methods | Covered | Coverage |", heading);
+    match baseline {
+        Some(_) => {
+            writeln!(markdown, "{} Δ |", header).unwrap();
+            writeln!(markdown, "| --- | ---: | ---: | ---: | ---: |").unwrap();
+        }
+        None => {
+            writeln!(markdown, "{}", header).unwrap();
+            writeln!(markdown, "| --- | ---: | ---: | ---: |").unwrap();
+        }
+    }
+    for (name, totals) in groups {
+        write!(
+            markdown,
+            "| {} | {} | {} | {:.2}% |",
+            code(name).replace('|', "\\|"),
+            totals.total,
+            totals.covered,
+            totals.percent()
+        )
+        .unwrap();
+        if let Some(baseline) = baseline {
+            write!(markdown, " {} |", delta(totals, baseline.get(name))).unwrap();
+        }
+        writeln!(markdown).unwrap();
+    }
+}
+
+// Adds lines until the report would outgrow a comment, then notes how many were left out
+fn write_capped(lines: &[String], markdown: &mut String) {
+    for (i, line) in lines.iter().enumerate() {
+        if markdown.len() + line.len() + RESERVED_LENGTH > MAX_LENGTH {
+            writeln!(markdown, "\n…and {} more", lines.len() - i).unwrap();
+            return;
+        }
+        writeln!(markdown, "{}", line).unwrap();
+    }
+}
+
+/// Renders the results as a Markdown summary sized for a merge request comment:
+/// the overall coverage, tables by language and directory, the largest uncovered
+/// methods, and the full method lists folded into `<details>` sections. Given a
+/// baseline, coverage is compared against it.
+pub fn markdown_report(results: &CoverageResults, baseline: Option<&Baseline>) -> String {
+    let mut totals = MethodTotals::default();
+    let mut languages: BTreeMap<String, MethodTotals> = BTreeMap::new();
+    let mut directories: BTreeMap<String, MethodTotals> = BTreeMap::new();
+    for method in &results.methods {
+        totals.add(method);
+        languages
+            .entry(language_name(&method.path))
+            .or_default()
+            .add(method);
+        directories
+            .entry(directory(&method.path))
+            .or_default()
+            .add(method);
+    }
+
+    let mut markdown = String::new();
+    writeln!(markdown, "## Test Coverage Report").unwrap();
+    writeln!(markdown).unwrap();
+    write!(
+        markdown,
+        "**Method coverage: {:.2}%** ({} of {} methods)",
+        totals.percent(),
+        totals.covered,
+        totals.total
+    )
+    .unwrap();
+    if let Some(baseline) = baseline {
+        let baseline_totals = baseline.totals(|_| String::new());
+        write!(
+            markdown,
+            ", {} against the baseline",
+            delta(&totals, baseline_totals.get(""))
+        )
+        .unwrap();
+    }
+    writeln!(markdown).unwrap();
+    if totals.via_interface > 0 {
+        writeln!(markdown).unwrap();
+        writeln!(
+            markdown,
+            "Including interface dispatch: {:.2}%",
+            totals.percent_with_interface()
+        )
+        .unwrap();
+    }
+
+    writeln!(markdown).unwrap();
+    totals_table(
+        "Language",
+        &languages,
+        baseline.map(|b| b.totals(language_name)).as_ref(),
+        &mut markdown,
+    );
+
+    writeln!(markdown).unwrap();
+    let folded = directories.len() > MAX_DIRECTORIES;
+    if folded {
+        writeln!(
+            markdown,
+            "<details><summary>Coverage by directory ({} directories)</summary>\n",
+            directories.len()
+        )
+        .unwrap();
+    }
+    totals_table(
+        "Directory",
+        &directories,
+        baseline.map(|b| b.totals(directory)).as_ref(),
+        &mut markdown,
+    );
+    if folded {
+        writeln!(markdown, "\n</details>").unwrap();
+    }
+
+    let mut uncovered: Vec<&MethodCoverage> = results
+        .methods
+        .iter()
+        .filter(|m| m.status == CoverageStatus::Uncovered)
+        .collect();
+    if !uncovered.is_empty() {
+        // Stable, so methods of the same size keep their source order
+        uncovered.sort_by_key(|m| std::cmp::Reverse(m.method.body.len()));
+        writeln!(markdown).unwrap();
+        writeln!(markdown, "### Largest uncovered methods").unwrap();
+        writeln!(markdown).unwrap();
+        writeln!(markdown, "| Method | File | Lines |").unwrap();
+        writeln!(markdown, "| --- | --- | ---: |").unwrap();
+        for method in uncovered.iter().take(TOP_UNCOVERED) {
+            writeln!(
+                markdown,
+                "| {} | {} | {} |",
+                code(&method.id).replace('|', "\\|"),
+                code(&format!("{}:{}", method.path, method.method.start_line)).replace('|', "\\|"),
+                method.method.body.len()
+            )
+            .unwrap();
+        }
+    }
+
+    let uncovered_lines: Vec<String> = results
+        .methods
+        .iter()
+        .filter(|m| m.status == CoverageStatus::Uncovered)
+        .map(|m| {
+            format!(
+                "- {} in {}",
+                code(&m.id),
+                code(&format!("{}:{}", m.path, m.method.start_line))
+            )
+        })
+        .collect();
+    let method_lines: Vec<String> = results
+        .methods
+        .iter()
+        .map(|m| {
+            let tests = match m.tests.len() {
+                0 => String::new(),
+                1 => String::from(" (1 test)"),
+                n => format!(" ({} tests)", n),
+            };
+            format!("- {} {}{}", status_icon(&m.status), code(&m.id), tests)
+        })
+        .collect();
+    for (summary, lines) in [
+        ("Uncovered methods", uncovered_lines),
+        ("All methods", method_lines),
+    ] {
+        if lines.is_empty() {
+            continue;
+        }
+        writeln!(markdown).unwrap();
+        writeln!(
+            markdown,
+            "<details><summary>{} ({})</summary>\n",
+            summary,
+            lines.len()
+        )
+        .unwrap();
+        write_capped(&lines, &mut markdown);
+        writeln!(markdown, "\n</details>").unwrap();
+    }
+    markdown
+}
+
+#[test]
+fn test_markdown_report() {
+    use crate::codeanalysis::create_lang_settings;
+    use crate::utils::{CallTarget, Lang, Method, TestedMethods};
+
+    let method = |file: &str, class: &str, name: &str, lines: usize| Method {
+        class_name: class.to_string(),
+        method_name: name.to_string(),
+        body: vec![String::new(); lines],
+        start_line: 1,
+        is_test: false,
+        file_path: format!("/repo/{}", file),
+    };
+    let methods = vec![
+        method("src/cart.rb", "Cart", "add", 3),
+        method("src/cart.rb", "Cart", "total", 8),
+        method("lib/slug.rb", "Slug", "make", 2),
+        method("web/app.js", "App", "start", 4),
+    ];
+    let mut tests = TestedMethods::default();
+    tests.record(CallTarget::exact(&methods[0]), "CartTest.test_add");
+    tests.record(CallTarget::exact(&methods[3]), "starts");
+
+    let lang_settings = create_lang_settings(&Lang::Ruby);
+    let results = CoverageResults::new("/repo", &methods, &tests, &lang_settings);
+    use crate::report::BaselineMethod;
+
+    let baseline = Baseline::parse(
+        r#"<coverage><packages><package name="src"><classes>
+        <class name="Cart" filename="src/cart.rb"><methods>
+          <method name="add" line-rate="0"/><method name="total" line-rate="0"/>
+        </methods></class>
+        <class name="App" filename="web/app.js"><methods>
+          <method name="start" line-rate="0.5"/>
+        </methods></class>
+        </classes></package></packages></coverage>"#,
+    )
+    .unwrap();
+    assert_eq!(
+        baseline.methods[2],
+        BaselineMethod {
+            file: String::from("web/app.js"),
+            class: String::from("App"),
+            name: String::from("start"),
+            covered: true,
+        }
+    );
+    assert_eq!(baseline.was_covered(&results.methods[1]), Some(false));
+    assert_eq!(baseline.was_covered(&results.methods[2]), None);
+
+    let markdown = markdown_report(&results, Some(&baseline));
+    assert!(markdown
+        .contains("**Method coverage: 50.00%** (2 of 4 methods), +16.67% against the baseline\n"));
+    assert!(markdown.contains("| `Ruby` | 3 | 1 | 33.33% | +33.33% |\n"));
+    assert!(markdown.contains("| `JavaScript` | 1 | 1 | 100.00% | +0.00% |\n"));
+    assert!(markdown.contains("| `lib` | 1 | 0 | 0.00% | new |\n"));
+    assert!(markdown.contains(
+        "| Method | File | Lines |\n| --- | --- | ---: |\n| `Cart.total` | `src/cart.rb:1` | 8 |\n| `Slug.make` | `lib/slug.rb:1` | 2 |\n"
+    ));
+    assert!(markdown.contains("<details><summary>Uncovered methods (2)</summary>"));
+    assert!(markdown.contains("- ✅ `Cart.add` (1 test)\n"));
+
+    let without_baseline = markdown_report(&results, None);
+    assert!(without_baseline.contains("| Language | Methods | Covered | Coverage |\n| --- |"));
+    assert!(!without_baseline.contains("baseline"));
+}
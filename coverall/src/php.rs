@@ -0,0 +1,1152 @@
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+use crate::utils::{
+    extract_body, get_parser, repo_relative_path, should_skip_dir, CallTarget, Method,
+    TestedMethods,
+};
+
+const PHP_TYPE_DECLARATIONS: [&str; 4] = [
+    "class_declaration",
+    "trait_declaration",
+    "enum_declaration",
+    "interface_declaration",
+];
+// PHPUnit methods run around every test of a class, and Pest's equivalent
+const PHPUNIT_HOOKS: [&str; 2] = ["setUp", "setUpBeforeClass"];
+const PEST_HOOKS: [&str; 2] = ["beforeEach", "beforeAll"];
+
+/// Test sources live under `tests/` by convention, and PHPUnit test classes are
+/// named `*Test.php`.
+pub fn is_php_test_file(repo: &str, path: &Path) -> bool {
+    let path = repo_relative_path(repo, path);
+    let in_test_dir = path.parent().is_some_and(|dir| {
+        dir.components()
+            .any(|c| c.as_os_str() == "tests" || c.as_os_str() == "test")
+    });
+    in_test_dir
+        || path
+            .file_stem()
+            .is_some_and(|s| s.to_string_lossy().ends_with("Test"))
+}
+
+// The namespace and `use` imports in force at a point of a file
+#[derive(Debug, Default, Clone)]
+struct PhpScope {
+    namespace: String,
+    classes: HashMap<String, String>,
+    functions: HashMap<String, String>,
+}
+
+impl PhpScope {
+    fn qualify(&self, name: &str) -> String {
+        if self.namespace.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}\\{}", self.namespace, name)
+        }
+    }
+
+    // Fully qualified name of a class as written in code
+    fn resolve_class(&self, name: &str) -> String {
+        if let Some(qualified) = name.strip_prefix('\\') {
+            return qualified.to_string();
+        }
+        let (first, rest) = match name.split_once('\\') {
+            Some((first, rest)) => (first, Some(rest)),
+            None => (name, None),
+        };
+        match (self.classes.get(first), rest) {
+            (Some(imported), Some(rest)) => format!("{}\\{}", imported, rest),
+            (Some(imported), None) => imported.clone(),
+            _ => self.qualify(name),
+        }
+    }
+
+    // Names a function call can refer to, in the order PHP tries them: an imported
+    // function, one in the current namespace, then the global one
+    fn resolve_function(&self, name: &str) -> Vec<String> {
+        if let Some(qualified) = name.strip_prefix('\\') {
+            return vec![qualified.to_string()];
+        }
+        if let Some(imported) = self.functions.get(name) {
+            return vec![imported.clone()];
+        }
+        if name.contains('\\') {
+            return vec![self.resolve_class(name)];
+        }
+        vec![self.qualify(name), name.to_string()]
+    }
+
+    fn add_imports(&mut self, declaration: tree_sitter::Node, source_code: &str) {
+        let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+        let mut cursor = declaration.walk();
+        let children: Vec<tree_sitter::Node> = declaration.children(&mut cursor).collect();
+        let is_function = children.iter().any(|c| c.kind() == "function");
+        // `use App\{Cart, Order}` shares a prefix between its clauses
+        let prefix = children
+            .iter()
+            .find(|c| c.kind() == "namespace_name")
+            .map(|p| format!("{}\\", text(*p)))
+            .unwrap_or_default();
+        let clauses: Vec<tree_sitter::Node> = match declaration.child_by_field_name("body") {
+            Some(group) => {
+                let mut cursor = group.walk();
+                group.named_children(&mut cursor).collect()
+            }
+            None => children
+                .into_iter()
+                .filter(|c| c.kind() == "namespace_use_clause")
+                .collect(),
+        };
+
+        for clause in clauses {
+            let mut cursor = clause.walk();
+            let is_function =
+                is_function || clause.children(&mut cursor).any(|c| c.kind() == "function");
+            let Some(name) = clause.named_child(0) else {
+                continue;
+            };
+            let qualified = format!("{}{}", prefix, text(name).trim_start_matches('\\'));
+            let alias = clause
+                .child_by_field_name("alias")
+                .map(text)
+                .unwrap_or_else(|| qualified.rsplit('\\').next().unwrap_or("").to_string());
+            if is_function {
+                self.functions.insert(alias, qualified);
+            } else {
+                self.classes.insert(alias, qualified);
+            }
+        }
+    }
+}
+
+// Top-level declarations of a file with the scope they are declared in, following
+// both `namespace Foo;` and `namespace Foo { ... }` forms
+fn php_declarations<'a>(
+    root: tree_sitter::Node<'a>,
+    source_code: &str,
+) -> Vec<(tree_sitter::Node<'a>, PhpScope)> {
+    let mut declarations = Vec::new();
+    let mut scope = PhpScope::default();
+    let mut cursor = root.walk();
+    for child in root.named_children(&mut cursor) {
+        match child.kind() {
+            "namespace_definition" => {
+                let namespace = child
+                    .child_by_field_name("name")
+                    .map(|n| source_code[n.start_byte()..n.end_byte()].to_string())
+                    .unwrap_or_default();
+                match child.child_by_field_name("body") {
+                    Some(body) => {
+                        let mut block_scope = PhpScope {
+                            namespace,
+                            ..Default::default()
+                        };
+                        let mut cursor = body.walk();
+                        for statement in body.named_children(&mut cursor) {
+                            if statement.kind() == "namespace_use_declaration" {
+                                block_scope.add_imports(statement, source_code);
+                            } else {
+                                declarations.push((statement, block_scope.clone()));
+                            }
+                        }
+                    }
+                    None => {
+                        scope = PhpScope {
+                            namespace,
+                            ..Default::default()
+                        }
+                    }
+                }
+            }
+            "namespace_use_declaration" => scope.add_imports(child, source_code),
+            _ => declarations.push((child, scope.clone())),
+        }
+    }
+    declarations
+}
+
+/// Collects functions and the methods of classes, traits and enums. Classes are
+/// named with their namespace (`App\Shop\Cart`); functions have no class and are
+/// named with their namespace instead (`App\Shop\helper`).
+pub fn traverse_php_nodes(
+    node: tree_sitter::Node,
+    source_code: &str,
+    file_path: &str,
+    is_test_file: bool,
+    methods: &mut Vec<Method>,
+) {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+
+    for (declaration, scope) in php_declarations(node, source_code) {
+        if declaration.kind() == "function_definition" {
+            if let Some(name) = declaration.child_by_field_name("name") {
+                methods.push(Method {
+                    class_name: String::new(),
+                    method_name: scope.qualify(&text(name)),
+                    body: extract_body(declaration, source_code),
+                    start_line: declaration.start_position().row + 1,
+                    is_test: is_test_file,
+                    file_path: file_path.to_string(),
+                });
+            }
+            continue;
+        }
+
+        if !PHP_TYPE_DECLARATIONS.contains(&declaration.kind()) {
+            continue;
+        }
+        let (Some(name), Some(body)) = (
+            declaration.child_by_field_name("name"),
+            declaration.child_by_field_name("body"),
+        ) else {
+            continue;
+        };
+        let class_name = scope.qualify(&text(name));
+        let is_test_class = is_php_test_class(declaration, source_code);
+
+        let mut cursor = body.walk();
+        // Abstract and interface methods have no body to cover
+        for method in body
+            .named_children(&mut cursor)
+            .filter(|m| m.kind() == "method_declaration" && m.child_by_field_name("body").is_some())
+        {
+            if let Some(name) = method.child_by_field_name("name") {
+                methods.push(Method {
+                    class_name: class_name.clone(),
+                    method_name: text(name),
+                    body: extract_body(method, source_code),
+                    start_line: method.start_position().row + 1,
+                    // Helpers in test classes aren't logic to be covered
+                    is_test: is_test_file || is_test_class,
+                    file_path: file_path.to_string(),
+                });
+            }
+        }
+    }
+}
+
+fn is_php_test_class(class: tree_sitter::Node, source_code: &str) -> bool {
+    let mut cursor = class.walk();
+    let extends_test_case = class
+        .children(&mut cursor)
+        .filter(|c| c.kind() == "base_clause")
+        .any(|base| {
+            source_code[base.start_byte()..base.end_byte()]
+                .trim_end()
+                .ends_with("TestCase")
+        });
+    extends_test_case
+}
+
+// The docblock right before a declaration
+fn php_docblock<'a>(node: tree_sitter::Node, source_code: &'a str) -> &'a str {
+    node.prev_named_sibling()
+        .filter(|c| c.kind() == "comment")
+        .map(|c| &source_code[c.start_byte()..c.end_byte()])
+        .filter(|c| c.starts_with("/**"))
+        .unwrap_or("")
+}
+
+// Attributes on a declaration, with their arguments as written
+fn php_attributes(node: tree_sitter::Node, source_code: &str) -> Vec<(String, Vec<String>)> {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+    let Some(list) = node.child_by_field_name("attributes") else {
+        return Vec::new();
+    };
+
+    let mut attributes = Vec::new();
+    let mut groups = list.walk();
+    for group in list.named_children(&mut groups) {
+        let mut cursor = group.walk();
+        for attribute in group
+            .named_children(&mut cursor)
+            .filter(|a| a.kind() == "attribute")
+        {
+            let Some(name) = attribute.named_child(0) else {
+                continue;
+            };
+            let arguments = attribute
+                .child_by_field_name("parameters")
+                .map(|parameters| {
+                    let mut cursor = parameters.walk();
+                    let arguments: Vec<String> =
+                        parameters.named_children(&mut cursor).map(text).collect();
+                    arguments
+                })
+                .unwrap_or_default();
+            let name = text(name);
+            attributes.push((
+                name.rsplit('\\').next().unwrap_or(&name).to_string(),
+                arguments,
+            ));
+        }
+    }
+    attributes
+}
+
+/// What a test declares it covers with `@covers`, `@coversNothing` and PHPUnit's
+/// `#[CoversClass]`, `#[CoversMethod]`, `#[CoversFunction]` and `#[CoversNothing]`.
+#[derive(Debug, Default, Clone)]
+struct PhpCovers {
+    classes: Vec<String>,
+    methods: Vec<(String, String)>,
+    functions: Vec<String>,
+    nothing: bool,
+}
+
+impl PhpCovers {
+    fn read(node: tree_sitter::Node, source_code: &str, scope: &PhpScope) -> PhpCovers {
+        let mut covers = PhpCovers::default();
+        let docblock = php_docblock(node, source_code);
+        let tag =
+            Regex::new(r"@(coversDefaultClass|coversNothing|covers)\b[ \t]*([^\s*]*)").unwrap();
+        let default_class = tag
+            .captures_iter(docblock)
+            .find(|c| &c[1] == "coversDefaultClass")
+            .map(|c| scope.resolve_class(&c[2]));
+
+        for captures in tag.captures_iter(docblock) {
+            match &captures[1] {
+                "coversNothing" => covers.nothing = true,
+                "covers" => covers.add(&captures[2], default_class.as_deref(), scope),
+                _ => {}
+            }
+        }
+
+        let unquote = |s: &str| s.trim_matches(['\'', '"']).to_string();
+        let class_argument = |s: &str| scope.resolve_class(s.trim_end_matches("::class"));
+        for (name, arguments) in php_attributes(node, source_code) {
+            match (name.as_str(), arguments.as_slice()) {
+                ("CoversClass" | "CoversTrait", [class, ..]) => {
+                    covers.classes.push(class_argument(class))
+                }
+                ("CoversMethod", [class, method, ..]) => covers
+                    .methods
+                    .push((class_argument(class), unquote(method))),
+                ("CoversFunction", [function, ..]) => covers
+                    .functions
+                    .push(unquote(function).trim_start_matches('\\').to_string()),
+                ("CoversNothing", _) => covers.nothing = true,
+                _ => {}
+            }
+        }
+        covers
+    }
+
+    // `@covers Foo`, `@covers Foo::bar`, and `@covers ::bar` for a method of the
+    // default class or else a global function
+    fn add(&mut self, target: &str, default_class: Option<&str>, scope: &PhpScope) {
+        match target.split_once("::") {
+            Some(("", name)) => match default_class {
+                Some(class) => self.methods.push((class.to_string(), name.to_string())),
+                None => self
+                    .functions
+                    .push(name.trim_start_matches('\\').to_string()),
+            },
+            Some((class, name)) => self
+                .methods
+                .push((scope.resolve_class(class), name.to_string())),
+            // A namespaced function is written like a class, `@covers \App\helper`
+            None if !target.is_empty() => {
+                self.classes.push(scope.resolve_class(target));
+                self.functions.push(scope.resolve_class(target));
+            }
+            None => {}
+        }
+    }
+
+    fn merge(&self, other: &PhpCovers) -> PhpCovers {
+        let mut merged = self.clone();
+        merged.classes.extend(other.classes.iter().cloned());
+        merged.methods.extend(other.methods.iter().cloned());
+        merged.functions.extend(other.functions.iter().cloned());
+        merged.nothing |= other.nothing;
+        merged
+    }
+
+    fn is_declared(&self) -> bool {
+        !(self.classes.is_empty() && self.methods.is_empty() && self.functions.is_empty())
+    }
+
+    fn allows(&self, method: &Method) -> bool {
+        if method.class_name.is_empty() {
+            self.functions.contains(&method.method_name)
+        } else {
+            self.classes.contains(&method.class_name)
+                || self
+                    .methods
+                    .iter()
+                    .any(|(c, m)| *c == method.class_name && *m == method.method_name)
+        }
+    }
+}
+
+struct PhpTest<'a> {
+    name: String,
+    blocks: Vec<tree_sitter::Node<'a>>,
+    scope: PhpScope,
+    covers: PhpCovers,
+}
+
+// PHPUnit tests: `test*` methods, and methods marked `@test` or `#[Test]`, in
+// subclasses of `TestCase`. `setUp` counts for every test of its class.
+fn collect_phpunit_tests<'a>(
+    class: tree_sitter::Node<'a>,
+    source_code: &str,
+    scope: &PhpScope,
+    tests: &mut Vec<PhpTest<'a>>,
+) {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+    let (Some(class_name), Some(body)) = (
+        class.child_by_field_name("name"),
+        class.child_by_field_name("body"),
+    ) else {
+        return;
+    };
+    let class_covers = PhpCovers::read(class, source_code, scope);
+
+    let mut cursor = body.walk();
+    let methods: Vec<tree_sitter::Node> = body
+        .named_children(&mut cursor)
+        .filter(|m| m.kind() == "method_declaration")
+        .collect();
+    let method_name = |m: &tree_sitter::Node| m.child_by_field_name("name").map(text);
+    let hooks: Vec<tree_sitter::Node> = methods
+        .iter()
+        .filter(|m| method_name(m).is_some_and(|n| PHPUNIT_HOOKS.contains(&n.as_str())))
+        .filter_map(|m| m.child_by_field_name("body"))
+        .collect();
+    // Property types let `$this->cart->total()` be resolved
+    let properties: Vec<tree_sitter::Node> = body
+        .named_children(&mut body.walk())
+        .filter(|p| p.kind() == "property_declaration")
+        .collect();
+
+    let test_tag = Regex::new(r"@test\b").unwrap();
+    for method in &methods {
+        let (Some(name), Some(method_body)) =
+            (method_name(method), method.child_by_field_name("body"))
+        else {
+            continue;
+        };
+        let is_test = name.starts_with("test")
+            || test_tag.is_match(php_docblock(*method, source_code))
+            || php_attributes(*method, source_code)
+                .iter()
+                .any(|(a, _)| a == "Test");
+        if !is_test || marks_itself_skipped(method_body, source_code) {
+            continue;
+        }
+
+        let mut blocks = vec![method_body];
+        blocks.extend(properties.iter().copied());
+        blocks.extend(hooks.iter().copied());
+        if let Some(parameters) = method.child_by_field_name("parameters") {
+            blocks.push(parameters);
+        }
+        tests.push(PhpTest {
+            name: format!("{}.{}", text(class_name), name),
+            blocks,
+            scope: scope.clone(),
+            covers: class_covers.merge(&PhpCovers::read(*method, source_code, scope)),
+        });
+    }
+}
+
+struct PestCall<'a, 's> {
+    function: String,
+    title: Option<String>,
+    body: Option<tree_sitter::Node<'a>>,
+    scope: &'s PhpScope,
+    skipped: bool,
+}
+
+// Pest's `it('...', fn)` and `test('...', fn)`, nested in `describe` blocks, with the
+// `beforeEach` hooks around them
+fn collect_pest_tests<'a>(
+    statements: &[(tree_sitter::Node<'a>, PhpScope)],
+    source_code: &str,
+    path: &[String],
+    hooks: &[tree_sitter::Node<'a>],
+    tests: &mut Vec<PhpTest<'a>>,
+) {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+    let calls: Vec<PestCall> = statements
+        .iter()
+        .filter_map(|(statement, scope)| {
+            let call = pest_call(*statement)?;
+            let function = text(call.child_by_field_name("function")?);
+            let arguments = call.child_by_field_name("arguments")?;
+            let mut cursor = arguments.walk();
+            let arguments: Vec<tree_sitter::Node> = arguments
+                .named_children(&mut cursor)
+                .filter_map(|a| a.named_child(0))
+                .collect();
+            let title = arguments
+                .first()
+                .filter(|a| matches!(a.kind(), "string" | "encapsed_string"))
+                .map(|a| text(*a).trim_matches(['\'', '"']).to_string());
+            let body = arguments
+                .iter()
+                .find(|a| matches!(a.kind(), "anonymous_function" | "arrow_function"))
+                .and_then(|c| c.child_by_field_name("body"));
+            Some(PestCall {
+                function,
+                title,
+                body,
+                scope,
+                skipped: is_skipped_pest_test(call, source_code)
+                    || body.is_some_and(|b| marks_itself_skipped(b, source_code)),
+            })
+        })
+        .collect();
+
+    let mut hooks = hooks.to_vec();
+    hooks.extend(
+        calls
+            .iter()
+            .filter(|c| PEST_HOOKS.contains(&c.function.as_str()))
+            .filter_map(|c| c.body),
+    );
+
+    for PestCall {
+        function,
+        title,
+        body,
+        scope,
+        skipped,
+    } in calls
+    {
+        let (Some(title), Some(body), false) = (title, body, skipped) else {
+            continue;
+        };
+        let mut path = path.to_vec();
+        match function.as_str() {
+            "it" | "test" => {
+                path.push(if function == "it" {
+                    format!("it {}", title)
+                } else {
+                    title
+                });
+                let mut blocks = vec![body];
+                blocks.extend(hooks.iter().copied());
+                tests.push(PhpTest {
+                    name: path.join(" > "),
+                    blocks,
+                    scope: scope.clone(),
+                    covers: PhpCovers::default(),
+                });
+            }
+            "describe" => {
+                path.push(title);
+                let mut cursor = body.walk();
+                let statements: Vec<(tree_sitter::Node, PhpScope)> = body
+                    .named_children(&mut cursor)
+                    .map(|s| (s, scope.clone()))
+                    .collect();
+                collect_pest_tests(&statements, source_code, &path, &hooks, tests);
+            }
+            _ => {}
+        }
+    }
+}
+
+// The Pest call made by a statement, looking through chained modifiers such as
+// `it(...)->with([...])`
+fn pest_call(statement: tree_sitter::Node) -> Option<tree_sitter::Node> {
+    let mut expression = match statement.kind() {
+        "expression_statement" => statement.named_child(0)?,
+        _ => statement,
+    };
+    while expression.kind() == "member_call_expression" {
+        expression = expression.child_by_field_name("object")?;
+    }
+    Some(expression).filter(|e| e.kind() == "function_call_expression")
+}
+
+// `->skip()` and `->todo()` chained onto a test or group
+fn is_skipped_pest_test(call: tree_sitter::Node, source_code: &str) -> bool {
+    let mut node = call;
+    while let Some(parent) = node
+        .parent()
+        .filter(|p| p.kind() == "member_call_expression")
+    {
+        if parent
+            .child_by_field_name("name")
+            .is_some_and(|n| matches!(&source_code[n.start_byte()..n.end_byte()], "skip" | "todo"))
+        {
+            return true;
+        }
+        node = parent;
+    }
+    false
+}
+
+// `$this->markTestSkipped()` or `markTestIncomplete()` as a statement of the test's
+// own body, rather than under a condition
+fn marks_itself_skipped(body: tree_sitter::Node, source_code: &str) -> bool {
+    let mut cursor = body.walk();
+    let skipped = body.named_children(&mut cursor).any(|statement| {
+        statement.kind() == "expression_statement"
+            && statement
+                .named_child(0)
+                .filter(|call| {
+                    matches!(
+                        call.kind(),
+                        "member_call_expression" | "scoped_call_expression"
+                    )
+                })
+                .and_then(|call| call.child_by_field_name("name"))
+                .is_some_and(|name| {
+                    matches!(
+                        &source_code[name.byte_range()],
+                        "markTestSkipped" | "markTestIncomplete"
+                    )
+                })
+    });
+    skipped
+}
+
+// A call made in a test
+#[derive(PartialEq, Debug)]
+enum PhpCall {
+    // `helper(1)`, as written
+    Function(String),
+    // `$cart->total()` or `Cart::empty()`, with the receiver's class when known
+    Method(Option<String>, String),
+}
+
+// The class of an expression such as `$cart`, `$this->cart` or `new Cart()`
+fn php_expression_type(
+    node: tree_sitter::Node,
+    source_code: &str,
+    scope: &PhpScope,
+    types: &HashMap<String, String>,
+) -> Option<String> {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+    match node.kind() {
+        "variable_name" => types.get(&text(node)).cloned(),
+        "member_access_expression" => {
+            let property = node.child_by_field_name("name")?;
+            types.get(&format!("->{}", text(property))).cloned()
+        }
+        "object_creation_expression" => {
+            let mut cursor = node.walk();
+            let class = node
+                .named_children(&mut cursor)
+                .find(|c| matches!(c.kind(), "name" | "qualified_name"))
+                .map(|c| scope.resolve_class(&text(c)));
+            class
+        }
+        "parenthesized_expression" => {
+            php_expression_type(node.named_child(0)?, source_code, scope, types)
+        }
+        "scoped_call_expression" => {
+            // `Cart::create()` is taken to return a `Cart`
+            let class = node.child_by_field_name("scope")?;
+            matches!(class.kind(), "name" | "qualified_name")
+                .then(|| scope.resolve_class(&text(class)))
+        }
+        _ => None,
+    }
+}
+
+// Records the classes of typed properties and parameters, and of variables and
+// properties assigned from `new Foo()`
+fn collect_php_types(
+    node: tree_sitter::Node,
+    source_code: &str,
+    scope: &PhpScope,
+    types: &mut HashMap<String, String>,
+) {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+    let declared_type = |n: tree_sitter::Node| {
+        let declared = text(n.child_by_field_name("type")?);
+        let declared = declared.trim_start_matches('?');
+        declared
+            .starts_with(|c: char| c.is_uppercase() || c == '\\')
+            .then(|| scope.resolve_class(declared))
+    };
+
+    match node.kind() {
+        "simple_parameter" | "property_promotion_parameter" => {
+            if let (Some(class), Some(name)) =
+                (declared_type(node), node.child_by_field_name("name"))
+            {
+                types.insert(text(name), class);
+            }
+        }
+        "property_declaration" => {
+            if let Some(class) = declared_type(node) {
+                let mut cursor = node.walk();
+                for element in node
+                    .named_children(&mut cursor)
+                    .filter(|e| e.kind() == "property_element")
+                {
+                    if let Some(name) = element.child_by_field_name("name") {
+                        types.insert(
+                            format!("->{}", text(name).trim_start_matches('$')),
+                            class.clone(),
+                        );
+                    }
+                }
+            }
+        }
+        "assignment_expression" => {
+            if let (Some(left), Some(right)) = (
+                node.child_by_field_name("left"),
+                node.child_by_field_name("right"),
+            ) {
+                let key = match left.kind() {
+                    "variable_name" => Some(text(left)),
+                    "member_access_expression" => left
+                        .child_by_field_name("name")
+                        .map(|n| format!("->{}", text(n))),
+                    _ => None,
+                };
+                if let (Some(key), Some(class)) =
+                    (key, php_expression_type(right, source_code, scope, types))
+                {
+                    types.insert(key, class);
+                }
+            }
+        }
+        _ => {}
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_php_types(child, source_code, scope, types);
+    }
+}
+
+fn collect_php_calls(
+    node: tree_sitter::Node,
+    source_code: &str,
+    scope: &PhpScope,
+    types: &HashMap<String, String>,
+    calls: &mut Vec<PhpCall>,
+) {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+
+    let call = match node.kind() {
+        "function_call_expression" => node
+            .child_by_field_name("function")
+            .filter(|f| matches!(f.kind(), "name" | "qualified_name"))
+            .map(|f| PhpCall::Function(text(f))),
+        "member_call_expression" | "nullsafe_member_call_expression" => {
+            node.child_by_field_name("name").map(|name| {
+                let class = node
+                    .child_by_field_name("object")
+                    .and_then(|o| php_expression_type(o, source_code, scope, types));
+                PhpCall::Method(class, text(name))
+            })
+        }
+        "scoped_call_expression" => node
+            .child_by_field_name("scope")
+            .filter(|s| matches!(s.kind(), "name" | "qualified_name"))
+            .zip(node.child_by_field_name("name"))
+            .map(|(class, name)| {
+                PhpCall::Method(Some(scope.resolve_class(&text(class))), text(name))
+            }),
+        "object_creation_expression" => php_expression_type(node, source_code, scope, types)
+            .map(|class| PhpCall::Method(Some(class), String::from("__construct"))),
+        _ => None,
+    };
+
+    if let Some(call) = call {
+        if !calls.contains(&call) {
+            calls.push(call);
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_php_calls(child, source_code, scope, types, calls);
+    }
+}
+
+/// Resolves the calls made by PHPUnit and Pest tests through the test file's
+/// namespace and imports. When a test declares what it covers, only calls into those
+/// classes, methods and functions count, and the methods and functions it names are
+/// covered even if no call to them is found; `@coversNothing` tests cover nothing.
+pub fn extract_php_tested_methods(repo: &str, logic_methods: &[Method]) -> TestedMethods {
+    let mut tested_methods = TestedMethods::default();
+    let candidates: Vec<&Method> = logic_methods.iter().filter(|m| !m.is_test).collect();
+
+    for entry in WalkDir::new(repo)
+        .into_iter()
+        .filter_entry(|e| !should_skip_dir(e))
+        .filter_map(Result::ok)
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "php"))
+    {
+        let Ok(source_code) = fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let mut parser = get_parser("php");
+        let Some(tree) = parser.parse(&source_code, None) else {
+            continue;
+        };
+
+        let mut tests = Vec::new();
+        let declarations = php_declarations(tree.root_node(), &source_code);
+        for (declaration, scope) in &declarations {
+            if declaration.kind() == "class_declaration"
+                && is_php_test_class(*declaration, &source_code)
+            {
+                collect_phpunit_tests(*declaration, &source_code, scope, &mut tests);
+            }
+        }
+        // Pest tests are plain function calls, so only test files are searched
+        if is_php_test_file(repo, entry.path()) {
+            collect_pest_tests(&declarations, &source_code, &[], &[], &mut tests);
+        }
+
+        for test in tests {
+            let mut types = HashMap::new();
+            for block in test.blocks.iter().rev() {
+                collect_php_types(*block, &source_code, &test.scope, &mut types);
+            }
+            let mut calls = Vec::new();
+            for block in &test.blocks {
+                collect_php_calls(*block, &source_code, &test.scope, &types, &mut calls);
+            }
+
+            if test.covers.nothing {
+                continue;
+            }
+            let mut covered: Vec<&Method> = Vec::new();
+            for call in calls {
+                match call {
+                    PhpCall::Function(name) => {
+                        if let Some(functions) =
+                            test.scope.resolve_function(&name).iter().find_map(|f| {
+                                let functions: Vec<&Method> = candidates
+                                    .iter()
+                                    .filter(|m| m.class_name.is_empty() && m.method_name == *f)
+                                    .copied()
+                                    .collect();
+                                (!functions.is_empty()).then_some(functions)
+                            })
+                        {
+                            covered.extend(functions);
+                        }
+                    }
+                    PhpCall::Method(class, name) => covered.extend(candidates.iter().filter(|m| {
+                        m.method_name == name
+                            && !m.class_name.is_empty()
+                            && class.as_ref().is_none_or(|c| *c == m.class_name)
+                    })),
+                }
+            }
+
+            if test.covers.is_declared() {
+                covered.retain(|m| test.covers.allows(m));
+                covered.extend(candidates.iter().filter(|m| {
+                    (m.class_name.is_empty() && test.covers.functions.contains(&m.method_name))
+                        || test
+                            .covers
+                            .methods
+                            .iter()
+                            .any(|(c, n)| *c == m.class_name && *n == m.method_name)
+                }));
+            }
+            for method in covered {
+                tested_methods.record(CallTarget::exact(method), &test.name);
+            }
+        }
+    }
+
+    tested_methods
+}
+
+#[test]
+fn test_traverse_php_nodes() {
+    let source_code = r#"<?php
+namespace App\Shop;
+
+class Cart
+{
+    public function __construct(private array $items = []) {}
+    public function total(): int { return array_sum($this->items); }
+    abstract protected function area(): int;
+}
+
+function helper(int $x): int { return $x; }
+
+interface Shape { public function area(): float; }
+trait Greets { public function greet() { return "hi"; } }
+
+namespace App\Util { enum Suit { case Hearts; public function label() { return "h"; } } }
+"#;
+    let mut parser = get_parser("php");
+    let tree = parser.parse(source_code, None).unwrap();
+    let mut methods = Vec::new();
+    traverse_php_nodes(
+        tree.root_node(),
+        source_code,
+        "src/Cart.php",
+        false,
+        &mut methods,
+    );
+
+    let found: Vec<(&str, &str)> = methods
+        .iter()
+        .map(|m| (m.class_name.as_str(), m.method_name.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("App\\Shop\\Cart", "__construct"),
+            ("App\\Shop\\Cart", "total"),
+            ("", "App\\Shop\\helper"),
+            ("App\\Shop\\Greets", "greet"),
+            ("App\\Util\\Suit", "label"),
+        ]
+    );
+    assert!(is_php_test_file(".", Path::new("tests/Unit/CartTest.php")));
+    assert!(!is_php_test_file(".", Path::new("src/Cart.php")));
+}
+
+#[test]
+fn test_is_php_test_file() {
+    // Only directories inside the repo count
+    let repo = "/home/me/test/shop";
+    assert!(!is_php_test_file(
+        repo,
+        Path::new("/home/me/test/shop/src/Cart.php")
+    ));
+    assert!(is_php_test_file(
+        repo,
+        Path::new("/home/me/test/shop/tests/Feature/Checkout.php")
+    ));
+    // Files outside the repo root are judged on their whole path
+    assert!(is_php_test_file(repo, Path::new("/srv/tests/Pest.php")));
+    assert!(!is_php_test_file(
+        repo,
+        Path::new("/srv/vendor/src/Cart.php")
+    ));
+}
+
+#[test]
+fn test_extract_php_tested_methods() {
+    use std::fs::create_dir_all;
+    use tempfile::tempdir;
+
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    // Checked out under a test/ directory, which mustn't make src/ test code
+    let root = temp_dir.path().join("test/shop");
+    create_dir_all(root.join("src")).unwrap();
+    create_dir_all(root.join("tests")).unwrap();
+    fs::write(
+        root.join("src/Cart.php"),
+        r#"<?php
+namespace App;
+
+class Cart
+{
+    public function __construct(private array $items = []) {}
+    public function total(): int { return array_sum($this->items); }
+    public function count(): int { return count($this->items); }
+    public static function empty(): self { return new self(); }
+}
+
+class Order
+{
+    public function total(): int { return 0; }
+    public function ship(): void {}
+}
+
+function helper(int $x): int { return $x; }
+"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("tests/CartTest.php"),
+        r#"<?php
+namespace Tests;
+
+use App\Cart;
+use App\Order;
+use PHPUnit\Framework\TestCase;
+use function App\helper;
+
+/**
+ * @covers \App\Cart
+ */
+final class CartTest extends TestCase
+{
+    private Cart $cart;
+
+    protected function setUp(): void { $this->cart = new Cart([1]); }
+
+    public function testTotal(): void
+    {
+        $this->assertSame(1, $this->cart->total());
+        (new Order())->ship();
+    }
+
+    /**
+     * @test
+     * @covers \App\helper
+     */
+    public function it_helps(): void { helper(1); }
+
+    #[\PHPUnit\Framework\Attributes\CoversNothing]
+    public function testNothing(): void { $this->cart->count(); }
+}
+"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("tests/OrderTest.php"),
+        r#"<?php
+use App\Order;
+
+describe('orders', function () {
+    beforeEach(function () { $this->order = new Order(); });
+
+    it('ships', function () { expect($this->order->ship())->toBeNull(); });
+});
+test('empty cart', fn () => \App\Cart::empty());
+it('skipped', function () { (new Order())->total(); })->skip();
+"#,
+    )
+    .unwrap();
+
+    let mut methods = Vec::new();
+    for file in ["src/Cart.php", "tests/CartTest.php", "tests/OrderTest.php"] {
+        let path = root.join(file);
+        let source_code = fs::read_to_string(&path).unwrap();
+        let mut parser = get_parser("php");
+        let tree = parser.parse(&source_code, None).unwrap();
+        traverse_php_nodes(
+            tree.root_node(),
+            &source_code,
+            &path.to_string_lossy(),
+            is_php_test_file(&root.to_string_lossy(), &path),
+            &mut methods,
+        );
+    }
+
+    let tested = extract_php_tested_methods(&root.to_string_lossy(), &methods);
+    let tests_for = |class: &str, name: &str| {
+        let method = methods
+            .iter()
+            .find(|m| m.class_name == class && m.method_name == name)
+            .unwrap();
+        tested.tests_for(method)
+    };
+
+    assert_eq!(tests_for("App\\Cart", "total"), vec!["CartTest.testTotal"]);
+    assert!(tests_for("App\\Order", "total").is_empty());
+    assert_eq!(tests_for("", "App\\helper"), vec!["CartTest.it_helps"]);
+    assert!(tests_for("App\\Cart", "count").is_empty());
+    // `@covers \App\Cart` keeps the call to Order out of CartTest
+    assert_eq!(tests_for("App\\Order", "ship"), vec!["orders > it ships"]);
+    assert_eq!(tests_for("App\\Cart", "empty"), vec!["empty cart"]);
+}
+
+#[test]
+fn test_php_skipped_tests_and_shared_names() {
+    use tempfile::tempdir;
+
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let root = temp_dir.path();
+    // PHP has no overloads, but methods share names across classes
+    fs::write(
+        root.join("Cart.php"),
+        r#"<?php
+class Cart
+{
+    public function total(): int { return 0; }
+    public function clear(): void {}
+}
+
+class Order
+{
+    public function total(): int { return 0; }
+}
+"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("CartTest.php"),
+        r#"<?php
+use PHPUnit\Framework\TestCase;
+
+class CartTest extends TestCase
+{
+    public function testTotal(): void { (new Cart())->total(); }
+
+    public function testClear(): void
+    {
+        $this->markTestSkipped('flaky');
+        (new Cart())->clear();
+    }
+
+    public function testClearLater(): void
+    {
+        self::markTestIncomplete();
+        (new Cart())->clear();
+    }
+
+    public function testClearOnLinux(): void
+    {
+        if (PHP_OS_FAMILY === 'Windows') {
+            $this->markTestSkipped();
+        }
+        (new Cart())->clear();
+    }
+}
+"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("OrderTest.php"),
+        r#"<?php
+describe('orders', function () {
+    it('totals', function () { (new Order())->total(); });
+})->skip();
+it('totals later', function () { (new Order())->total(); })->todo();
+test('totals soon', function () {
+    $this->markTestSkipped();
+    (new Order())->total();
+});
+"#,
+    )
+    .unwrap();
+
+    let mut methods = Vec::new();
+    let path = root.join("Cart.php");
+    let source_code = fs::read_to_string(&path).unwrap();
+    let tree = get_parser("php").parse(&source_code, None).unwrap();
+    traverse_php_nodes(
+        tree.root_node(),
+        &source_code,
+        &path.to_string_lossy(),
+        false,
+        &mut methods,
+    );
+
+    let tested = extract_php_tested_methods(&root.to_string_lossy(), &methods);
+    let tests_for = |class: &str, name: &str| {
+        let method = methods
+            .iter()
+            .find(|m| m.class_name == class && m.method_name == name)
+            .unwrap();
+        tested.tests_for(method)
+    };
+
+    assert_eq!(tests_for("Cart", "total"), vec!["CartTest.testTotal"]);
+    assert!(tests_for("Order", "total").is_empty());
+    assert_eq!(
+        tests_for("Cart", "clear"),
+        vec!["CartTest.testClearOnLinux"]
+    );
+}
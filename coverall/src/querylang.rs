@@ -0,0 +1,743 @@
+use anyhow::{anyhow, Context, Result};
+use libloading::Library;
+use regex::Regex;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tree_sitter::{
+    Language, Node, Parser, Query, QueryCursor, QueryMatch, StreamingIterator, LANGUAGE_VERSION,
+    MIN_COMPATIBLE_LANGUAGE_VERSION,
+};
+use tree_sitter_language::LanguageFn;
+use walkdir::WalkDir;
+
+use crate::js::json_strings;
+use crate::utils::{
+    extract_body, glob_to_regex, normalize_path, should_skip_dir, CallTarget, Method, TestedMethods,
+};
+
+// The queries of a definition, with the captures each of them has to use
+const QUERY_FILES: [(&str, &[&str]); 3] = [
+    ("functions.scm", &["function", "function.name"]),
+    ("tests.scm", &["test"]),
+    ("calls.scm", &["call", "call.name"]),
+];
+
+// Grammars loaded from shared libraries, by library path
+static LOADED_GRAMMARS: Mutex<Vec<(PathBuf, Language)>> = Mutex::new(Vec::new());
+
+/// A language defined in `languages/<name>/` of the config directory instead of
+/// being built in: a grammar, the files it covers, and tree-sitter queries that pick
+/// out function definitions, their owners, tests and call sites.
+#[derive(PartialEq, Debug, Default)]
+pub struct QueryLanguage {
+    pub name: String,
+    pub grammar: String,
+    // The shared library holding the grammar, when it isn't bundled
+    pub grammar_path: Option<PathBuf>,
+    pub extensions: Vec<String>,
+    // Globs, relative to the repo, of the files holding tests
+    pub test_files: Vec<String>,
+    pub functions: String,
+    pub tests: String,
+    pub calls: String,
+}
+
+/// `$COVERME_CONFIG_DIR`, or else `coverme/` in the user's config directory.
+pub fn config_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("COVERME_CONFIG_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .map(|dir| dir.join("coverme"))
+}
+
+/// Loads every language defined under `languages/` in the config directory, with
+/// grammars that aren't bundled taken from `grammars/`. A definition that can't be
+/// read is an error rather than being skipped, so mistakes in it surface straight
+/// away. Grammars aren't loaded until a language is validated.
+pub fn load_query_languages(config_dir: &Path) -> Result<Vec<QueryLanguage>> {
+    let Ok(entries) = fs::read_dir(config_dir.join("languages")) else {
+        return Ok(Vec::new());
+    };
+    let mut languages = Vec::new();
+    for entry in entries.filter_map(Result::ok) {
+        if entry.path().is_dir() {
+            languages.push(QueryLanguage::load(
+                &entry.path(),
+                &config_dir.join("grammars"),
+            )?);
+        }
+    }
+    languages.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(languages)
+}
+
+/// Loads the language of that name from the config directory, if it defines one,
+/// leaving the other definitions alone.
+pub fn load_query_language(config_dir: &Path, name: &str) -> Result<Option<QueryLanguage>> {
+    let dir = config_dir.join("languages").join(name);
+    if !dir.is_dir() {
+        return Ok(None);
+    }
+    QueryLanguage::load(&dir, &config_dir.join("grammars")).map(Some)
+}
+
+/// The grammars bundled with coverme, by their tree-sitter names.
+pub fn grammar_language(grammar: &str) -> Option<Language> {
+    let language = match grammar {
+        "rust" => tree_sitter_rust::LANGUAGE,
+        "c_sharp" | "c-sharp" => tree_sitter_c_sharp::LANGUAGE,
+        "python" => tree_sitter_python::LANGUAGE,
+        "javascript" => tree_sitter_javascript::LANGUAGE,
+        "typescript" => tree_sitter_typescript::LANGUAGE_TYPESCRIPT,
+        "tsx" => tree_sitter_typescript::LANGUAGE_TSX,
+        "go" => tree_sitter_go::LANGUAGE,
+        "java" => tree_sitter_java::LANGUAGE,
+        "c" => tree_sitter_c::LANGUAGE,
+        "cpp" => tree_sitter_cpp::LANGUAGE,
+        "ruby" => tree_sitter_ruby::LANGUAGE,
+        "php" => tree_sitter_php::LANGUAGE_PHP,
+        "swift" => tree_sitter_swift::LANGUAGE,
+        _ => return None,
+    };
+    Some(language.into())
+}
+
+// `tree-sitter build` names the library after the grammar, and the grammars'
+// Makefiles after their repo
+fn find_grammar_library(grammar: &str, dirs: &[&Path]) -> Option<PathBuf> {
+    let ext = env::consts::DLL_EXTENSION;
+    let file_names = [
+        format!("{}.{}", grammar, ext),
+        format!("libtree-sitter-{}.{}", grammar, ext),
+        format!("tree-sitter-{}.{}", grammar, ext),
+    ];
+    dirs.iter()
+        .flat_map(|dir| file_names.iter().map(|name| dir.join(name)))
+        .find(|path| path.is_file())
+}
+
+/// Loads a grammar compiled into a shared library through its `tree_sitter_<grammar>`
+/// function. The library stays loaded for the rest of the run, as the language
+/// points into it.
+pub fn load_grammar_library(path: &Path, grammar: &str) -> Result<Language> {
+    let mut loaded = LOADED_GRAMMARS.lock().unwrap();
+    if let Some((_, language)) = loaded.iter().find(|(p, _)| p == path) {
+        return Ok(language.clone());
+    }
+
+    let symbol = format!("tree_sitter_{}", grammar.replace('-', "_"));
+    // Safety: the library is taken to be a tree-sitter grammar, whose function
+    // takes no arguments and returns its language
+    let language = unsafe {
+        let library = Library::new(path)
+            .with_context(|| format!("Failed to load grammar {}", path.display()))?;
+        let constructor = *library
+            .get::<unsafe extern "C" fn() -> *const ()>(symbol.as_bytes())
+            .with_context(|| format!("{} has no {} function", path.display(), symbol))?;
+        let language = Language::new(LanguageFn::from_raw(constructor));
+        std::mem::forget(library);
+        language
+    };
+    if !(MIN_COMPATIBLE_LANGUAGE_VERSION..=LANGUAGE_VERSION).contains(&language.abi_version()) {
+        return Err(anyhow!(
+            "{} was generated for tree-sitter ABI {}, but coverme supports {} to {}",
+            path.display(),
+            language.abi_version(),
+            MIN_COMPATIBLE_LANGUAGE_VERSION,
+            LANGUAGE_VERSION
+        ));
+    }
+
+    loaded.push((path.to_path_buf(), language.clone()));
+    Ok(language)
+}
+
+impl QueryLanguage {
+    /// Reads `language.json` and the `.scm` queries from a definition's directory,
+    /// which names the language. A grammar that isn't bundled is loaded from the
+    /// library at `grammar_path`, or else found by its name in the definition's
+    /// directory or `grammar_dir`.
+    pub fn load(dir: &Path, grammar_dir: &Path) -> Result<QueryLanguage> {
+        let definition_path = dir.join("language.json");
+        let definition: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(&definition_path)
+                .with_context(|| format!("Failed to read {}", definition_path.display()))?,
+        )
+        .with_context(|| format!("Failed to parse {}", definition_path.display()))?;
+        let read_query = |file: &str| {
+            fs::read_to_string(dir.join(file))
+                .with_context(|| format!("Failed to read {}", dir.join(file).display()))
+        };
+
+        let grammar = definition["grammar"]
+            .as_str()
+            .ok_or_else(|| anyhow!("{} has no \"grammar\"", definition_path.display()))?
+            .to_string();
+        let grammar_path = match definition["grammar_path"].as_str() {
+            Some(path) => Some(dir.join(path)),
+            None if grammar_language(&grammar).is_some() => None,
+            None => Some(
+                find_grammar_library(&grammar, &[dir, grammar_dir]).ok_or_else(|| {
+                    anyhow!(
+                        "\"{}\" isn't a bundled grammar, and no library for it is in {} or {}",
+                        grammar,
+                        dir.display(),
+                        grammar_dir.display()
+                    )
+                })?,
+            ),
+        };
+
+        let language = QueryLanguage {
+            name: dir
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            grammar,
+            grammar_path,
+            extensions: json_strings(&definition["extensions"]),
+            test_files: json_strings(&definition["test_files"]),
+            functions: read_query(QUERY_FILES[0].0)?,
+            tests: read_query(QUERY_FILES[1].0)?,
+            calls: read_query(QUERY_FILES[2].0)?,
+        };
+        if language.extensions.is_empty() {
+            return Err(anyhow!(
+                "{} has no \"extensions\"",
+                definition_path.display()
+            ));
+        }
+        Ok(language)
+    }
+
+    /// Loads the grammar and checks the queries compile against it, use the captures
+    /// they have to and that the `test_files` globs can be matched.
+    pub fn validate(&self) -> Result<()> {
+        self.queries().map(|_| ())
+    }
+
+    /// Compiles the definition's queries against its grammar.
+    pub fn queries(&self) -> Result<LanguageQueries> {
+        let language = match &self.grammar_path {
+            Some(path) => load_grammar_library(path, &self.grammar)?,
+            None => grammar_language(&self.grammar)
+                .ok_or_else(|| anyhow!("{}: unknown grammar \"{}\"", self.name, self.grammar))?,
+        };
+        let mut queries = Vec::new();
+        for ((file, captures), source) in
+            QUERY_FILES
+                .iter()
+                .zip([&self.functions, &self.tests, &self.calls])
+        {
+            let query = Query::new(&language, source)
+                .map_err(|e| anyhow!("{}/{}: {}", self.name, file, e))?;
+            if let Some(capture) = captures
+                .iter()
+                .find(|c| query.capture_index_for_name(c).is_none())
+            {
+                return Err(anyhow!(
+                    "{}/{} doesn't capture @{}",
+                    self.name,
+                    file,
+                    capture
+                ));
+            }
+            queries.push(query);
+        }
+        let calls = queries.pop().unwrap();
+        let tests = queries.pop().unwrap();
+        let functions = queries.pop().unwrap();
+        let test_files = self
+            .test_files
+            .iter()
+            .map(|glob| {
+                glob_to_regex(glob).ok_or_else(|| {
+                    anyhow!(
+                        "{}: test_files glob \"{}\" isn't supported",
+                        self.name,
+                        glob
+                    )
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(LanguageQueries {
+            language,
+            functions,
+            tests,
+            calls,
+            test_files,
+        })
+    }
+}
+
+pub struct LanguageQueries {
+    language: Language,
+    functions: Query,
+    tests: Query,
+    calls: Query,
+    test_files: Vec<Regex>,
+}
+
+// A function definition found by `functions.scm`
+struct QueryFunction<'a> {
+    node: Node<'a>,
+    class_name: String,
+    method_name: String,
+}
+
+impl QueryFunction<'_> {
+    fn id(&self) -> String {
+        if self.class_name.is_empty() {
+            self.method_name.clone()
+        } else {
+            format!("{}.{}", self.class_name, self.method_name)
+        }
+    }
+}
+
+fn capture<'a>(query: &Query, query_match: &QueryMatch<'_, 'a>, name: &str) -> Option<Node<'a>> {
+    let index = query.capture_index_for_name(name)?;
+    query_match
+        .captures
+        .iter()
+        .find(|c| c.index == index)
+        .map(|c| c.node)
+}
+
+fn contains(outer: Node, inner: Node) -> bool {
+    outer.start_byte() <= inner.start_byte() && inner.end_byte() <= outer.end_byte()
+}
+
+// Names may be captured as string literals, e.g. a test's title
+fn capture_text(node: Node, source_code: &str) -> String {
+    source_code[node.start_byte()..node.end_byte()]
+        .trim_matches(['"', '\'', '`'])
+        .to_string()
+}
+
+impl LanguageQueries {
+    pub fn parser(&self) -> Parser {
+        let mut parser = Parser::new();
+        parser.set_language(&self.language).unwrap();
+        parser
+    }
+
+    pub fn is_test_file(&self, repo: &str, path: &Path) -> bool {
+        let path = normalize_path(path);
+        let relative = path
+            .strip_prefix(normalize_path(Path::new(repo)))
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        self.test_files.iter().any(|p| p.is_match(&relative))
+    }
+
+    // A function is owned by the `@owner` nodes around it, outermost first, unless
+    // its match captures `@function.owner` itself
+    fn find_functions<'a>(&self, root: Node<'a>, source_code: &str) -> Vec<QueryFunction<'a>> {
+        let mut owners = Vec::new();
+        let mut definitions: Vec<(Node, String, Option<String>)> = Vec::new();
+        let mut cursor = QueryCursor::new();
+        let mut matches = cursor.matches(&self.functions, root, source_code.as_bytes());
+        while let Some(query_match) = matches.next() {
+            let query = &self.functions;
+            if let (Some(node), Some(name)) = (
+                capture(query, query_match, "owner"),
+                capture(query, query_match, "owner.name"),
+            ) {
+                owners.push((node, capture_text(name, source_code)));
+            }
+            if let (Some(node), Some(name)) = (
+                capture(query, query_match, "function"),
+                capture(query, query_match, "function.name"),
+            ) {
+                if !definitions.iter().any(|(n, _, _)| n.id() == node.id()) {
+                    let owner = capture(query, query_match, "function.owner")
+                        .map(|o| capture_text(o, source_code));
+                    definitions.push((node, capture_text(name, source_code), owner));
+                }
+            }
+        }
+
+        definitions
+            .into_iter()
+            .map(|(node, method_name, owner)| QueryFunction {
+                node,
+                class_name: owner.unwrap_or_else(|| {
+                    owners
+                        .iter()
+                        .filter(|(o, _)| o.id() != node.id() && contains(*o, node))
+                        .map(|(_, name)| name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(".")
+                }),
+                method_name,
+            })
+            .collect()
+    }
+
+    // Tests are named after the function they are, or else their `@test.name`
+    fn find_tests<'a>(
+        &self,
+        root: Node<'a>,
+        source_code: &str,
+        functions: &[QueryFunction<'a>],
+    ) -> Vec<(Node<'a>, String)> {
+        let mut tests: Vec<(Node, String)> = Vec::new();
+        let mut cursor = QueryCursor::new();
+        let mut matches = cursor.matches(&self.tests, root, source_code.as_bytes());
+        while let Some(query_match) = matches.next() {
+            let Some(node) = capture(&self.tests, query_match, "test") else {
+                continue;
+            };
+            if tests.iter().any(|(n, _)| n.id() == node.id()) {
+                continue;
+            }
+            let name = functions
+                .iter()
+                .find(|f| f.node.id() == node.id())
+                .map(QueryFunction::id)
+                .or_else(|| {
+                    capture(&self.tests, query_match, "test.name")
+                        .map(|n| capture_text(n, source_code))
+                })
+                .unwrap_or_else(|| format!("test at line {}", node.start_position().row + 1));
+            tests.push((node, name));
+        }
+        tests
+    }
+
+    // The name and, when captured, the receiver of each call made under the node
+    fn find_calls(&self, node: Node, source_code: &str) -> Vec<(String, Option<String>)> {
+        let mut calls = Vec::new();
+        let mut cursor = QueryCursor::new();
+        let mut matches = cursor.matches(&self.calls, node, source_code.as_bytes());
+        while let Some(query_match) = matches.next() {
+            if let Some(name) = capture(&self.calls, query_match, "call.name") {
+                let receiver = capture(&self.calls, query_match, "call.receiver")
+                    .map(|r| capture_text(r, source_code));
+                let call = (capture_text(name, source_code), receiver);
+                if !calls.contains(&call) {
+                    calls.push(call);
+                }
+            }
+        }
+        calls
+    }
+}
+
+/// Collects the functions `functions.scm` finds. Those in test files, or inside a
+/// node `tests.scm` captures as a test, are test code.
+pub fn traverse_query_nodes(
+    root: Node,
+    source_code: &str,
+    file_path: &str,
+    is_test_file: bool,
+    queries: &LanguageQueries,
+    methods: &mut Vec<Method>,
+) {
+    let functions = queries.find_functions(root, source_code);
+    let tests = queries.find_tests(root, source_code, &functions);
+
+    for function in functions {
+        methods.push(Method {
+            is_test: is_test_file || tests.iter().any(|(t, _)| contains(*t, function.node)),
+            body: extract_body(function.node, source_code),
+            start_line: function.node.start_position().row + 1,
+            class_name: function.class_name,
+            method_name: function.method_name,
+            file_path: file_path.to_string(),
+        });
+    }
+}
+
+/// Matches the calls each test makes by name. A receiver that names an owner, as in
+/// `Cart.empty()`, narrows the match to that owner's functions.
+pub fn extract_query_tested_methods(
+    repo: &str,
+    language: &QueryLanguage,
+    logic_methods: &[Method],
+) -> TestedMethods {
+    let mut tested_methods = TestedMethods::default();
+    let Ok(queries) = language.queries() else {
+        return tested_methods;
+    };
+    let candidates: Vec<&Method> = logic_methods.iter().filter(|m| !m.is_test).collect();
+
+    for entry in WalkDir::new(repo)
+        .into_iter()
+        .filter_entry(|e| !should_skip_dir(e))
+        .filter_map(Result::ok)
+        .filter(|e| {
+            e.path()
+                .extension()
+                .is_some_and(|ext| language.extensions.iter().any(|x| ext == x.as_str()))
+        })
+    {
+        let Ok(source_code) = fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let Some(tree) = queries.parser().parse(&source_code, None) else {
+            continue;
+        };
+        let root = tree.root_node();
+        let functions = queries.find_functions(root, &source_code);
+
+        for (test, test_name) in queries.find_tests(root, &source_code, &functions) {
+            for (name, receiver) in queries.find_calls(test, &source_code) {
+                let named: Vec<&&Method> = candidates
+                    .iter()
+                    .filter(|m| m.method_name == name)
+                    .collect();
+                let owned: Vec<&&Method> = named
+                    .iter()
+                    .filter(|m| {
+                        receiver.as_ref().is_some_and(|r| {
+                            m.class_name == *r || m.class_name.rsplit('.').next() == Some(r)
+                        })
+                    })
+                    .copied()
+                    .collect();
+                for method in if owned.is_empty() { named } else { owned } {
+                    tested_methods.record(CallTarget::exact(method), &test_name);
+                }
+            }
+        }
+    }
+
+    tested_methods
+}
+
+#[test]
+fn test_load_query_languages() {
+    use tempfile::tempdir;
+
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let config_dir = temp_dir.path();
+    assert!(load_query_languages(config_dir).unwrap().is_empty());
+
+    let dir = config_dir.join("languages/pyq");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("language.json"),
+        r#"{ "grammar": "python", "extensions": ["py"] }"#,
+    )
+    .unwrap();
+    fs::write(
+        dir.join("functions.scm"),
+        "(function_definition name: (identifier) @function.name) @function",
+    )
+    .unwrap();
+    fs::write(dir.join("tests.scm"), "(function_definition) @test").unwrap();
+    fs::write(
+        dir.join("calls.scm"),
+        "(call function: (identifier) @call.name) @call",
+    )
+    .unwrap();
+    let languages = load_query_languages(config_dir).unwrap();
+    assert_eq!(languages.len(), 1);
+    assert_eq!(languages[0].name, "pyq");
+    assert_eq!(languages[0].grammar, "python");
+    assert_eq!(languages[0].extensions, vec!["py"]);
+    assert!(languages[0].validate().is_ok());
+
+    // Loading one language by name doesn't read the others
+    fs::create_dir_all(config_dir.join("languages/broken")).unwrap();
+    assert!(load_query_languages(config_dir).is_err());
+    let language = load_query_language(config_dir, "pyq").unwrap().unwrap();
+    assert_eq!(language.name, "pyq");
+    assert!(load_query_language(config_dir, "cobol").unwrap().is_none());
+
+    // A query that doesn't compile, or lacks a required capture, is reported
+    fs::write(dir.join("calls.scm"), "(call function: (identifier) @call)").unwrap();
+    let language = load_query_language(config_dir, "pyq").unwrap().unwrap();
+    let error = language.validate().unwrap_err().to_string();
+    assert_eq!(error, "pyq/calls.scm doesn't capture @call.name");
+    fs::write(dir.join("calls.scm"), "(call").unwrap();
+    let language = load_query_language(config_dir, "pyq").unwrap().unwrap();
+    assert!(language.validate().is_err());
+
+    // So is a test_files glob that can't be matched
+    fs::write(
+        dir.join("calls.scm"),
+        "(call function: (identifier) @call.name) @call",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("language.json"),
+        r#"{ "grammar": "python", "extensions": ["py"], "test_files": ["!(src)/**"] }"#,
+    )
+    .unwrap();
+    let language = load_query_language(config_dir, "pyq").unwrap().unwrap();
+    let error = language.validate().unwrap_err().to_string();
+    assert_eq!(error, "pyq: test_files glob \"!(src)/**\" isn't supported");
+}
+
+#[test]
+fn test_load_grammar_library() {
+    use tempfile::tempdir;
+
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let config_dir = temp_dir.path();
+    let dir = config_dir.join("languages/lua");
+    fs::create_dir_all(&dir).unwrap();
+    fs::create_dir_all(config_dir.join("grammars")).unwrap();
+    fs::write(
+        dir.join("language.json"),
+        r#"{ "grammar": "lua", "extensions": ["lua"] }"#,
+    )
+    .unwrap();
+    for file in ["functions.scm", "tests.scm", "calls.scm"] {
+        fs::write(dir.join(file), "").unwrap();
+    }
+
+    let error = load_query_languages(config_dir).unwrap_err().to_string();
+    assert!(error.starts_with("\"lua\" isn't a bundled grammar"));
+
+    // Found by name in grammars/, but only loaded when the language is validated
+    let library = config_dir
+        .join("grammars")
+        .join(format!("lua.{}", env::consts::DLL_EXTENSION));
+    fs::write(&library, "not a library").unwrap();
+    let language = load_query_languages(config_dir).unwrap().remove(0);
+    assert_eq!(language.grammar_path.as_ref(), Some(&library));
+    let error = language.validate().unwrap_err().to_string();
+    assert_eq!(
+        error,
+        format!("Failed to load grammar {}", library.display())
+    );
+
+    assert_eq!(
+        find_grammar_library("lua", &[&dir, &config_dir.join("grammars")]),
+        Some(library)
+    );
+    assert_eq!(find_grammar_library("lua", &[&dir]), None);
+}
+
+#[test]
+fn test_extract_query_tested_methods() {
+    use tempfile::tempdir;
+
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let config_dir = temp_dir.path().join("config");
+    let repo = temp_dir.path().join("repo");
+    let dir = config_dir.join("languages/pyq");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("language.json"),
+        r#"{ "grammar": "python", "extensions": ["py"], "test_files": ["tests/**"] }"#,
+    )
+    .unwrap();
+    fs::write(
+        dir.join("functions.scm"),
+        r#"(class_definition name: (identifier) @owner.name) @owner
+(function_definition name: (identifier) @function.name) @function"#,
+    )
+    .unwrap();
+    fs::write(
+        dir.join("tests.scm"),
+        r#"(function_definition name: (identifier) @test.name (#match? @test.name "^test_")) @test"#,
+    )
+    .unwrap();
+    fs::write(
+        dir.join("calls.scm"),
+        r#"(call function: (identifier) @call.name) @call
+(call function: (attribute object: (_) @call.receiver attribute: (identifier) @call.name)) @call"#,
+    )
+    .unwrap();
+    fs::create_dir_all(repo.join("shop")).unwrap();
+    fs::create_dir_all(repo.join("tests")).unwrap();
+    fs::write(
+        repo.join("shop/cart.py"),
+        r#"class Cart:
+    def add(self, price):
+        pass
+
+    def total(self):
+        return 0
+
+class Order:
+    def total(self):
+        return 0
+
+    @staticmethod
+    def empty():
+        return Order()
+
+def slugify(s):
+    return s.lower()
+"#,
+    )
+    .unwrap();
+    fs::write(
+        repo.join("tests/test_cart.py"),
+        r#"class TestCart:
+    def test_total(self):
+        cart = Cart()
+        cart.add(1)
+        assert cart.total() == 1
+
+    def helper(self):
+        slugify("x")
+
+def test_empty():
+    Order.empty()
+"#,
+    )
+    .unwrap();
+
+    let language = load_query_languages(&config_dir).unwrap().remove(0);
+    let queries = language.queries().unwrap();
+    let repo = repo.to_string_lossy().to_string();
+    let mut methods = Vec::new();
+    for file in ["shop/cart.py", "tests/test_cart.py"] {
+        let path = Path::new(&repo).join(file);
+        let source_code = fs::read_to_string(&path).unwrap();
+        let tree = queries.parser().parse(&source_code, None).unwrap();
+        traverse_query_nodes(
+            tree.root_node(),
+            &source_code,
+            &path.to_string_lossy(),
+            queries.is_test_file(&repo, &path),
+            &queries,
+            &mut methods,
+        );
+    }
+
+    let logic: Vec<String> = methods
+        .iter()
+        .filter(|m| !m.is_test)
+        .map(|m| format!("{}.{}", m.class_name, m.method_name))
+        .collect();
+    assert_eq!(
+        logic,
+        vec![
+            "Cart.add",
+            "Cart.total",
+            "Order.total",
+            "Order.empty",
+            ".slugify"
+        ]
+    );
+
+    let tested = extract_query_tested_methods(&repo, &language, &methods);
+    let tests_for = |class: &str, name: &str| {
+        let method = methods
+            .iter()
+            .find(|m| m.class_name == class && m.method_name == name)
+            .unwrap();
+        tested.tests_for(method)
+    };
+    assert_eq!(tests_for("Cart", "add"), vec!["TestCart.test_total"]);
+    // Without a receiver naming an owner, calls match by name alone
+    assert_eq!(tests_for("Order", "total"), vec!["TestCart.test_total"]);
+    assert_eq!(tests_for("Order", "empty"), vec!["test_empty"]);
+    assert!(tests_for("", "slugify").is_empty());
+}
@@ -0,0 +1,309 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::coverage::{coverage_percent, method_id};
+use crate::utils::{normalize_path, CoverageStatus, LangSettings, Method, TestedMethods};
+
+/// A logic method with what the analysis found for it, as the report formats see it.
+pub struct MethodCoverage<'a> {
+    pub method: &'a Method,
+    // The method as the text report names it
+    pub id: String,
+    // The method's file, relative to the repo and with `/` separators
+    pub path: String,
+    pub status: CoverageStatus,
+    pub tests: Vec<String>,
+}
+
+impl MethodCoverage<'_> {
+    // Like the text report's totals, only direct calls from tests count as coverage
+    pub fn is_covered(&self) -> bool {
+        self.status == CoverageStatus::Covered
+    }
+
+    /// The number of tests calling the method, as a stand-in for execution counts.
+    pub fn hits(&self) -> usize {
+        if self.is_covered() {
+            self.tests.len()
+        } else {
+            0
+        }
+    }
+}
+
+/// A test method and the file it's in, relative to the repo.
+pub struct TestMethod<'a> {
+    pub method: &'a Method,
+    pub id: String,
+    pub path: String,
+}
+
+/// The logic methods of an analysis with their coverage, in the order they were found.
+pub struct CoverageResults<'a> {
+    // The repo's absolute path
+    pub root: PathBuf,
+    pub methods: Vec<MethodCoverage<'a>>,
+    pub test_methods: Vec<TestMethod<'a>>,
+    // The file and line of the test methods, under the names tests are recorded by.
+    // Tests that aren't methods, such as Jest's `it` blocks, aren't in it.
+    pub test_locations: HashMap<String, (String, usize)>,
+}
+
+impl<'a> CoverageResults<'a> {
+    pub fn new(
+        repo: &str,
+        data: &'a [Method],
+        tests: &TestedMethods,
+        lang_settings: &LangSettings,
+    ) -> CoverageResults<'a> {
+        let base = normalize_path(Path::new(repo));
+        let relative_path = |method: &Method| {
+            let path = normalize_path(Path::new(&method.file_path));
+            path.strip_prefix(&base)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace('\\', "/")
+        };
+        let methods = data
+            .iter()
+            .filter(|m| !m.is_test)
+            .map(|method| MethodCoverage {
+                method,
+                id: method_id(&method.class_name, &method.method_name, lang_settings),
+                path: relative_path(method),
+                status: tests.status(method),
+                tests: tests.tests_for(method),
+            })
+            .collect();
+
+        let test_methods: Vec<TestMethod> = data
+            .iter()
+            .filter(|m| m.is_test)
+            .map(|method| TestMethod {
+                method,
+                id: method_id(&method.class_name, &method.method_name, lang_settings),
+                path: relative_path(method),
+            })
+            .collect();
+
+        let mut test_locations = HashMap::new();
+        for test in &test_methods {
+            let location = (test.path.clone(), test.method.start_line);
+            if !test.method.class_name.is_empty() {
+                test_locations
+                    .entry(format!(
+                        "{}.{}",
+                        test.method.class_name, test.method.method_name
+                    ))
+                    .or_insert_with(|| location.clone());
+            }
+            test_locations
+                .entry(test.method.method_name.clone())
+                .or_insert(location);
+        }
+
+        CoverageResults {
+            root: fs::canonicalize(repo).unwrap_or(base),
+            methods,
+            test_methods,
+            test_locations,
+        }
+    }
+
+    pub fn covered(&self) -> usize {
+        self.methods.iter().filter(|m| m.is_covered()).count()
+    }
+
+    pub fn percent(&self) -> f64 {
+        coverage_percent(self.covered(), self.methods.len())
+    }
+}
+
+/// Method counts for a group of methods, such as a language or directory.
+#[derive(Default)]
+pub struct MethodTotals {
+    pub total: usize,
+    pub covered: usize,
+    pub via_interface: usize,
+}
+
+impl MethodTotals {
+    pub fn add(&mut self, method: &MethodCoverage) {
+        self.total += 1;
+        match method.status {
+            CoverageStatus::Covered => self.covered += 1,
+            CoverageStatus::ViaInterface => self.via_interface += 1,
+            CoverageStatus::Uncovered => {}
+        }
+    }
+
+    pub fn percent(&self) -> f64 {
+        coverage_percent(self.covered, self.total)
+    }
+
+    pub fn percent_with_interface(&self) -> f64 {
+        coverage_percent(self.covered + self.via_interface, self.total)
+    }
+}
+
+/// The language a file is written in, going by its extension.
+pub fn language_name(path: &str) -> String {
+    let ext = Path::new(path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let name = match ext.as_str() {
+        "rs" => "Rust",
+        "cs" => "C#",
+        "py" => "Python",
+        "js" | "jsx" | "mjs" | "cjs" => "JavaScript",
+        "ts" | "tsx" | "mts" | "cts" => "TypeScript",
+        "go" => "Go",
+        "java" => "Java",
+        "kt" | "kts" => "Kotlin",
+        "c" | "h" => "C",
+        "cpp" | "cc" | "cxx" | "hpp" | "hh" | "hxx" => "C++",
+        "rb" => "Ruby",
+        "php" => "PHP",
+        "swift" => "Swift",
+        "" => return String::from("Other"),
+        _ => return format!(".{}", ext),
+    };
+    name.to_string()
+}
+
+/// The directory of a repo-relative path, `.` for the repo itself.
+pub fn directory(path: &str) -> String {
+    match path.rsplit_once('/') {
+        Some((dir, _)) if !dir.is_empty() => dir.to_string(),
+        _ => String::from("."),
+    }
+}
+
+/// Splits a package-qualified class name into its package and class, at the first
+/// segment starting with an uppercase letter: `com.example.Cart.Line` gives
+/// `com.example` and `Cart.Line`.
+pub fn split_package(class_name: &str) -> (String, String) {
+    let segments: Vec<&str> = class_name.split('.').collect();
+    let class_start = segments
+        .iter()
+        .position(|s| s.starts_with(|c: char| c.is_uppercase()))
+        .unwrap_or(segments.len());
+    (
+        segments[..class_start].join("."),
+        segments[class_start..].join("."),
+    )
+}
+
+/// Hits per line, from the spans of the methods covering them. Where spans overlap, a
+/// line keeps the highest count.
+#[derive(Default)]
+pub struct LineHits(BTreeMap<usize, usize>);
+
+impl LineHits {
+    pub fn add(&mut self, method: &MethodCoverage) {
+        for line in method.method.start_line..=method.method.end_line() {
+            let hits = self.0.entry(line).or_default();
+            *hits = (*hits).max(method.hits());
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&usize, &usize)> {
+        self.0.iter()
+    }
+
+    pub fn covered(&self) -> usize {
+        self.0.values().filter(|hits| **hits > 0).count()
+    }
+
+    pub fn valid(&self) -> usize {
+        self.0.len()
+    }
+}
+
+/// A method of an earlier run, as its Cobertura report lists it.
+#[derive(Debug, PartialEq)]
+pub struct BaselineMethod {
+    // Relative to the repo, with `/` separators
+    pub file: String,
+    pub class: String,
+    pub name: String,
+    pub covered: bool,
+}
+
+/// Method coverage from an earlier run, read from the Cobertura report it wrote.
+#[derive(Debug, Default, PartialEq)]
+pub struct Baseline {
+    pub methods: Vec<BaselineMethod>,
+}
+
+impl Baseline {
+    pub fn load(path: &Path) -> Result<Baseline> {
+        let xml = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Baseline::parse(&xml).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    // A method with any line hit counts as covered
+    pub fn parse(xml: &str) -> Result<Baseline> {
+        let document = roxmltree::Document::parse_with_options(
+            xml,
+            roxmltree::ParsingOptions {
+                allow_dtd: true,
+                ..Default::default()
+            },
+        )?;
+        let mut methods = Vec::new();
+        for class in document.descendants().filter(|n| n.has_tag_name("class")) {
+            let file = class
+                .attribute("filename")
+                .unwrap_or_default()
+                .replace('\\', "/");
+            for method in class.descendants().filter(|n| n.has_tag_name("method")) {
+                let rate: f64 = method
+                    .attribute("line-rate")
+                    .and_then(|rate| rate.parse().ok())
+                    .unwrap_or_default();
+                methods.push(BaselineMethod {
+                    file: file.clone(),
+                    class: class.attribute("name").unwrap_or_default().to_string(),
+                    name: method.attribute("name").unwrap_or_default().to_string(),
+                    covered: rate > 0.0,
+                });
+            }
+        }
+        Ok(Baseline { methods })
+    }
+
+    pub fn totals(&self, group: impl Fn(&str) -> String) -> BTreeMap<String, MethodTotals> {
+        let mut totals: BTreeMap<String, MethodTotals> = BTreeMap::new();
+        for method in &self.methods {
+            let group_totals = totals.entry(group(&method.file)).or_default();
+            group_totals.total += 1;
+            if method.covered {
+                group_totals.covered += 1;
+            }
+        }
+        totals
+    }
+
+    /// Whether the method was covered in the baseline, if it was there at all. Free
+    /// functions are looked up under their file's name, the class Cobertura reports
+    /// list them in.
+    pub fn was_covered(&self, method: &MethodCoverage) -> Option<bool> {
+        let class = if method.method.class_name.is_empty() {
+            method.path.rsplit('/').next().unwrap_or_default()
+        } else {
+            &method.method.class_name
+        };
+        self.methods
+            .iter()
+            .find(|m| {
+                m.file == method.path && m.class == class && m.name == method.method.method_name
+            })
+            .map(|m| m.covered)
+    }
+}
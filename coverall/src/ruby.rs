@@ -0,0 +1,828 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+use crate::utils::{
+    extract_body, get_parser, repo_relative_path, should_skip_dir, CallTarget, Method,
+    TestedMethods,
+};
+
+// RSpec (and Minitest::Spec) blocks grouping examples
+const RSPEC_GROUPS: [&str; 4] = ["describe", "context", "feature", "shared_examples"];
+const RSPEC_EXAMPLES: [&str; 5] = ["it", "specify", "example", "scenario", "test"];
+// `let` and `subject` definitions, evaluated when an example refers to them
+const RSPEC_MEMOIZED: [&str; 4] = ["let", "let!", "subject", "subject!"];
+
+/// RSpec keeps specs under `spec/` and Minitest under `test/`; `*_spec.rb` and
+/// `*_test.rb` files elsewhere count as well.
+pub fn is_ruby_test_file(repo: &str, path: &Path) -> bool {
+    let path = repo_relative_path(repo, path);
+    let in_test_dir = path.parent().is_some_and(|dir| {
+        dir.components()
+            .any(|c| c.as_os_str() == "spec" || c.as_os_str() == "test")
+    });
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    in_test_dir || stem.ends_with("_spec") || stem.ends_with("_test") || stem.starts_with("test_")
+}
+
+/// Collects `def` methods, both instance methods and class methods (`def self.foo`
+/// and methods in `class << self`), named after their nesting of modules and
+/// classes, e.g. `Shop::Cart`.
+pub fn traverse_ruby_nodes(
+    node: tree_sitter::Node,
+    source_code: &str,
+    file_path: &str,
+    is_test_file: bool,
+    methods: &mut Vec<Method>,
+) {
+    collect_ruby_methods(
+        node,
+        source_code,
+        file_path,
+        is_test_file,
+        &mut Vec::new(),
+        methods,
+    );
+}
+
+fn collect_ruby_methods(
+    node: tree_sitter::Node,
+    source_code: &str,
+    file_path: &str,
+    is_test_file: bool,
+    scopes: &mut Vec<String>,
+    methods: &mut Vec<Method>,
+) {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+
+    match node.kind() {
+        "class" | "module" => {
+            let Some(name) = node.child_by_field_name("name") else {
+                return;
+            };
+            scopes.push(text(name));
+            let mut cursor = node.walk();
+            for child in node.named_children(&mut cursor) {
+                collect_ruby_methods(child, source_code, file_path, is_test_file, scopes, methods);
+            }
+            scopes.pop();
+            return;
+        }
+        "method" | "singleton_method" => {
+            if let Some(name) = node.child_by_field_name("name") {
+                let method_name = text(name);
+                methods.push(Method {
+                    class_name: scopes.join("::"),
+                    // Minitest helpers and specs' `def`s aren't logic to be covered
+                    is_test: is_test_file || method_name.starts_with("test_"),
+                    method_name,
+                    body: extract_body(node, source_code),
+                    start_line: node.start_position().row + 1,
+                    file_path: file_path.to_string(),
+                });
+            }
+            // Methods defined inside a method only exist once it has run
+            return;
+        }
+        _ => {}
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_ruby_methods(child, source_code, file_path, is_test_file, scopes, methods);
+    }
+}
+
+// The receiver of a call made in a test
+#[derive(PartialEq, Debug, Clone)]
+enum RubyReceiver {
+    // `helper(x)`
+    Unqualified,
+    // `Shop::Cart.empty`, `described_class.new.total` or `cart.total` on a typed `let`
+    Type(String),
+    // Anything else, such as the result of a chained call
+    Unknown,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+struct RubyCall {
+    receiver: RubyReceiver,
+    name: String,
+}
+
+// An example group (or a Minitest class), with what its examples inherit
+#[derive(Clone, Default)]
+struct RubyGroup<'a> {
+    path: Vec<String>,
+    described: Option<String>,
+    memoized: HashMap<String, tree_sitter::Node<'a>>,
+    hooks: Vec<tree_sitter::Node<'a>>,
+}
+
+struct RubyTest<'a> {
+    name: String,
+    block: tree_sitter::Node<'a>,
+    group: RubyGroup<'a>,
+}
+
+fn call_name(node: tree_sitter::Node, source_code: &str) -> Option<String> {
+    let method = node.child_by_field_name("method")?;
+    Some(source_code[method.start_byte()..method.end_byte()].to_string())
+}
+
+// The first argument of a call as a title: a string's contents, a constant or a symbol
+fn first_argument(node: tree_sitter::Node, source_code: &str) -> Option<String> {
+    let argument = node.child_by_field_name("arguments")?.named_child(0)?;
+    let text = &source_code[argument.start_byte()..argument.end_byte()];
+    Some(match argument.kind() {
+        "string" => text.trim_matches(['"', '\'']).to_string(),
+        "simple_symbol" => text.trim_start_matches(':').to_string(),
+        _ => text.to_string(),
+    })
+}
+
+// `skip:` or `pending:` metadata, also written as the bare symbols `:skip` and `:pending`
+fn is_skipped_example(node: tree_sitter::Node, source_code: &str) -> bool {
+    node.child_by_field_name("arguments")
+        .is_some_and(|arguments| {
+            let mut cursor = arguments.walk();
+            let skipped = arguments
+                .named_children(&mut cursor)
+                .any(|a| match a.kind() {
+                    "pair" => a.child_by_field_name("key").is_some_and(|k| {
+                        let key = &source_code[k.start_byte()..k.end_byte()];
+                        matches!(key, "skip:" | "skip" | ":skip" | "pending:" | "pending")
+                    }),
+                    "simple_symbol" => matches!(&source_code[a.byte_range()], ":skip" | ":pending"),
+                    _ => false,
+                });
+            skipped
+        })
+}
+
+// A `skip` at the top of an example or Minitest test, which ends it before anything
+// after it runs
+fn skips_itself(node: tree_sitter::Node, source_code: &str) -> bool {
+    node.child_by_field_name("body").is_some_and(|body| {
+        let mut cursor = body.walk();
+        let skipped = body.named_children(&mut cursor).any(|statement| {
+            let name = match statement.kind() {
+                "identifier" => Some(&source_code[statement.byte_range()]),
+                "call" if statement.child_by_field_name("receiver").is_none() => statement
+                    .child_by_field_name("method")
+                    .map(|m| &source_code[m.byte_range()]),
+                _ => None,
+            };
+            name == Some("skip")
+        });
+        skipped
+    })
+}
+
+// Finds the examples of RSpec and Minitest::Spec groups, and Minitest `test_*`
+// methods and `test "..."` blocks, with the group each one belongs to
+fn collect_ruby_tests<'a>(
+    node: tree_sitter::Node<'a>,
+    source_code: &str,
+    group: &RubyGroup<'a>,
+    tests: &mut Vec<RubyTest<'a>>,
+) {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+
+    match node.kind() {
+        "class" => {
+            let Some(name) = node.child_by_field_name("name") else {
+                return;
+            };
+            let mut group = group.clone();
+            group.path.push(text(name));
+            if let Some(body) = node.child_by_field_name("body") {
+                let mut cursor = body.walk();
+                group
+                    .hooks
+                    .extend(body.named_children(&mut cursor).filter(|m| {
+                        m.kind() == "method"
+                            && m.child_by_field_name("name")
+                                .is_some_and(|n| text(n) == "setup")
+                    }));
+
+                let mut cursor = body.walk();
+                for child in body.named_children(&mut cursor) {
+                    let name = child.child_by_field_name("name").map(text);
+                    match name {
+                        Some(name) if child.kind() == "method" && name.starts_with("test_") => {
+                            if !skips_itself(child, source_code) {
+                                let mut path = group.path.clone();
+                                path.push(name);
+                                tests.push(RubyTest {
+                                    name: path.join("."),
+                                    block: child,
+                                    group: group.clone(),
+                                });
+                            }
+                        }
+                        _ => collect_ruby_tests(child, source_code, &group, tests),
+                    }
+                }
+            }
+            return;
+        }
+        "call" => {
+            let name = call_name(node, source_code).unwrap_or_default();
+            let receiver = node.child_by_field_name("receiver").map(text);
+            let block = node.child_by_field_name("block");
+
+            // `xdescribe`, `xcontext` and groups marked `skip:` skip all they hold
+            let is_group = |name: &str| RSPEC_GROUPS.contains(&name);
+            if block.is_some()
+                && (name.strip_prefix('x').is_some_and(is_group)
+                    || (is_group(&name) && is_skipped_example(node, source_code)))
+            {
+                return;
+            }
+
+            if let (true, Some(block)) = (
+                RSPEC_GROUPS.contains(&name.as_str())
+                    && receiver.as_deref().is_none_or(|r| r == "RSpec"),
+                block,
+            ) {
+                let mut group = group.clone();
+                let argument = node
+                    .child_by_field_name("arguments")
+                    .and_then(|a| a.named_child(0));
+                if let Some(argument) =
+                    argument.filter(|a| matches!(a.kind(), "constant" | "scope_resolution"))
+                {
+                    group.described = Some(text(argument));
+                }
+                group
+                    .path
+                    .push(first_argument(node, source_code).unwrap_or(name));
+
+                if let Some(body) = block.child_by_field_name("body") {
+                    let mut cursor = body.walk();
+                    for child in body
+                        .named_children(&mut cursor)
+                        .filter(|c| c.kind() == "call" && c.child_by_field_name("block").is_some())
+                    {
+                        let name = call_name(child, source_code).unwrap_or_default();
+                        let block = child.child_by_field_name("block").unwrap();
+                        if RSPEC_MEMOIZED.contains(&name.as_str()) {
+                            if name.starts_with("subject") {
+                                group.memoized.insert(String::from("subject"), block);
+                            }
+                            if let Some(named) = first_argument(child, source_code) {
+                                group.memoized.insert(named, block);
+                            }
+                        } else if name == "before" {
+                            group.hooks.push(block);
+                        }
+                    }
+
+                    let mut cursor = body.walk();
+                    for child in body.named_children(&mut cursor) {
+                        collect_ruby_tests(child, source_code, &group, tests);
+                    }
+                }
+                return;
+            }
+
+            if let (true, Some(block)) = (RSPEC_EXAMPLES.contains(&name.as_str()), block) {
+                if !is_skipped_example(node, source_code) && !skips_itself(block, source_code) {
+                    let in_class = node
+                        .parent()
+                        .and_then(|body| body.parent())
+                        .is_some_and(|c| c.kind() == "class");
+                    let (title, separator) = match first_argument(node, source_code) {
+                        // Rails' `test "adds items"` defines `test_adds_items`
+                        Some(title) if name == "test" && in_class => {
+                            (format!("test_{}", title.replace(' ', "_")), ".")
+                        }
+                        Some(title) => (title, " > "),
+                        None => (
+                            format!("example at line {}", node.start_position().row + 1),
+                            " > ",
+                        ),
+                    };
+                    let mut path = group.path.clone();
+                    path.push(title);
+                    tests.push(RubyTest {
+                        name: path.join(separator),
+                        block,
+                        group: group.clone(),
+                    });
+                }
+                return;
+            }
+        }
+        _ => {}
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_ruby_tests(child, source_code, group, tests);
+    }
+}
+
+// The class a `let`, `subject` or instance variable holds, from the expression
+// assigned to it: `described_class.new`, `Cart.new(...)` or `Cart.build(...)`
+fn ruby_value_type(
+    value: tree_sitter::Node,
+    source_code: &str,
+    described: Option<&str>,
+) -> Option<String> {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+    match value.kind() {
+        "constant" | "scope_resolution" => Some(text(value)),
+        "identifier" if text(value) == "described_class" => described.map(String::from),
+        "call" => ruby_value_type(
+            value.child_by_field_name("receiver")?,
+            source_code,
+            described,
+        ),
+        "block" | "do_block" | "block_body" | "body_statement" => {
+            let body = value.child_by_field_name("body").unwrap_or(value);
+            let last = body.named_child(body.named_child_count().checked_sub(1)?)?;
+            ruby_value_type(last, source_code, described)
+        }
+        _ => None,
+    }
+}
+
+// `@cart = Cart.new` assignments in hooks and `setup` methods
+fn collect_ruby_instance_variables(
+    node: tree_sitter::Node,
+    source_code: &str,
+    described: Option<&str>,
+    types: &mut HashMap<String, String>,
+) {
+    if node.kind() == "assignment" {
+        if let (Some(left), Some(right)) = (
+            node.child_by_field_name("left"),
+            node.child_by_field_name("right"),
+        ) {
+            if let Some(class) = ruby_value_type(right, source_code, described) {
+                types.insert(
+                    source_code[left.start_byte()..left.end_byte()].to_string(),
+                    class,
+                );
+            }
+        }
+    }
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_ruby_instance_variables(child, source_code, described, types);
+    }
+}
+
+fn collect_ruby_calls(
+    node: tree_sitter::Node,
+    source_code: &str,
+    types: &HashMap<String, String>,
+    described: Option<&str>,
+    calls: &mut Vec<RubyCall>,
+    references: &mut Vec<String>,
+) {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+
+    match node.kind() {
+        // A bare name may be a `let` or `subject`
+        "identifier" => references.push(text(node)),
+        "call" => {
+            if let Some(name) = call_name(node, source_code) {
+                let receiver = match node.child_by_field_name("receiver") {
+                    None => {
+                        references.push(name.clone());
+                        RubyReceiver::Unqualified
+                    }
+                    Some(receiver) => match receiver.kind() {
+                        "identifier" | "instance_variable" => types
+                            .get(&text(receiver))
+                            .cloned()
+                            .or_else(|| ruby_value_type(receiver, source_code, described))
+                            .map_or(RubyReceiver::Unknown, RubyReceiver::Type),
+                        "call" if call_name(receiver, source_code).as_deref() == Some("new") => {
+                            ruby_value_type(receiver, source_code, described)
+                                .map_or(RubyReceiver::Unknown, RubyReceiver::Type)
+                        }
+                        "constant" | "scope_resolution" => RubyReceiver::Type(text(receiver)),
+                        _ => RubyReceiver::Unknown,
+                    },
+                };
+                // `Cart.new` runs `initialize`
+                let name = if name == "new" && receiver != RubyReceiver::Unqualified {
+                    String::from("initialize")
+                } else {
+                    name
+                };
+                let call = RubyCall { receiver, name };
+                if !calls.contains(&call) {
+                    calls.push(call);
+                }
+            }
+        }
+        _ => {}
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_ruby_calls(child, source_code, types, described, calls, references);
+    }
+}
+
+/// Resolves the calls made by RSpec examples and Minitest tests. Calls made in the
+/// `before` hooks or `setup` method around a test, and in the `let`s and `subject`
+/// it refers to, count for it too. `described_class` and `subject` are resolved to
+/// the class passed to the outermost `describe`.
+pub fn extract_ruby_tested_methods(repo: &str, logic_methods: &[Method]) -> TestedMethods {
+    let mut tested_methods = TestedMethods::default();
+    let candidates: Vec<&Method> = logic_methods.iter().filter(|m| !m.is_test).collect();
+
+    for entry in WalkDir::new(repo)
+        .into_iter()
+        .filter_entry(|e| !should_skip_dir(e))
+        .filter_map(Result::ok)
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "rb"))
+        .filter(|e| is_ruby_test_file(repo, e.path()))
+    {
+        let Ok(source_code) = fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let mut parser = get_parser("rb");
+        let Some(tree) = parser.parse(&source_code, None) else {
+            continue;
+        };
+
+        let mut tests = Vec::new();
+        collect_ruby_tests(
+            tree.root_node(),
+            &source_code,
+            &RubyGroup::default(),
+            &mut tests,
+        );
+
+        for test in tests {
+            let described = test.group.described.as_deref();
+            let mut types = HashMap::new();
+            for (name, block) in &test.group.memoized {
+                if let Some(class) = ruby_value_type(*block, &source_code, described) {
+                    types.insert(name.clone(), class);
+                }
+            }
+            for hook in &test.group.hooks {
+                collect_ruby_instance_variables(*hook, &source_code, described, &mut types);
+            }
+            if let Some(described) = described {
+                types
+                    .entry(String::from("subject"))
+                    .or_insert_with(|| described.to_string());
+            }
+
+            let mut calls = Vec::new();
+            let mut references = Vec::new();
+            let mut blocks = vec![test.block];
+            blocks.extend(&test.group.hooks);
+            let mut evaluated: Vec<String> = Vec::new();
+            while let Some(block) = blocks.pop() {
+                collect_ruby_calls(
+                    block,
+                    &source_code,
+                    &types,
+                    described,
+                    &mut calls,
+                    &mut references,
+                );
+                // `let`s are only evaluated when an example (or another `let`) uses them
+                for reference in references.drain(..) {
+                    if let Some(memoized) = test.group.memoized.get(&reference) {
+                        if !evaluated.contains(&reference) {
+                            evaluated.push(reference);
+                            blocks.push(*memoized);
+                        }
+                    }
+                }
+            }
+
+            for call in calls {
+                for method in candidates.iter().filter(|m| {
+                    m.method_name == call.name
+                        && match &call.receiver {
+                            RubyReceiver::Unqualified => m.class_name.is_empty(),
+                            RubyReceiver::Type(class) => {
+                                m.class_name == *class
+                                    || m.class_name.ends_with(&format!("::{}", class))
+                            }
+                            RubyReceiver::Unknown => !m.class_name.is_empty(),
+                        }
+                }) {
+                    tested_methods.record(CallTarget::exact(method), &test.name);
+                }
+            }
+        }
+    }
+
+    tested_methods
+}
+
+#[test]
+fn test_traverse_ruby_nodes() {
+    let source_code = r#"
+module Shop
+  class Cart
+    def initialize(items = [])
+      @items = items
+    end
+
+    def self.empty
+      new
+    end
+
+    class << self
+      def build(*items) = new(items)
+    end
+
+    private
+
+    def secret; 1; end
+  end
+end
+
+class Shop::Discount
+  def apply(x) x end
+end
+
+def helper; end
+"#;
+    let mut parser = get_parser("rb");
+    let tree = parser.parse(source_code, None).unwrap();
+    let mut methods = Vec::new();
+    traverse_ruby_nodes(
+        tree.root_node(),
+        source_code,
+        "lib/shop/cart.rb",
+        false,
+        &mut methods,
+    );
+
+    let found: Vec<(&str, &str)> = methods
+        .iter()
+        .map(|m| (m.class_name.as_str(), m.method_name.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("Shop::Cart", "initialize"),
+            ("Shop::Cart", "empty"),
+            ("Shop::Cart", "build"),
+            ("Shop::Cart", "secret"),
+            ("Shop::Discount", "apply"),
+            ("", "helper"),
+        ]
+    );
+    assert!(is_ruby_test_file(
+        ".",
+        Path::new("spec/models/cart_spec.rb")
+    ));
+    assert!(is_ruby_test_file(".", Path::new("./test/cart_test.rb")));
+    assert!(!is_ruby_test_file(".", Path::new("lib/shop/cart.rb")));
+}
+
+#[test]
+fn test_is_ruby_test_file() {
+    // Only directories inside the repo count
+    let repo = "/home/me/spec/shop";
+    assert!(!is_ruby_test_file(
+        repo,
+        Path::new("/home/me/spec/shop/lib/cart.rb")
+    ));
+    assert!(is_ruby_test_file(
+        repo,
+        Path::new("/home/me/spec/shop/spec/cart_spec.rb")
+    ));
+    assert!(is_ruby_test_file(
+        &format!("{}/", repo),
+        Path::new("/home/me/spec/shop/test/cart_test.rb")
+    ));
+    // Files outside the repo root are judged on their whole path
+    assert!(is_ruby_test_file(repo, Path::new("/srv/spec/support.rb")));
+    assert!(!is_ruby_test_file(repo, Path::new("/srv/gems/lib/cart.rb")));
+}
+
+#[test]
+fn test_extract_ruby_tested_methods() {
+    use std::fs::create_dir_all;
+    use tempfile::tempdir;
+
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    // Checked out under a spec/ directory, which mustn't make lib/ test code
+    let root = temp_dir.path().join("spec/shop");
+    for dir in ["lib", "spec", "test"] {
+        create_dir_all(root.join(dir)).unwrap();
+    }
+    fs::write(
+        root.join("lib/cart.rb"),
+        r#"module Shop
+  class Cart
+    def initialize(items = []); @items = items; end
+    def total; @items.sum; end
+    def count; @items.size; end
+    def clear; @items = []; end
+    def self.empty; new; end
+  end
+
+  class Discount
+    def apply(x) x end
+    def remove(x) x end
+  end
+end
+"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("spec/cart_spec.rb"),
+        r##"RSpec.describe Shop::Cart do
+  subject(:cart) { described_class.new([1, 2]) }
+  let(:empty) { Shop::Cart.empty }
+
+  describe "#total" do
+    it "sums items" do
+      expect(cart.total).to eq(3)
+    end
+
+    context "when empty" do
+      it { expect(described_class.new.count).to eq(0) }
+    end
+  end
+
+  xit "clears" do
+    subject.clear
+  end
+end
+"##,
+    )
+    .unwrap();
+    fs::write(
+        root.join("test/discount_test.rb"),
+        r#"class DiscountTest < Minitest::Test
+  def setup
+    @discount = Shop::Discount.new
+  end
+
+  def test_apply
+    assert_equal 1, @discount.apply(1)
+  end
+
+  def helper_remove
+    @discount.remove(1)
+  end
+end
+"#,
+    )
+    .unwrap();
+
+    let mut methods = Vec::new();
+    for file in ["lib/cart.rb", "spec/cart_spec.rb", "test/discount_test.rb"] {
+        let path = root.join(file);
+        let source_code = fs::read_to_string(&path).unwrap();
+        let mut parser = get_parser("rb");
+        let tree = parser.parse(&source_code, None).unwrap();
+        traverse_ruby_nodes(
+            tree.root_node(),
+            &source_code,
+            &path.to_string_lossy(),
+            is_ruby_test_file(&root.to_string_lossy(), &path),
+            &mut methods,
+        );
+    }
+
+    let tested = extract_ruby_tested_methods(&root.to_string_lossy(), &methods);
+    let tests_for = |name: &str| {
+        let method = methods
+            .iter()
+            .find(|m| !m.is_test && m.method_name == name)
+            .unwrap();
+        tested.tests_for(method)
+    };
+
+    assert_eq!(tests_for("total"), vec!["Shop::Cart > #total > sums items"]);
+    assert_eq!(
+        tests_for("count"),
+        vec!["Shop::Cart > #total > when empty > example at line 11"]
+    );
+    assert!(tests_for("clear").is_empty());
+    // `empty` is never referenced, so the `let` isn't evaluated
+    assert!(tests_for("empty").is_empty());
+    assert_eq!(tests_for("apply"), vec!["DiscountTest.test_apply"]);
+    assert!(tests_for("remove").is_empty());
+    assert_eq!(
+        tests_for("initialize"),
+        vec![
+            "Shop::Cart > #total > sums items",
+            "Shop::Cart > #total > when empty > example at line 11",
+        ]
+    );
+}
+
+#[test]
+fn test_ruby_skipped_and_redefined_methods() {
+    use tempfile::tempdir;
+
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let root = temp_dir.path();
+    // Ruby has no overloads; a reopened class redefines `total` instead
+    fs::write(
+        root.join("cart.rb"),
+        r#"class Cart
+  def total; 0; end
+  def clear; end
+end
+
+class Cart
+  def total; 1; end
+end
+
+class Order
+  def total; 2; end
+end
+"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("cart_spec.rb"),
+        r##"RSpec.describe Cart do
+  it "totals" do
+    Cart.new.total
+  end
+
+  it "clears later", skip: "flaky" do
+    Cart.new.clear
+  end
+
+  it "clears eventually", :pending do
+    Cart.new.clear
+  end
+
+  it "clears soon" do
+    skip "not yet"
+    Cart.new.clear
+  end
+
+  xcontext "when empty" do
+    it { Cart.new.clear }
+  end
+
+  describe "#clear", skip: true do
+    it("resets") { Cart.new.clear }
+  end
+end
+
+xdescribe Order do
+  it { Order.new.total }
+end
+"##,
+    )
+    .unwrap();
+    fs::write(
+        root.join("cart_test.rb"),
+        r#"class CartTest < Minitest::Test
+  def test_clear
+    skip
+    Cart.new.clear
+  end
+end
+"#,
+    )
+    .unwrap();
+
+    let mut methods = Vec::new();
+    let path = root.join("cart.rb");
+    let source_code = fs::read_to_string(&path).unwrap();
+    let tree = get_parser("rb").parse(&source_code, None).unwrap();
+    traverse_ruby_nodes(
+        tree.root_node(),
+        &source_code,
+        &path.to_string_lossy(),
+        false,
+        &mut methods,
+    );
+
+    let tested = extract_ruby_tested_methods(&root.to_string_lossy(), &methods);
+    let tests_for = |class: &str, name: &str| -> Vec<Vec<String>> {
+        methods
+            .iter()
+            .filter(|m| m.class_name == class && m.method_name == name)
+            .map(|m| tested.tests_for(m))
+            .collect()
+    };
+
+    assert_eq!(
+        tests_for("Cart", "total"),
+        vec![vec!["Cart > totals"], vec!["Cart > totals"]]
+    );
+    assert_eq!(tests_for("Order", "total"), vec![Vec::<String>::new()]);
+    assert_eq!(tests_for("Cart", "clear"), vec![Vec::<String>::new()]);
+}
@@ -0,0 +1,279 @@
+use std::collections::HashSet;
+
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use serde_json::{json, Value};
+
+use crate::report::{Baseline, CoverageResults, TestMethod};
+use crate::utils::{CoverageStatus, Method};
+
+pub const UNTESTED_METHOD: &str = "coverme/untested-method";
+pub const TEST_WITHOUT_ASSERTION: &str = "coverme/test-without-assertion";
+
+const LEVELS: [&str; 4] = ["none", "note", "warning", "error"];
+
+struct Rule {
+    id: &'static str,
+    name: &'static str,
+    description: &'static str,
+    help: &'static str,
+    level: &'static str,
+}
+
+const RULES: [Rule; 2] = [
+    Rule {
+        id: UNTESTED_METHOD,
+        name: "UntestedMethod",
+        description: "Method isn't called by any test.",
+        help: "No test calls this method directly. Add a test exercising it, or remove the method if it's unused.",
+        level: "warning",
+    },
+    Rule {
+        id: TEST_WITHOUT_ASSERTION,
+        name: "TestWithoutAssertion",
+        description: "Test doesn't assert anything.",
+        help: "The test calls code without checking the outcome, so it only fails when something throws. Assert on the results it should produce.",
+        level: "note",
+    },
+];
+
+/// Parses a `--sarif-level` value such as `untested-method=error`, with or without
+/// the `coverme/` prefix on the rule.
+pub fn parse_rule_level(value: &str) -> Result<(String, String)> {
+    let (rule, level) = value
+        .split_once('=')
+        .ok_or_else(|| anyhow!("Expected <rule>=<level> rather than {}", value))?;
+    let rule = if rule.starts_with("coverme/") {
+        rule.to_string()
+    } else {
+        format!("coverme/{}", rule)
+    };
+    if !RULES.iter().any(|r| r.id == rule) {
+        return Err(anyhow!(
+            "Unknown rule {}, expected one of {}",
+            rule,
+            RULES.map(|r| r.id).join(", ")
+        ));
+    }
+    if !LEVELS.contains(&level) {
+        return Err(anyhow!(
+            "Unknown level {}, expected one of {}",
+            level,
+            LEVELS.join(", ")
+        ));
+    }
+    Ok((rule, level.to_string()))
+}
+
+// Assertions from the test frameworks of the supported languages: assert and expect
+// forms, RSpec and Chai's should, Mockito's verify, Go's t.Error and t.Fatal, XCTest,
+// Catch2's CHECK and REQUIRE, and tests expecting a panic or exception
+fn assertion_pattern() -> Regex {
+    Regex::new(
+        r"(?i:\b(assert|expect|refute|verify|should|must|require|fail|raises)\w*)|XCT(Assert|Fail|Unwrap)|\b(CHECK|REQUIRE)\w*|\bt\.(Error|Fatal)|\bpanic!",
+    )
+    .unwrap()
+}
+
+fn has_assertion(method: &Method, pattern: &Regex) -> bool {
+    method.body.iter().any(|line| pattern.is_match(line))
+}
+
+// Test classes also hold setup methods and helpers, so a method only counts as a test
+// when tests were recorded under its name or the name says it's one
+fn is_test(test: &TestMethod, test_names: &HashSet<&str>) -> bool {
+    let method = test.method;
+    test_names.contains(test.id.as_str())
+        || test_names.contains(method.method_name.as_str())
+        || test_names.contains(format!("{}.{}", method.class_name, method.method_name).as_str())
+        || method.method_name.to_lowercase().starts_with("test")
+}
+
+// Percent-encodes a path for use in a URI, keeping its separators
+fn uri_path(path: &str) -> String {
+    let mut uri = String::new();
+    for byte in path.replace('\\', "/").bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
+fn location(path: &str, method: &Method, id: &str) -> Value {
+    json!({
+        "physicalLocation": {
+            "artifactLocation": { "uri": uri_path(path), "uriBaseId": "SRCROOT" },
+            "region": { "startLine": method.start_line, "endLine": method.end_line() }
+        },
+        "logicalLocations": [{ "fullyQualifiedName": id, "kind": "function" }]
+    })
+}
+
+/// Renders the results as a SARIF 2.1.0 log for code scanning tools, with a result
+/// per uncovered method and per test without assertions. Levels can be overridden
+/// per rule with `(rule, level)` pairs, and a rule set to `none` reports nothing.
+/// Given a baseline, methods that were already uncovered in it are left out.
+pub fn sarif_report(
+    results: &CoverageResults,
+    levels: &[(String, String)],
+    baseline: Option<&Baseline>,
+) -> String {
+    let level_of = |rule: &Rule| {
+        levels
+            .iter()
+            .rev()
+            .find(|(id, _)| id == rule.id)
+            .map(|(_, level)| level.as_str())
+            .unwrap_or(rule.level)
+    };
+
+    let rules: Vec<Value> = RULES
+        .iter()
+        .map(|rule| {
+            let level = level_of(rule);
+            json!({
+                "id": rule.id,
+                "name": rule.name,
+                "shortDescription": { "text": rule.description },
+                "help": { "text": rule.help },
+                "defaultConfiguration": { "level": level, "enabled": level != "none" }
+            })
+        })
+        .collect();
+
+    let mut sarif_results = Vec::new();
+    let untested_level = level_of(&RULES[0]);
+    if untested_level != "none" {
+        for method in results
+            .methods
+            .iter()
+            .filter(|m| m.status == CoverageStatus::Uncovered)
+            .filter(|m| baseline.and_then(|b| b.was_covered(m)) != Some(false))
+        {
+            sarif_results.push(json!({
+                "ruleId": UNTESTED_METHOD,
+                "ruleIndex": 0,
+                "level": untested_level,
+                "message": { "text": format!("{} isn't called by any test.", method.id) },
+                "locations": [location(&method.path, method.method, &method.id)],
+                "partialFingerprints": { "methodId/v1": format!("{}:{}", method.path, method.id) }
+            }));
+        }
+    }
+
+    let assertion_level = level_of(&RULES[1]);
+    if assertion_level != "none" {
+        let pattern = assertion_pattern();
+        let test_names: HashSet<&str> = results
+            .methods
+            .iter()
+            .flat_map(|m| &m.tests)
+            .map(String::as_str)
+            .collect();
+        for test in results
+            .test_methods
+            .iter()
+            .filter(|t| is_test(t, &test_names) && !has_assertion(t.method, &pattern))
+        {
+            sarif_results.push(json!({
+                "ruleId": TEST_WITHOUT_ASSERTION,
+                "ruleIndex": 1,
+                "level": assertion_level,
+                "message": { "text": format!("{} doesn't assert anything.", test.id) },
+                "locations": [location(&test.path, test.method, &test.id)],
+                "partialFingerprints": { "methodId/v1": format!("{}:{}", test.path, test.id) }
+            }));
+        }
+    }
+
+    let root = results.root.to_string_lossy().replace('\\', "/");
+    let root_uri = format!(
+        "file://{}{}/",
+        if root.starts_with('/') { "" } else { "/" },
+        uri_path(root.trim_end_matches('/'))
+    );
+    let log = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "coverme",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_HOMEPAGE"),
+                    "rules": rules
+                }
+            },
+            "originalUriBaseIds": { "SRCROOT": { "uri": root_uri } },
+            "results": sarif_results
+        }]
+    });
+    format!("{}\n", serde_json::to_string_pretty(&log).unwrap())
+}
+
+#[test]
+fn test_sarif_report() {
+    use crate::codeanalysis::create_lang_settings;
+    use crate::utils::{CallTarget, Lang, TestedMethods};
+
+    let method = |file: &str, name: &str, body: &[&str], is_test: bool| Method {
+        class_name: String::from("Cart"),
+        method_name: name.to_string(),
+        body: body.iter().map(|l| l.to_string()).collect(),
+        start_line: 3,
+        is_test,
+        file_path: format!("/repo/{}", file),
+    };
+    let methods = vec![
+        method("src/cart.rb", "add", &["def add(item)", "end"], false),
+        method("src/cart.rb", "total", &["def total", "  0", "end"], false),
+        method(
+            "test/cart test.rb",
+            "test_add",
+            &["def test_add", "  assert_equal 1, Cart.new.add(1)", "end"],
+            true,
+        ),
+        method(
+            "test/cart test.rb",
+            "test_total",
+            &["def test_total", "  Cart.new.total", "end"],
+            true,
+        ),
+        method("test/cart test.rb", "setup", &["def setup", "end"], true),
+    ];
+    let mut tests = TestedMethods::default();
+    tests.record(CallTarget::exact(&methods[0]), "Cart.test_add");
+
+    let lang_settings = create_lang_settings(&Lang::Ruby);
+    let results = CoverageResults::new("/repo", &methods, &tests, &lang_settings);
+    let levels = vec![parse_rule_level("test-without-assertion=error").unwrap()];
+    let log: Value = serde_json::from_str(&sarif_report(&results, &levels, None)).unwrap();
+
+    let run = &log["runs"][0];
+    assert_eq!(run["originalUriBaseIds"]["SRCROOT"]["uri"], "file:///repo/");
+    assert_eq!(
+        run["tool"]["driver"]["rules"][1]["defaultConfiguration"]["level"],
+        "error"
+    );
+    let results_json = run["results"].as_array().unwrap();
+    assert_eq!(results_json.len(), 2);
+    assert_eq!(results_json[0]["ruleId"], UNTESTED_METHOD);
+    assert_eq!(results_json[0]["level"], "warning");
+    assert_eq!(
+        results_json[0]["locations"][0]["physicalLocation"]["region"],
+        json!({ "startLine": 3, "endLine": 5 })
+    );
+    assert_eq!(results_json[1]["ruleId"], TEST_WITHOUT_ASSERTION);
+    assert_eq!(results_json[1]["level"], "error");
+    assert_eq!(
+        results_json[1]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+        "test/cart%20test.rb"
+    );
+
+    assert!(parse_rule_level("untested-method=fatal").is_err());
+    assert!(parse_rule_level("coverme/missing=note").is_err());
+}
@@ -0,0 +1,754 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+use crate::utils::{
+    extract_body, get_parser, repo_relative_path, should_skip_dir, CallTarget, Method,
+    TestedMethods,
+};
+
+// XCTest methods run before every test of a case
+const XCTEST_HOOKS: [&str; 2] = ["setUp", "setUpWithError"];
+
+/// SwiftPM keeps tests in `Tests/<Target>Tests/`, and Xcode projects in a
+/// `<App>Tests` group.
+pub fn is_swift_test_file(repo: &str, path: &Path) -> bool {
+    let path = repo_relative_path(repo, path);
+    let in_test_dir = path.parent().is_some_and(|dir| {
+        dir.components().any(|c| {
+            let name = c.as_os_str().to_string_lossy();
+            name == "Tests" || name.ends_with("Tests")
+        })
+    });
+    in_test_dir
+        || path
+            .file_stem()
+            .is_some_and(|s| s.to_string_lossy().ends_with("Tests"))
+}
+
+fn swift_type_name(node: tree_sitter::Node, source_code: &str) -> Option<String> {
+    let name = node.child_by_field_name("name")?;
+    let name = &source_code[name.start_byte()..name.end_byte()];
+    // `extension Array<Int>` and `Cart!` name the type itself
+    let name = name.split('<').next().unwrap_or(name);
+    Some(name.trim_end_matches(['!', '?']).trim().to_string())
+}
+
+// Simple names of the attributes on a declaration, with their arguments as written
+fn swift_attributes(node: tree_sitter::Node, source_code: &str) -> Vec<(String, String)> {
+    let mut cursor = node.walk();
+    let Some(modifiers) = node.children(&mut cursor).find(|c| c.kind() == "modifiers") else {
+        return Vec::new();
+    };
+    let mut cursor = modifiers.walk();
+    let attributes = modifiers
+        .named_children(&mut cursor)
+        .filter(|a| a.kind() == "attribute")
+        .filter_map(|attribute| {
+            let name = attribute.named_child(0)?;
+            let name = &source_code[name.start_byte()..name.end_byte()];
+            let text = &source_code[attribute.start_byte()..attribute.end_byte()];
+            let arguments = text.split_once('(').map_or("", |(_, a)| a);
+            Some((name.to_string(), arguments.to_string()))
+        })
+        .collect();
+    attributes
+}
+
+// Swift Testing's `@Test` on a function, and `@Suite` on a type
+fn has_attribute(node: tree_sitter::Node, source_code: &str, attribute: &str) -> bool {
+    swift_attributes(node, source_code)
+        .iter()
+        .any(|(name, _)| name == attribute)
+}
+
+fn is_disabled(node: tree_sitter::Node, source_code: &str) -> bool {
+    swift_attributes(node, source_code)
+        .iter()
+        .any(|(name, arguments)| {
+            matches!(name.as_str(), "Test" | "Suite") && arguments.contains(".disabled")
+        })
+}
+
+// `throw XCTSkip(...)` as a statement of the test's own body, rather than under a
+// condition
+fn skips_itself(body: tree_sitter::Node, source_code: &str) -> bool {
+    let mut cursor = body.walk();
+    let statements: Vec<tree_sitter::Node> = body
+        .named_children(&mut cursor)
+        .flat_map(|c| {
+            let mut cursor = c.walk();
+            match c.kind() {
+                "statements" => c.named_children(&mut cursor).collect(),
+                _ => vec![c],
+            }
+        })
+        .collect();
+    statements.iter().any(|statement| {
+        statement.kind() == "control_transfer_statement"
+            && source_code[statement.byte_range()]
+                .strip_prefix("throw")
+                .is_some_and(|thrown| thrown.trim_start().starts_with("XCTSkip("))
+    })
+}
+
+fn is_xctest_case(node: tree_sitter::Node, source_code: &str) -> bool {
+    let mut cursor = node.walk();
+    let inherits = node
+        .children(&mut cursor)
+        .filter(|c| c.kind() == "inheritance_specifier")
+        .any(|c| source_code[c.start_byte()..c.end_byte()].ends_with("XCTestCase"));
+    inherits
+}
+
+/// Collects functions, methods, initializers, subscripts and computed properties.
+/// Members declared in an `extension Foo` are reported on `Foo`, as are default
+/// implementations in a protocol extension; nested types are named `Outer.Inner`.
+pub fn traverse_swift_nodes(
+    node: tree_sitter::Node,
+    source_code: &str,
+    file_path: &str,
+    is_test_file: bool,
+    methods: &mut Vec<Method>,
+) {
+    collect_swift_members(
+        node,
+        source_code,
+        file_path,
+        is_test_file,
+        &mut Vec::new(),
+        methods,
+    );
+}
+
+fn collect_swift_members(
+    node: tree_sitter::Node,
+    source_code: &str,
+    file_path: &str,
+    is_test: bool,
+    types: &mut Vec<String>,
+    methods: &mut Vec<Method>,
+) {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+
+    if matches!(node.kind(), "class_declaration" | "protocol_declaration") {
+        let (Some(name), Some(body)) = (
+            swift_type_name(node, source_code),
+            node.child_by_field_name("body"),
+        ) else {
+            return;
+        };
+        // Helpers in test types aren't logic to be covered
+        let is_test = is_test
+            || is_xctest_case(node, source_code)
+            || has_attribute(node, source_code, "Suite");
+        let mut cursor = node.walk();
+        let is_extension = node.children(&mut cursor).any(|c| c.kind() == "extension");
+        // `extension Outer.Inner` already names the full path
+        let outer = if is_extension {
+            std::mem::take(types)
+        } else {
+            types.clone()
+        };
+        types.push(name);
+        collect_swift_members(body, source_code, file_path, is_test, types, methods);
+        *types = outer;
+        return;
+    }
+
+    let name = match node.kind() {
+        "function_declaration" => node.child_by_field_name("name").map(text),
+        "init_declaration" => Some(String::from("init")),
+        "deinit_declaration" => Some(String::from("deinit")),
+        "subscript_declaration" => Some(String::from("subscript")),
+        "property_declaration" => {
+            let mut cursor = node.walk();
+            let computed = node
+                .children(&mut cursor)
+                .any(|c| c.kind() == "computed_property");
+            node.child_by_field_name("name")
+                .filter(|_| computed)
+                .map(|n| text(n).trim().to_string())
+        }
+        _ => None,
+    };
+    let mut cursor = node.walk();
+    let has_body = node
+        .children(&mut cursor)
+        .any(|c| matches!(c.kind(), "function_body" | "computed_property"));
+
+    if let Some(name) = name.filter(|_| has_body) {
+        methods.push(Method {
+            class_name: types.join("."),
+            method_name: name,
+            body: extract_body(node, source_code),
+            start_line: node.start_position().row + 1,
+            is_test: is_test || has_attribute(node, source_code, "Test"),
+            file_path: file_path.to_string(),
+        });
+        // Nested functions and closures belong to the member they are declared in
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_swift_members(child, source_code, file_path, is_test, types, methods);
+    }
+}
+
+/// Orders methods so that the members of each type are listed together, wherever
+/// their extensions are declared.
+pub fn merge_swift_extensions(methods: &mut [Method]) {
+    let mut first_seen: HashMap<String, usize> = HashMap::new();
+    for (i, method) in methods.iter().enumerate() {
+        first_seen.entry(method.class_name.clone()).or_insert(i);
+    }
+    methods.sort_by_key(|m| first_seen[&m.class_name]);
+}
+
+// A reference made in a test
+#[derive(PartialEq, Debug)]
+enum SwiftCall {
+    // `slugify("x")`
+    Function(String),
+    // `Cart(items: [])`
+    Construct(String),
+    // `cart.total`, `cart.add(1)`, `Cart.empty()` or `.empty()`, with the receiver's
+    // type if known
+    Member(Option<String>, String),
+}
+
+struct SwiftTest<'a> {
+    name: String,
+    blocks: Vec<tree_sitter::Node<'a>>,
+}
+
+// XCTest's `test*` methods in `XCTestCase` subclasses and Swift Testing's `@Test`
+// functions, in `@Suite` types or at the top level. `setUp` and the stored
+// properties of the type count as part of every test.
+fn collect_swift_tests<'a>(
+    node: tree_sitter::Node<'a>,
+    source_code: &str,
+    suite: Option<(&str, bool, &[tree_sitter::Node<'a>])>,
+    tests: &mut Vec<SwiftTest<'a>>,
+) {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+
+    if node.kind() == "class_declaration" {
+        let (Some(name), Some(body)) = (
+            swift_type_name(node, source_code),
+            node.child_by_field_name("body"),
+        ) else {
+            return;
+        };
+        let xctest = is_xctest_case(node, source_code);
+        if is_disabled(node, source_code) {
+            return;
+        }
+
+        let mut cursor = body.walk();
+        let members: Vec<tree_sitter::Node> = body.named_children(&mut cursor).collect();
+        let setup: Vec<tree_sitter::Node> = members
+            .iter()
+            .filter(|m| match m.kind() {
+                "property_declaration" | "init_declaration" => true,
+                "function_declaration" => m
+                    .child_by_field_name("name")
+                    .is_some_and(|n| XCTEST_HOOKS.contains(&text(n).as_str())),
+                _ => false,
+            })
+            .copied()
+            .collect();
+        for member in members {
+            collect_swift_tests(member, source_code, Some((&name, xctest, &setup)), tests);
+        }
+        return;
+    }
+
+    if node.kind() == "function_declaration" {
+        let (Some(name), Some(body)) = (
+            node.child_by_field_name("name").map(text),
+            node.children(&mut node.walk())
+                .find(|c| c.kind() == "function_body"),
+        ) else {
+            return;
+        };
+        let (suite_name, xctest, setup) = suite.unwrap_or(("", false, &[]));
+        let mut cursor = node.walk();
+        let takes_parameters = node.children(&mut cursor).any(|c| c.kind() == "parameter");
+        let is_test = (xctest && name.starts_with("test") && !takes_parameters)
+            || has_attribute(node, source_code, "Test");
+
+        if is_test && !is_disabled(node, source_code) && !skips_itself(body, source_code) {
+            let mut blocks = vec![body];
+            blocks.extend(setup.iter().copied());
+            tests.push(SwiftTest {
+                name: if suite_name.is_empty() {
+                    name
+                } else {
+                    format!("{}.{}", suite_name, name)
+                },
+                blocks,
+            });
+        }
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_swift_tests(child, source_code, suite, tests);
+    }
+}
+
+// The type of an expression such as `Cart(items: [])`, `Cart.empty()` or a typed
+// variable. A static call on a type is taken to return that type.
+fn swift_expression_type(
+    node: tree_sitter::Node,
+    source_code: &str,
+    variables: &HashMap<String, String>,
+) -> Option<String> {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+    match node.kind() {
+        "simple_identifier" => {
+            let name = text(node);
+            variables
+                .get(&name)
+                .cloned()
+                .or_else(|| name.starts_with(|c: char| c.is_uppercase()).then_some(name))
+        }
+        "call_expression" | "navigation_expression" => {
+            let target = node
+                .child_by_field_name("target")
+                .or_else(|| node.named_child(0))?;
+            swift_expression_type(target, source_code, variables)
+        }
+        _ => None,
+    }
+}
+
+// Records the types of stored properties and locals, declared or inferred from
+// their initial value, and of variables assigned in `setUp`
+fn collect_swift_variables(
+    node: tree_sitter::Node,
+    source_code: &str,
+    variables: &mut HashMap<String, String>,
+) {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+
+    match node.kind() {
+        "property_declaration" => {
+            let mut cursor = node.walk();
+            let declared = node
+                .children(&mut cursor)
+                .find(|c| c.kind() == "type_annotation")
+                .and_then(|a| swift_type_name(a, source_code));
+            let inferred = node
+                .child_by_field_name("value")
+                .and_then(|v| swift_expression_type(v, source_code, variables));
+            if let (Some(name), Some(class)) =
+                (node.child_by_field_name("name"), declared.or(inferred))
+            {
+                variables.insert(text(name).trim().to_string(), class);
+            }
+        }
+        "assignment" => {
+            let target = node
+                .child_by_field_name("target")
+                .map(|t| text(t).trim_start_matches("self.").to_string());
+            let value = node
+                .child_by_field_name("result")
+                .and_then(|v| swift_expression_type(v, source_code, variables));
+            if let (Some(target), Some(class)) = (target, value) {
+                variables.insert(target, class);
+            }
+        }
+        _ => {}
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_swift_variables(child, source_code, variables);
+    }
+}
+
+fn collect_swift_calls(
+    node: tree_sitter::Node,
+    source_code: &str,
+    variables: &HashMap<String, String>,
+    calls: &mut Vec<SwiftCall>,
+) {
+    let text = |n: tree_sitter::Node| source_code[n.start_byte()..n.end_byte()].to_string();
+
+    let call = match node.kind() {
+        // Method calls and property reads alike
+        "navigation_expression" => node
+            .child_by_field_name("suffix")
+            .and_then(|s| s.child_by_field_name("suffix"))
+            .map(|name| {
+                let receiver = node
+                    .child_by_field_name("target")
+                    .and_then(|t| swift_expression_type(t, source_code, variables));
+                SwiftCall::Member(receiver, text(name))
+            }),
+        "call_expression" => node
+            .named_child(0)
+            .filter(|f| f.kind() == "simple_identifier")
+            .map(|function| {
+                let name = text(function);
+                let is_subscript = node
+                    .named_child(1)
+                    .is_some_and(|suffix| text(suffix).starts_with('['));
+                if is_subscript {
+                    SwiftCall::Member(variables.get(&name).cloned(), String::from("subscript"))
+                } else if name.starts_with(|c: char| c.is_uppercase()) {
+                    SwiftCall::Construct(name)
+                } else {
+                    SwiftCall::Function(name)
+                }
+            })
+            .or_else(|| {
+                // An implicit member such as `.empty()`, whose type comes from context
+                let member = node
+                    .named_child(0)
+                    .filter(|f| f.kind() == "prefix_expression" && text(*f).starts_with('.'))?
+                    .child_by_field_name("target")?;
+                Some(SwiftCall::Member(None, text(member)))
+            }),
+        _ => None,
+    };
+
+    if let Some(call) = call {
+        if !calls.contains(&call) {
+            calls.push(call);
+        }
+    }
+
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        collect_swift_calls(child, source_code, variables, calls);
+    }
+}
+
+/// Resolves the calls and property reads made by XCTest and Swift Testing tests,
+/// using the declared or inferred types of the variables they are made on. Members
+/// used on receivers whose type can't be worked out match by name.
+pub fn extract_swift_tested_methods(repo: &str, logic_methods: &[Method]) -> TestedMethods {
+    let mut tested_methods = TestedMethods::default();
+    let candidates: Vec<&Method> = logic_methods.iter().filter(|m| !m.is_test).collect();
+    let simple_type = |m: &Method| m.class_name.rsplit('.').next().unwrap_or("").to_string();
+
+    for entry in WalkDir::new(repo)
+        .into_iter()
+        .filter_entry(|e| !should_skip_dir(e))
+        .filter_map(Result::ok)
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "swift"))
+    {
+        let Ok(source_code) = fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let mut parser = get_parser("swift");
+        let Some(tree) = parser.parse(&source_code, None) else {
+            continue;
+        };
+
+        let mut tests = Vec::new();
+        collect_swift_tests(tree.root_node(), &source_code, None, &mut tests);
+
+        for test in tests {
+            let mut variables = HashMap::new();
+            for block in test.blocks.iter().rev() {
+                collect_swift_variables(*block, &source_code, &mut variables);
+            }
+            let mut calls = Vec::new();
+            for block in &test.blocks {
+                collect_swift_calls(*block, &source_code, &variables, &mut calls);
+            }
+
+            for call in calls {
+                for method in candidates.iter().filter(|m| match &call {
+                    SwiftCall::Function(name) => m.class_name.is_empty() && m.method_name == *name,
+                    SwiftCall::Construct(class) => {
+                        m.method_name == "init" && simple_type(m) == *class
+                    }
+                    SwiftCall::Member(class, name) => {
+                        m.method_name == *name
+                            && !m.class_name.is_empty()
+                            && class.as_ref().is_none_or(|c| simple_type(m) == *c)
+                    }
+                }) {
+                    tested_methods.record(CallTarget::exact(method), &test.name);
+                }
+            }
+        }
+    }
+
+    tested_methods
+}
+
+#[test]
+fn test_traverse_swift_nodes() {
+    let source_code = r#"
+public struct Cart {
+    var items: [Int] = []
+    public init(items: [Int]) { self.items = items }
+    public mutating func add(_ price: Int) { items.append(price) }
+    public var total: Int { items.reduce(0, +) }
+    subscript(i: Int) -> Int { items[i] }
+}
+
+protocol Shape {
+    func area() -> Double
+}
+
+extension Shape {
+    func describe() -> String { "shape" }
+}
+
+func slugify(_ s: String) -> String { s.lowercased() }
+
+extension Cart {
+    func describe() -> String { "\(total)" }
+    struct Line { func render() {} }
+}
+"#;
+    let mut parser = get_parser("swift");
+    let tree = parser.parse(source_code, None).unwrap();
+    let mut methods = Vec::new();
+    traverse_swift_nodes(
+        tree.root_node(),
+        source_code,
+        "Sources/Shop/Cart.swift",
+        false,
+        &mut methods,
+    );
+    merge_swift_extensions(&mut methods);
+
+    let found: Vec<(&str, &str)> = methods
+        .iter()
+        .map(|m| (m.class_name.as_str(), m.method_name.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("Cart", "init"),
+            ("Cart", "add"),
+            ("Cart", "total"),
+            ("Cart", "subscript"),
+            ("Cart", "describe"),
+            ("Shape", "describe"),
+            ("", "slugify"),
+            ("Cart.Line", "render"),
+        ]
+    );
+    assert!(is_swift_test_file(
+        ".",
+        Path::new("Tests/ShopTests/CartTests.swift")
+    ));
+    assert!(!is_swift_test_file(
+        ".",
+        Path::new("Sources/Shop/Cart.swift")
+    ));
+    // A package checked out under another project's IntegrationTests
+    assert!(!is_swift_test_file(
+        "/work/IntegrationTests/Shop",
+        Path::new("/work/IntegrationTests/Shop/Sources/Shop/Cart.swift")
+    ));
+}
+
+#[test]
+fn test_extract_swift_tested_methods() {
+    use std::fs::create_dir_all;
+    use tempfile::tempdir;
+
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    // Nested in an IntegrationTests directory, which mustn't make the sources tests
+    let root = temp_dir.path().join("IntegrationTests/Shop");
+    create_dir_all(root.join("Sources/Shop")).unwrap();
+    create_dir_all(root.join("Tests/ShopTests")).unwrap();
+    fs::write(
+        root.join("Sources/Shop/Cart.swift"),
+        r#"public struct Cart {
+    var items: [Int]
+    public init(items: [Int]) { self.items = items }
+    public mutating func add(_ price: Int) { items.append(price) }
+    public var total: Int { items.reduce(0, +) }
+    static func empty() -> Cart { Cart(items: []) }
+}
+
+extension Cart {
+    func describe() -> String { "\(total)" }
+}
+
+struct Order {
+    var total: Int { 0 }
+}
+
+func slugify(_ s: String) -> String { s.lowercased() }
+"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("Tests/ShopTests/CartTests.swift"),
+        r#"import XCTest
+@testable import Shop
+
+final class CartTests: XCTestCase {
+    var cart: Cart!
+
+    override func setUp() {
+        cart = Cart(items: [1, 2])
+    }
+
+    func testTotal() {
+        XCTAssertEqual(cart.total, 3)
+    }
+
+    func testAdd() throws {
+        var c = Cart.empty()
+        c.add(1)
+    }
+}
+
+@Suite struct CartMathTests {
+    let cart = Cart(items: [1])
+
+    @Test("describes") func describes() {
+        #expect(cart.describe() == "1")
+    }
+
+    @Test(.disabled("later")) func later() { _ = slugify("x") }
+}
+"#,
+    )
+    .unwrap();
+
+    let mut methods = Vec::new();
+    for file in ["Sources/Shop/Cart.swift", "Tests/ShopTests/CartTests.swift"] {
+        let path = root.join(file);
+        let source_code = fs::read_to_string(&path).unwrap();
+        let mut parser = get_parser("swift");
+        let tree = parser.parse(&source_code, None).unwrap();
+        traverse_swift_nodes(
+            tree.root_node(),
+            &source_code,
+            &path.to_string_lossy(),
+            is_swift_test_file(&root.to_string_lossy(), &path),
+            &mut methods,
+        );
+    }
+
+    let tested = extract_swift_tested_methods(&root.to_string_lossy(), &methods);
+    let tests_for = |class: &str, name: &str| {
+        let method = methods
+            .iter()
+            .find(|m| m.class_name == class && m.method_name == name)
+            .unwrap();
+        tested.tests_for(method)
+    };
+
+    assert_eq!(tests_for("Cart", "total"), vec!["CartTests.testTotal"]);
+    assert!(tests_for("Order", "total").is_empty());
+    assert_eq!(tests_for("Cart", "add"), vec!["CartTests.testAdd"]);
+    assert_eq!(tests_for("Cart", "empty"), vec!["CartTests.testAdd"]);
+    assert_eq!(
+        tests_for("Cart", "describe"),
+        vec!["CartMathTests.describes"]
+    );
+    assert!(tests_for("", "slugify").is_empty());
+}
+
+#[test]
+fn test_is_swift_test_file() {
+    // Tests of a package nested inside another project's IntegrationTests
+    let repo = "/work/IntegrationTests/Shop";
+    assert!(is_swift_test_file(
+        repo,
+        Path::new("/work/IntegrationTests/Shop/Tests/ShopTests/CartTests.swift")
+    ));
+    assert!(!is_swift_test_file(
+        &format!("{}/", repo),
+        Path::new("/work/IntegrationTests/Shop/Sources/Shop/Cart.swift")
+    ));
+    // Files outside the repo root are judged on their whole path
+    assert!(is_swift_test_file(
+        repo,
+        Path::new("/work/AppTests/Helpers.swift")
+    ));
+    assert!(!is_swift_test_file(
+        repo,
+        Path::new("/work/Packages/Sources/Util.swift")
+    ));
+}
+
+#[test]
+fn test_swift_disabled_and_overloaded_tests() {
+    use tempfile::tempdir;
+
+    let temp_dir = tempdir().expect("Failed to create temp dir");
+    let root = temp_dir.path();
+    fs::write(
+        root.join("Cart.swift"),
+        r#"struct Cart {
+    func add(_ price: Int) { }
+    func add(_ price: Double) { }
+    func add(item: String) { }
+    func clear() { }
+}
+"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("CartTests.swift"),
+        r#"final class CartTests: XCTestCase {
+    func testAdd() { Cart().add(1) }
+
+    func testClear() throws {
+        throw XCTSkip("flaky")
+        Cart().clear()
+    }
+
+    func testClearLocally() throws {
+        if ProcessInfo.processInfo.environment["CI"] != nil {
+            throw XCTSkip("flaky on CI")
+        }
+        Cart().clear()
+    }
+}
+
+@Suite(.disabled("later")) struct LaterTests {
+    @Test func clears() { Cart().clear() }
+}
+
+struct CartSuite {
+    @Test(.disabled()) func clears() { Cart().clear() }
+}
+"#,
+    )
+    .unwrap();
+
+    let mut methods = Vec::new();
+    let path = root.join("Cart.swift");
+    let source_code = fs::read_to_string(&path).unwrap();
+    let tree = get_parser("swift").parse(&source_code, None).unwrap();
+    traverse_swift_nodes(
+        tree.root_node(),
+        &source_code,
+        &path.to_string_lossy(),
+        false,
+        &mut methods,
+    );
+
+    // Overloads aren't told apart by their arguments or labels, so a call covers
+    // each of them
+    let tested = extract_swift_tested_methods(&root.to_string_lossy(), &methods);
+    let tests_for = |name: &str| -> Vec<Vec<String>> {
+        methods
+            .iter()
+            .filter(|m| m.method_name == name)
+            .map(|m| tested.tests_for(m))
+            .collect()
+    };
+    assert_eq!(tests_for("add"), vec![vec!["CartTests.testAdd"]; 3]);
+    assert_eq!(tests_for("clear"), vec![vec!["CartTests.testClearLocally"]]);
+}
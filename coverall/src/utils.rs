@@ -1,22 +1,73 @@
-use std::collections::HashSet;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
 use tree_sitter::Parser;
 use tree_sitter_c_sharp;
+use tree_sitter_go;
+use tree_sitter_java;
 use tree_sitter_javascript;
 use tree_sitter_python;
 use tree_sitter_rust;
+use tree_sitter_typescript;
 
-#[derive(Debug)]
+use crate::badge::Badge;
+use crate::querylang::QueryLanguage;
+use crate::report::Baseline;
+
+#[derive(Debug, Default)]
 pub struct Command {
     pub repo: String,
     pub lang: Lang,
+    pub include_generated: bool,
+    pub show_tests: bool,
+    pub format: ReportFormat,
+    // Whether LCOV output has line records as well as function records
+    pub lcov_lines: bool,
+    // Where the report is written instead of stdout; the directory of an HTML report
+    pub output: Option<String>,
+    // An earlier run's coverage, for the Markdown summary to compare against
+    pub baseline: Option<Baseline>,
+    // Levels overriding the defaults of SARIF rules, as (rule, level) pairs
+    pub sarif_levels: Vec<(String, String)>,
+    // The repo-relative files changed since --changed-since, the only ones annotated
+    pub changed_files: Option<HashSet<String>>,
+    // The look of the badge the badge subcommand writes to `output`
+    pub badge: Badge,
+    // Where languages are defined, read for candidates when detecting the language
+    pub config_dir: Option<PathBuf>,
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Default, Clone, Copy)]
+pub enum ReportFormat {
+    #[default]
+    Text,
+    Cobertura,
+    Lcov,
+    Html,
+    Markdown,
+    Sarif,
+    Github,
+    Annotations,
+    Badge,
+}
+
+#[derive(PartialEq, Debug, Default)]
 pub enum Lang {
     Csharp,
     Python,
     JS,
+    TypeScript,
+    Go,
+    Java,
+    Kotlin,
+    Cpp,
+    Ruby,
+    Php,
+    Swift,
     Rust,
+    /// A language defined by tree-sitter queries in the config directory.
+    Query(Box<QueryLanguage>),
+    #[default]
     Undefined,
 }
 
@@ -25,7 +76,16 @@ pub struct Method {
     pub class_name: String,
     pub method_name: String,
     pub body: Vec<String>,
+    // 1-based line the method starts on; it spans the lines of its body
+    pub start_line: usize,
     pub is_test: bool,
+    pub file_path: String,
+}
+
+impl Method {
+    pub fn end_line(&self) -> usize {
+        self.start_line + self.body.len().max(1) - 1
+    }
 }
 
 #[derive(Debug)]
@@ -35,14 +95,144 @@ pub struct AnalysisData {
     pub tested_methods: HashSet<String>,
 }
 
+/// Something a test calls, used to decide which logic methods are covered.
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+pub enum CallTarget {
+    /// Any logic method with this name.
+    Name(String),
+    /// Exactly one logic method, identified by file, class and method name.
+    Exact(String, String, String),
+    /// Any logic method with this name declared in the given (normalized) file.
+    InFile(String, String),
+}
+
+impl CallTarget {
+    pub fn exact(method: &Method) -> CallTarget {
+        CallTarget::Exact(
+            method.file_path.clone(),
+            method.class_name.clone(),
+            method.method_name.clone(),
+        )
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum CoverageStatus {
+    Covered,
+    // Only reached through a call on an interface the method's class implements
+    ViaInterface,
+    Uncovered,
+}
+
+// Each target maps to the names of the tests calling it
+#[derive(Debug, Default)]
+pub struct TestedMethods {
+    pub targets: HashMap<CallTarget, Vec<String>>,
+    pub via_interface: HashMap<CallTarget, Vec<String>>,
+}
+
+impl TestedMethods {
+    pub fn record(&mut self, target: CallTarget, test_name: &str) {
+        record_test(&mut self.targets, target, test_name);
+    }
+
+    pub fn record_via_interface(&mut self, target: CallTarget, test_name: &str) {
+        record_test(&mut self.via_interface, target, test_name);
+    }
+
+    pub fn extend(&mut self, other: TestedMethods) {
+        for (target, tests) in other.targets {
+            for test_name in tests {
+                self.record(target.clone(), &test_name);
+            }
+        }
+        for (target, tests) in other.via_interface {
+            for test_name in tests {
+                self.record_via_interface(target.clone(), &test_name);
+            }
+        }
+    }
+
+    pub fn status(&self, method: &Method) -> CoverageStatus {
+        if !tests_for_method(&self.targets, method).is_empty() {
+            CoverageStatus::Covered
+        } else if !tests_for_method(&self.via_interface, method).is_empty() {
+            CoverageStatus::ViaInterface
+        } else {
+            CoverageStatus::Uncovered
+        }
+    }
+
+    pub fn covers(&self, method: &Method) -> bool {
+        self.status(method) == CoverageStatus::Covered
+    }
+
+    /// Names of the tests that cover the method, directly or via an interface.
+    pub fn tests_for(&self, method: &Method) -> Vec<String> {
+        let mut tests = tests_for_method(&self.targets, method);
+        if tests.is_empty() {
+            tests = tests_for_method(&self.via_interface, method);
+        }
+        tests
+    }
+}
+
+fn record_test(targets: &mut HashMap<CallTarget, Vec<String>>, target: CallTarget, test: &str) {
+    let tests = targets.entry(target).or_default();
+    if !tests.iter().any(|t| t == test) {
+        tests.push(test.to_string());
+    }
+}
+
+fn tests_for_method(targets: &HashMap<CallTarget, Vec<String>>, method: &Method) -> Vec<String> {
+    let mut tests = Vec::new();
+    for target in [
+        CallTarget::Name(method.method_name.clone()),
+        CallTarget::exact(method),
+        CallTarget::InFile(
+            normalize_path(Path::new(&method.file_path))
+                .to_string_lossy()
+                .to_string(),
+            method.method_name.clone(),
+        ),
+    ] {
+        for test in targets.get(&target).into_iter().flatten() {
+            if !tests.contains(test) {
+                tests.push(test.clone());
+            }
+        }
+    }
+    tests
+}
+
 #[derive(PartialEq, Debug)]
 pub struct LangSettings {
     pub ext: String,
+    // Every file extension read for the language, `ext` included
+    pub extensions: Vec<String>,
     pub uses_classes: bool,
     pub test_pattern: String,
     pub test_method_start: String,
 }
 
+impl LangSettings {
+    pub fn is_source_file(&self, path: &Path) -> bool {
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        // TypeScript declaration files only describe types
+        if [".d.ts", ".d.mts", ".d.cts"]
+            .iter()
+            .any(|suffix| file_name.ends_with(suffix))
+        {
+            return false;
+        }
+        path.extension()
+            .is_some_and(|ext| self.extensions.iter().any(|e| *ext == **e))
+    }
+}
+
 pub fn normalize_line(line: &str) -> String {
     line.replace(" ", "").replace("\t", "")
 }
@@ -56,12 +246,43 @@ pub fn get_parser(lang: &str) -> Parser {
         "py" => parser
             .set_language(&tree_sitter_python::LANGUAGE.into())
             .unwrap(),
-        "js" => parser
+        "js" | "mjs" | "cjs" | "jsx" => parser
             .set_language(&tree_sitter_javascript::LANGUAGE.into())
             .unwrap(),
         "cs" => parser
             .set_language(&tree_sitter_c_sharp::LANGUAGE.into())
             .unwrap(),
+        "go" => parser
+            .set_language(&tree_sitter_go::LANGUAGE.into())
+            .unwrap(),
+        "java" => parser
+            .set_language(&tree_sitter_java::LANGUAGE.into())
+            .unwrap(),
+        "ts" | "mts" | "cts" => parser
+            .set_language(&tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into())
+            .unwrap(),
+        "c" => parser
+            .set_language(&tree_sitter_c::LANGUAGE.into())
+            .unwrap(),
+        // C headers are parsed as C++, since they're usually shared with C++ code
+        "h" | "cc" | "cpp" | "cxx" | "c++" | "hh" | "hpp" | "hxx" => parser
+            .set_language(&tree_sitter_cpp::LANGUAGE.into())
+            .unwrap(),
+        "rb" => parser
+            .set_language(&tree_sitter_ruby::LANGUAGE.into())
+            .unwrap(),
+        "php" => parser
+            .set_language(&tree_sitter_php::LANGUAGE_PHP.into())
+            .unwrap(),
+        "swift" => parser
+            .set_language(&tree_sitter_swift::LANGUAGE.into())
+            .unwrap(),
+        "kt" | "kts" => parser
+            .set_language(&tree_sitter_kotlin_ng::LANGUAGE.into())
+            .unwrap(),
+        "tsx" => parser
+            .set_language(&tree_sitter_typescript::LANGUAGE_TSX.into())
+            .unwrap(),
         _ => panic!("Unsupported language: {}", lang),
     }
     parser
@@ -75,6 +296,98 @@ pub fn extract_body(node: tree_sitter::Node, source: &str) -> Vec<String> {
         .collect()
 }
 
+/// Lexically resolves `.` and `..` components so paths built from different
+/// starting points can be compared.
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push("..");
+                }
+            }
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    normalized
+}
+
+/// A file's path within the repo, so the directories the repo is checked out under
+/// don't decide what kind of file it is. Paths outside the repo are kept whole.
+pub fn repo_relative_path(repo: &str, path: &Path) -> PathBuf {
+    let path = normalize_path(path);
+    match path.strip_prefix(normalize_path(Path::new(repo))) {
+        Ok(relative) => relative.to_path_buf(),
+        Err(_) => path,
+    }
+}
+
+/// Converts a glob (with `**`, `{a,b}`, `[...]` and the `?(...)`, `+(...)`, `*(...)`,
+/// `@(...)` extglobs used by Jest) into an anchored regex over `/`-separated paths.
+pub fn glob_to_regex(glob: &str) -> Option<Regex> {
+    let chars: Vec<char> = glob.chars().collect();
+    let mut pattern = String::from("^");
+    // Closing text for each open extglob or brace group
+    let mut groups: Vec<&str> = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            '*' if next == Some('*') => {
+                if chars.get(i + 2) == Some(&'/') {
+                    pattern.push_str("(?:.*/)?");
+                    i += 2;
+                } else {
+                    pattern.push_str(".*");
+                    i += 1;
+                }
+            }
+            '?' | '+' | '*' | '@' if next == Some('(') => {
+                pattern.push_str("(?:");
+                groups.push(match c {
+                    '?' => ")?",
+                    '+' => ")+",
+                    '*' => ")*",
+                    _ => ")",
+                });
+                i += 1;
+            }
+            '!' if next == Some('(') => return None,
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            '{' => {
+                pattern.push_str("(?:");
+                groups.push(")");
+            }
+            ')' | '}' if !groups.is_empty() => pattern.push_str(groups.pop().unwrap()),
+            '|' | ',' if !groups.is_empty() => pattern.push('|'),
+            '[' => {
+                let end = chars[i + 1..].iter().position(|&ch| ch == ']')? + i + 1;
+                let class: String = chars[i + 1..end].iter().collect();
+                let class = class
+                    .strip_prefix('!')
+                    .map_or(class.clone(), |rest| format!("^{}", rest));
+                pattern.push('[');
+                pattern.push_str(&class);
+                pattern.push(']');
+                i = end;
+            }
+            _ => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+        i += 1;
+    }
+
+    if !groups.is_empty() {
+        return None;
+    }
+    pattern.push('$');
+    Regex::new(&pattern).ok()
+}
+
 pub fn should_skip_dir(entry: &walkdir::DirEntry) -> bool {
     let excluded_dirs: HashSet<&str> = ["node_modules"].iter().cloned().collect();
 
@@ -83,6 +396,34 @@ pub fn should_skip_dir(entry: &walkdir::DirEntry) -> bool {
     excluded_dirs.contains(dir_name.as_ref())
 }
 
+#[test]
+fn test_glob_to_regex() {
+    let jest_default = glob_to_regex("**/?(*.)+(spec|test).[jt]s?(x)").unwrap();
+    assert!(jest_default.is_match("src/parser.test.js"));
+    assert!(jest_default.is_match("parser.spec.tsx"));
+    assert!(jest_default.is_match("test.js"));
+    assert!(!jest_default.is_match("src/parser.js"));
+
+    let tests_dir = glob_to_regex("**/__tests__/**/*.{js,jsx}").unwrap();
+    assert!(tests_dir.is_match("__tests__/parser.js"));
+    assert!(tests_dir.is_match("src/__tests__/unit/parser.jsx"));
+    assert!(!tests_dir.is_match("src/parser.js"));
+
+    assert!(glob_to_regex("src/!(vendor)/*.js").is_none());
+}
+
+#[test]
+fn test_normalize_path() {
+    assert_eq!(
+        normalize_path(Path::new("./repo/tests/../src/./lib.cs")),
+        PathBuf::from("repo/src/lib.cs")
+    );
+    assert_eq!(
+        normalize_path(Path::new("/repo/a/b/../../c")),
+        PathBuf::from("/repo/c")
+    );
+}
+
 #[test]
 fn test_normalize_line() {
     // Test case 1: Line with spaces and tabs